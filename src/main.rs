@@ -2,20 +2,38 @@ mod raytracer;
 
 use raytracer::camera::Camera;
 use raytracer::light::Light;
-use raytracer::material::{Color, Material};
+use raytracer::material::{Color, Fresnel, Material};
 use raytracer::mesh::Triangle;
-use raytracer::raytracer::RayTracer;
+use raytracer::raytracer::{Integrator, RayTracer};
+use raytracer::scene::Scene;
+use raytracer::scene_file::load_scene;
+use raytracer::scene_graph::SceneGraph;
 use raytracer::sphere::Sphere;
+use raytracer::transform::Transform;
 use raytracer::vector::Vec3;
 
 use crate::raytracer::image::ACESFilmic;
 use crate::raytracer::vector::Float;
 use std::f32::consts::PI;
-use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::thread;
 
 fn main() {
+    // `cargo run --release -- scene.txt [output.png]` renders a scene file instead,
+    // `cargo run --release -- --furnace scene.txt` checks its materials for energy gain
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("--furnace") {
+        let Some(scene_path) = args.get(2) else {
+            eprintln!("Usage: --furnace <scene file>");
+            std::process::exit(2);
+        };
+        furnace_scene_file(scene_path);
+        return;
+    }
+    if let Some(scene_path) = args.get(1) {
+        let filename = args.get(2).map_or("output/scene.png", String::as_str);
+        render_scene_file(scene_path, filename);
+        return;
+    }
+
     let fps: Float = 60.0;
     let frames: usize = fps as usize * 8;
 
@@ -24,55 +42,12 @@ fn main() {
         .map(|n| n.get())
         .unwrap_or(1);
 
-    // Limit to at most 4 concurrent threads
-    let max_concurrent_threads = std::cmp::min(16, num_cores);
-
+    // Each frame is split into tiles rendered across all cores
     println!(
-        "Starting multi-threaded rendering with {} available CPU cores",
+        "Starting tile-based rendering with {} available CPU cores",
         num_cores
     );
-    println!(
-        "Limiting to {} concurrent rendering threads",
-        max_concurrent_threads
-    );
-
-    let mut handles: Vec<(usize, thread::JoinHandle<()>, Arc<AtomicBool>)> = vec![];
-    let mut next_frame = 0;
-
-    while next_frame < frames || !handles.is_empty() {
-        // Scan all handles and remove finished threads
-        handles.retain_mut(|(frame_idx, _handle, is_done)| {
-            if is_done.load(Ordering::Relaxed) {
-                println!("Frame {} completed", frame_idx);
-                false // Remove this handle
-            } else {
-                true // Keep this handle
-            }
-        });
-
-        // If we have capacity and frames left to render, spawn a new thread
-        if next_frame < frames && handles.len() < max_concurrent_threads {
-            let f = next_frame;
-            let is_done = Arc::new(AtomicBool::new(false));
-            let is_done_clone = Arc::clone(&is_done);
-
-            let handle = thread::spawn(move || {
-                let time = f as Float / fps;
-                frame(time, &format!("output/frame_{:03}.png", f));
-                is_done_clone.store(true, Ordering::Relaxed);
-            });
-            handles.push((f, handle, is_done));
-            next_frame += 1;
-        } else if !handles.is_empty() {
-            // If no capacity and frames remain, wait a tiny bit before checking again
-            std::thread::sleep(std::time::Duration::from_millis(10));
-        }
-    }
-
-    println!("All frames rendered!");
-}
 
-fn frame(time: Float, filename: &str) {
     // === CAMERA SETUP ===
     let camera = Camera::new(
         Vec3::new(0.0, -3.0, 3.0), // eye position
@@ -84,7 +59,27 @@ fn frame(time: Float, filename: &str) {
         4,                         // subdivisions for anti-aliasing
     );
 
-    // === SCENE ===
+    // The scene graph is built once; each frame only moves the mirror ball's node
+    let mut graph = build_scene_graph();
+
+    for f in 0..frames {
+        let time = f as Float / fps;
+        let x = (time * PI / 2.0).sin() * 1.5;
+        graph
+            .find_mut("mirror_ball")
+            .expect("scene has a mirror ball")
+            .transform = Transform::translation(Vec3::new(x, 0.0, 0.0));
+
+        frame(&camera, &graph, &format!("output/frame_{:03}.png", f));
+        println!("Frame {} completed", f);
+    }
+
+    println!("All frames rendered!");
+}
+
+/// Build the demo scene: glass spheres and a moving mirror ball on a matte floor.
+fn build_scene_graph() -> SceneGraph {
+    // === MATERIALS ===
     let mirror = Material::mirror(Color::new(0.05, 0.05, 0.05), 0.9);
     let red_glass = Material::new(
         Color::new(0.3, 0.0, 0.0),
@@ -93,7 +88,8 @@ fn frame(time: Float, filename: &str) {
         0.9,
         1.5,
         Color::new(0.0, 1.5, 1.5),
-    );
+    )
+    .with_fresnel(Fresnel::Exact);
     let green_glass = Material::new(
         Color::new(0.0, 0.3, 0.0),
         0.0,
@@ -101,7 +97,8 @@ fn frame(time: Float, filename: &str) {
         0.9,
         1.5,
         Color::new(1.5, 0.0, 1.5),
-    );
+    )
+    .with_fresnel(Fresnel::Exact);
     let blue_glass = Material::new(
         Color::new(0.0, 0.0, 0.3),
         0.0,
@@ -109,7 +106,8 @@ fn frame(time: Float, filename: &str) {
         0.9,
         1.5,
         Color::new(1.5, 1.5, 0.0),
-    );
+    )
+    .with_fresnel(Fresnel::Exact);
     let yellow_matte = Material::new(
         Color::new(1.0, 1.0, 1.0),
         0.2,
@@ -119,12 +117,20 @@ fn frame(time: Float, filename: &str) {
         Color::new(0.0, 0.0, 0.0),
     );
 
-    let x = (time * PI / 2.0).sin() * 1.5;
+    let mut graph = SceneGraph::new();
+    let root = graph.root();
+
+    // === OBJECTS ===
+    let mirror_ball = graph.add_node(root, "mirror_ball", Transform::identity());
+    graph
+        .node_mut(mirror_ball)
+        .add_surface(Sphere::new(Vec3::new(0.0, 1.5, 0.7), 0.7, mirror));
 
-    let sphere1 = Sphere::new(Vec3::new(x, 1.5, 0.7), 0.7, mirror);
-    let sphere2 = Sphere::new(Vec3::new(0.0, 0.0, 0.5), 0.5, red_glass);
-    let sphere3 = Sphere::new(Vec3::new(-1.2, 0.0, 0.5), 0.5, blue_glass);
-    let sphere4 = Sphere::new(Vec3::new(1.2, 0.0, 0.5), 0.5, green_glass);
+    let glass_balls = graph.add_node(root, "glass_balls", Transform::identity());
+    let glass = graph.node_mut(glass_balls);
+    glass.add_surface(Sphere::new(Vec3::new(0.0, 0.0, 0.5), 0.5, red_glass));
+    glass.add_surface(Sphere::new(Vec3::new(-1.2, 0.0, 0.5), 0.5, blue_glass));
+    glass.add_surface(Sphere::new(Vec3::new(1.2, 0.0, 0.5), 0.5, green_glass));
 
     let triangle1 = Triangle::new(
         Vec3::new(3.0, 3.0, 0.0),
@@ -139,34 +145,61 @@ fn frame(time: Float, filename: &str) {
         yellow_matte,
     );
 
-    // Use trait objects to store mixed geometry types
-    let surfaces: Vec<&dyn raytracer::Surface> = vec![
-        &sphere1, &sphere2, &sphere3, &sphere4, &triangle1, &triangle2,
-    ];
+    let floor = graph.add_node(root, "floor", Transform::identity());
+    graph.node_mut(floor).add_surface(triangle1);
+    graph.node_mut(floor).add_surface(triangle2);
 
     // === LIGHTING SETUP ===
-    let light1 = Light::new(Vec3::new(3.0, -3.0, 5.0), 3.0, Color::new(1.0, 1.0, 1.0));
-    let light2 = Light::new(Vec3::new(0.0, 0.0, 10.0), 2.0, Color::new(1.0, 1.0, 1.0));
-    let light3 = Light::new(Vec3::new(-10.0, -5.0, 5.0), 2.0, Color::new(1.0, 1.0, 1.0)); // Top light
+    // Lights are specified by total emitted power (watts)
+    let white = Color::new(1.0, 1.0, 1.0);
+    let lights = graph.add_node(root, "lights", Transform::identity());
+    let lights = graph.node_mut(lights);
+    lights.add_light(Light::with_power(
+        Vec3::new(3.0, -3.0, 5.0),
+        3.0,
+        white,
+        1800.0,
+    ));
+    lights.add_light(Light::with_power(
+        Vec3::new(0.0, 0.0, 10.0),
+        2.0,
+        white,
+        4000.0,
+    ));
+    lights.add_light(Light::with_power(
+        Vec3::new(-10.0, -5.0, 5.0),
+        2.0,
+        white,
+        5700.0,
+    )); // Top light
 
-    let lights = [light1, light2, light3];
+    graph
+}
 
+fn frame(camera: &Camera, graph: &SceneGraph, filename: &str) {
     // === RAYTRACER SETUP ===
     let vacuum = Material::new(Color::black(), 0.0, 0.0, 1.0, 1.0, Color::black());
-    let raytracer = RayTracer::new(
+    let mut raytracer = RayTracer::new(
         Color::new(0.0, 0.0, 0.0), // background color (darker blue)
         16,                        // max depth
         1e-3,                      // min weight
         vacuum,
     );
+    raytracer.light_samples = 16; // soft shadows from the sphere lights
+
+    // Flatten the scene graph into a scene owning the world-space surfaces and lights
+    let mut scene = Scene::new(raytracer);
+    scene.add_camera(camera.clone());
+    graph.add_to(&mut scene);
 
     // === RENDERING ===
+    let scene = scene.build();
     println!(
         "Rendering scene with {} surfaces and {} lights...",
-        surfaces.len(),
-        lights.len()
+        scene.surface_count(),
+        scene.lights().len()
     );
-    let image = raytracer.render(&camera, &surfaces[..], &lights);
+    let image = scene.render(0);
     println!("Render complete!");
 
     // === TONE MAPPING ===
@@ -193,6 +226,71 @@ fn frame(time: Float, filename: &str) {
     println!("Rendering complete. Image saved to {}", filename);
 }
 
+/// Run the white furnace test on every named material of a scene file with both
+/// integrators, and exit with an error if any material reflects more light than it receives.
+fn furnace_scene_file(path: &str) {
+    let scene = match load_scene(path) {
+        Ok(scene) => scene,
+        Err(error) => {
+            eprintln!("Failed to load scene: {}", error);
+            std::process::exit(1);
+        }
+    };
+
+    let mut names: Vec<&str> = scene.materials.keys().map(String::as_str).collect();
+    names.sort_unstable();
+    let mut failed = false;
+    for integrator in [Integrator::Branching, Integrator::PathTracing] {
+        let tracer = RayTracer {
+            integrator,
+            ..scene.raytracer
+        };
+        let materials = names.iter().map(|&name| (name, scene.materials[name]));
+        let flagged = tracer.white_furnace(materials, 4096);
+        println!(
+            "{:?}: {} of {} materials gain energy",
+            integrator,
+            flagged.len(),
+            names.len()
+        );
+        for (name, result) in &flagged {
+            println!(
+                "  {}: returns {:.3} ± {:.3} of the incoming light at cos θ = {:.2}",
+                name, result.albedo, result.error, result.cos_theta
+            );
+        }
+        failed |= !flagged.is_empty();
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+/// Render a scene description file to a PNG file.
+fn render_scene_file(path: &str, filename: &str) {
+    let scene = match load_scene(path) {
+        Ok(scene) => scene,
+        Err(error) => {
+            eprintln!("Failed to load scene: {}", error);
+            std::process::exit(1);
+        }
+    };
+
+    let scene = scene.build();
+    println!(
+        "Rendering {} with {} surfaces and {} lights...",
+        path,
+        scene.surface_count(),
+        scene.lights().len()
+    );
+    let image = scene.render(0);
+
+    let rgb8_data = image.convert(&ACESFilmic::new());
+    save_image_to_file(&rgb8_data, image.width, image.height, filename)
+        .expect("Failed to save image");
+}
+
 /// Convert RGB8 pixel data to an image and save to a PNG file
 fn save_image_to_file(
     rgb8_data: &[(u8, u8, u8)],
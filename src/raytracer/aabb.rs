@@ -0,0 +1,146 @@
+//! Axis-aligned bounding boxes for spatial queries over surfaces.
+
+use super::Ray;
+use super::vector::{Float, Vec3};
+
+/// Conservative scale for the slab test's exit distance.
+/// Compensates floating point rounding so that primitives touching a box face are never culled.
+const SLAB_TOLERANCE: Float = 1.0 + 2.0 * 3.0 * Float::EPSILON;
+
+/// An axis-aligned bounding box defined by its minimum and maximum corners.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Aabb {
+    /// Corner with the smallest coordinates
+    pub min: Vec3,
+    /// Corner with the largest coordinates
+    pub max: Vec3,
+}
+
+impl Aabb {
+    /// Create a new bounding box from its minimum and maximum corners.
+    pub fn new(min: Vec3, max: Vec3) -> Self {
+        Self { min, max }
+    }
+
+    /// Empty bounding box (the identity element for `union`).
+    pub fn empty() -> Self {
+        Self::new(
+            Vec3::new(Float::INFINITY, Float::INFINITY, Float::INFINITY),
+            Vec3::new(-Float::INFINITY, -Float::INFINITY, -Float::INFINITY),
+        )
+    }
+
+    /// Smallest bounding box enclosing both boxes.
+    pub fn union(self, other: Self) -> Self {
+        Self::new(
+            Vec3::new(
+                self.min.x.min(other.min.x),
+                self.min.y.min(other.min.y),
+                self.min.z.min(other.min.z),
+            ),
+            Vec3::new(
+                self.max.x.max(other.max.x),
+                self.max.y.max(other.max.y),
+                self.max.z.max(other.max.z),
+            ),
+        )
+    }
+
+    /// Smallest bounding box enclosing this box and a point.
+    pub fn union_point(self, point: Vec3) -> Self {
+        self.union(Self::new(point, point))
+    }
+
+    /// Size of the box along each axis.
+    pub fn extent(&self) -> Vec3 {
+        self.max - self.min
+    }
+
+    /// Center point of the box.
+    pub fn centroid(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    /// Total area of the six faces. Returns 0.0 for an empty box.
+    pub fn surface_area(&self) -> Float {
+        let d = self.extent();
+        if d.x < 0.0 || d.y < 0.0 || d.z < 0.0 {
+            0.0
+        } else {
+            2.0 * (d.x * d.y + d.y * d.z + d.z * d.x)
+        }
+    }
+
+    /// Index of the axis along which the box is longest (0 = x, 1 = y, 2 = z).
+    pub fn longest_axis(&self) -> usize {
+        let d = self.extent();
+        if d.x >= d.y && d.x >= d.z {
+            0
+        } else if d.y >= d.z {
+            1
+        } else {
+            2
+        }
+    }
+
+    /// Ray-box slab test.
+    /// Returns the distance at which the ray enters the box if it overlaps the range [0, t_max].
+    ///
+    /// # Arguments
+    /// * `ray` - The ray to test
+    /// * `inv_direction` - Component-wise reciprocal of the ray direction
+    /// * `t_max` - Farthest distance of interest along the ray
+    pub fn hit(&self, ray: &Ray, inv_direction: Vec3, t_max: Float) -> Option<Float> {
+        let mut t_near: Float = 0.0;
+        let mut t_far = t_max;
+
+        for axis in 0..3 {
+            let t0 = (self.min[axis] - ray.origin[axis]) * inv_direction[axis];
+            let t1 = (self.max[axis] - ray.origin[axis]) * inv_direction[axis];
+            let (t0, t1) = if t0 > t1 { (t1, t0) } else { (t0, t1) };
+
+            // `max`/`min` ignore NaN (0 * inf) so rays parallel to a face are not rejected
+            t_near = t_near.max(t0);
+            t_far = t_far.min(t1 * SLAB_TOLERANCE);
+
+            if t_near > t_far {
+                return None;
+            }
+        }
+
+        Some(t_near)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aabb_union_and_area() {
+        let a = Aabb::new(Vec3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 1.0, 1.0));
+        let b = Aabb::new(Vec3::new(2.0, 0.0, 0.0), Vec3::new(3.0, 1.0, 1.0));
+        let u = a.union(b);
+
+        assert_eq!(u.min, Vec3::new(0.0, 0.0, 0.0));
+        assert_eq!(u.max, Vec3::new(3.0, 1.0, 1.0));
+        assert_eq!(u.centroid(), Vec3::new(1.5, 0.5, 0.5));
+        assert_eq!(a.surface_area(), 6.0);
+        assert_eq!(Aabb::empty().surface_area(), 0.0);
+        assert_eq!(Aabb::empty().union(a), a);
+    }
+
+    #[test]
+    fn test_aabb_hit() {
+        let aabb = Aabb::new(Vec3::new(-1.0, -1.0, 4.0), Vec3::new(1.0, 1.0, 6.0));
+        let ray = Ray::new(Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        let inv = Vec3::new(1.0 / 0.0, 1.0 / 0.0, 1.0);
+
+        let t = aabb.hit(&ray, inv, Float::INFINITY).expect("Expected hit");
+        assert!((t - 4.0).abs() < 1e-5);
+        assert!(aabb.hit(&ray, inv, 3.0).is_none());
+
+        let miss = Ray::new(Vec3::new(2.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        assert!(aabb.hit(&miss, inv, Float::INFINITY).is_none());
+    }
+}
@@ -0,0 +1,517 @@
+//! Bounding volume hierarchy for accelerating ray-surface intersection.
+
+use super::aabb::Aabb;
+use super::material::Material;
+use super::vector::{Float, Vec3};
+use super::{Intersection, Ray, Surface};
+
+/// Minimum hit distance accepted by the BVH.
+/// Matches the self-intersection threshold of `RayTracer::find_closest_intersection`
+/// so that a BVH produces exactly the same hits as the brute-force loop.
+const MIN_HIT_DISTANCE: Float = 1e-5;
+
+/// Maximum number of primitives stored in a single leaf node.
+const MAX_LEAF_SIZE: usize = 4;
+
+/// Number of buckets used to evaluate the surface area heuristic.
+const SAH_BUCKETS: usize = 12;
+
+/// Relative cost of visiting an interior node compared to intersecting a primitive.
+const TRAVERSAL_COST: Float = 0.125;
+
+/// A node of the flattened hierarchy.
+///
+/// Nodes are stored in depth-first order: the first child of an interior node
+/// immediately follows it, and `offset` points to the second child.
+/// For leaves, `offset` is the index of the first primitive and `count` the number of primitives.
+#[derive(Copy, Clone, Debug)]
+struct BvhNode {
+    /// Bounds of everything below this node
+    bounds: Aabb,
+    /// First primitive index (leaf) or second child index (interior)
+    offset: u32,
+    /// Number of primitives (0 for interior nodes)
+    count: u16,
+    /// Axis the node was split along (interior nodes only)
+    axis: u8,
+}
+
+/// Per-primitive data used while building the hierarchy.
+#[derive(Copy, Clone, Debug)]
+struct BuildPrimitive {
+    /// Index of the primitive in the input list
+    index: usize,
+    /// Bounds of the primitive
+    bounds: Aabb,
+    /// Centroid of the primitive bounds
+    centroid: Vec3,
+}
+
+/// A bounding volume hierarchy over a set of surfaces.
+///
+/// Built with the surface area heuristic (SAH) and stored as a flat array of nodes.
+/// Implements `Surface`, so it can be passed to `RayTracer::render` in place of the
+/// primitive list and yields the same intersections as testing every primitive.
+pub struct Bvh<S> {
+    /// Flattened nodes in depth-first order (empty if there are no primitives)
+    nodes: Vec<BvhNode>,
+    /// Primitives reordered so that each leaf references a contiguous range
+    primitives: Vec<S>,
+    /// Original input index of each reordered primitive (used to break ties)
+    original_indices: Vec<usize>,
+}
+
+impl<S: Surface> Bvh<S> {
+    /// Build a hierarchy over the given surfaces.
+    pub fn new(surfaces: Vec<S>) -> Self {
+        let mut build: Vec<BuildPrimitive> = surfaces
+            .iter()
+            .enumerate()
+            .map(|(index, surface)| {
+                let bounds = surface.bounds();
+                BuildPrimitive {
+                    index,
+                    bounds,
+                    centroid: bounds.centroid(),
+                }
+            })
+            .collect();
+
+        let mut nodes = Vec::with_capacity(2 * build.len());
+        if !build.is_empty() {
+            Self::build_recursive(&mut build, 0, &mut nodes);
+        }
+
+        // Reorder primitives to match the leaf ranges
+        let original_indices: Vec<usize> = build.iter().map(|p| p.index).collect();
+        let mut slots: Vec<Option<S>> = surfaces.into_iter().map(Some).collect();
+        let primitives = original_indices
+            .iter()
+            .map(|&i| slots[i].take().expect("primitive referenced twice"))
+            .collect();
+
+        Self {
+            nodes,
+            primitives,
+            original_indices,
+        }
+    }
+
+    /// Number of primitives in the hierarchy.
+    pub fn len(&self) -> usize {
+        self.primitives.len()
+    }
+
+    /// Check whether the hierarchy contains no primitives.
+    pub fn is_empty(&self) -> bool {
+        self.primitives.is_empty()
+    }
+
+    /// Recursively build the subtree for `primitives` and append it to `nodes`.
+    /// `first` is the offset of `primitives` within the full primitive list.
+    /// Returns the index of the created node.
+    fn build_recursive(
+        primitives: &mut [BuildPrimitive],
+        first: usize,
+        nodes: &mut Vec<BvhNode>,
+    ) -> usize {
+        let bounds = primitives
+            .iter()
+            .fold(Aabb::empty(), |acc, p| acc.union(p.bounds));
+        let node_index = nodes.len();
+        nodes.push(BvhNode {
+            bounds,
+            offset: first as u32,
+            count: primitives.len() as u16,
+            axis: 0,
+        });
+
+        if primitives.len() <= MAX_LEAF_SIZE {
+            return node_index;
+        }
+
+        let centroid_bounds = primitives
+            .iter()
+            .fold(Aabb::empty(), |acc, p| acc.union_point(p.centroid));
+        let axis = centroid_bounds.longest_axis();
+        let axis_min = centroid_bounds.min[axis];
+        let axis_extent = centroid_bounds.max[axis] - axis_min;
+
+        // All centroids coincide: splitting cannot separate them
+        if axis_extent <= 0.0 {
+            return Self::make_leaf_or_median(primitives, first, nodes, node_index, axis);
+        }
+
+        // === SAH BUCKETING ===
+        let bucket_of = |p: &BuildPrimitive| -> usize {
+            let relative = (p.centroid[axis] - axis_min) / axis_extent;
+            ((relative * SAH_BUCKETS as Float) as usize).min(SAH_BUCKETS - 1)
+        };
+
+        let mut bucket_counts = [0usize; SAH_BUCKETS];
+        let mut bucket_bounds = [Aabb::empty(); SAH_BUCKETS];
+        for p in primitives.iter() {
+            let b = bucket_of(p);
+            bucket_counts[b] += 1;
+            bucket_bounds[b] = bucket_bounds[b].union(p.bounds);
+        }
+
+        // Sweep from the right to get the cost of every right-hand side
+        let mut right_area = [0.0; SAH_BUCKETS];
+        let mut right_count = [0usize; SAH_BUCKETS];
+        let mut acc_bounds = Aabb::empty();
+        let mut acc_count = 0;
+        for b in (1..SAH_BUCKETS).rev() {
+            acc_bounds = acc_bounds.union(bucket_bounds[b]);
+            acc_count += bucket_counts[b];
+            right_area[b] = acc_bounds.surface_area();
+            right_count[b] = acc_count;
+        }
+
+        // Sweep from the left and pick the cheapest split plane
+        let mut best_cost = Float::INFINITY;
+        let mut best_split = 0;
+        let mut acc_bounds = Aabb::empty();
+        let mut acc_count = 0;
+        for b in 0..SAH_BUCKETS - 1 {
+            acc_bounds = acc_bounds.union(bucket_bounds[b]);
+            acc_count += bucket_counts[b];
+            if acc_count == 0 || right_count[b + 1] == 0 {
+                continue;
+            }
+            let cost = acc_bounds.surface_area() * acc_count as Float
+                + right_area[b + 1] * right_count[b + 1] as Float;
+            if cost < best_cost {
+                best_cost = cost;
+                best_split = b;
+            }
+        }
+
+        let parent_area = bounds.surface_area();
+        let leaf_cost = primitives.len() as Float;
+        let split_cost = if parent_area > 0.0 {
+            TRAVERSAL_COST + best_cost / parent_area
+        } else {
+            Float::INFINITY
+        };
+
+        if split_cost >= leaf_cost && primitives.len() <= u16::MAX as usize {
+            return node_index;
+        }
+        if best_cost == Float::INFINITY {
+            return Self::make_leaf_or_median(primitives, first, nodes, node_index, axis);
+        }
+
+        // Partition primitives in place around the chosen bucket boundary
+        let mut mid = 0;
+        for i in 0..primitives.len() {
+            if bucket_of(&primitives[i]) <= best_split {
+                primitives.swap(i, mid);
+                mid += 1;
+            }
+        }
+
+        Self::split_node(primitives, first, nodes, node_index, axis, mid)
+    }
+
+    /// Fallback when SAH bucketing cannot separate primitives.
+    /// Keeps small sets as a leaf and splits large sets at the median.
+    fn make_leaf_or_median(
+        primitives: &mut [BuildPrimitive],
+        first: usize,
+        nodes: &mut Vec<BvhNode>,
+        node_index: usize,
+        axis: usize,
+    ) -> usize {
+        if primitives.len() <= u16::MAX as usize {
+            return node_index;
+        }
+        let mid = primitives.len() / 2;
+        primitives
+            .select_nth_unstable_by(mid, |a, b| a.centroid[axis].total_cmp(&b.centroid[axis]));
+        Self::split_node(primitives, first, nodes, node_index, axis, mid)
+    }
+
+    /// Turn `node_index` into an interior node whose children cover `[..mid]` and `[mid..]`.
+    fn split_node(
+        primitives: &mut [BuildPrimitive],
+        first: usize,
+        nodes: &mut Vec<BvhNode>,
+        node_index: usize,
+        axis: usize,
+        mid: usize,
+    ) -> usize {
+        let (left, right) = primitives.split_at_mut(mid);
+        Self::build_recursive(left, first, nodes);
+        let second_child = Self::build_recursive(right, first + mid, nodes);
+
+        let node = &mut nodes[node_index];
+        node.offset = second_child as u32;
+        node.count = 0;
+        node.axis = axis as u8;
+        node_index
+    }
+}
+
+impl<S: Surface> Surface for Bvh<S> {
+    /// Find the closest intersection by traversing the hierarchy front to back.
+    /// Ties in distance are resolved in favor of the primitive that came first in the input,
+    /// matching the order-dependent result of a linear scan.
+    fn intersect(&self, ray: &Ray) -> Option<Intersection> {
+        if self.nodes.is_empty() {
+            return None;
+        }
+
+        let inv_direction = Vec3::new(
+            1.0 / ray.direction.x,
+            1.0 / ray.direction.y,
+            1.0 / ray.direction.z,
+        );
+        let direction_negative = [
+            ray.direction.x < 0.0,
+            ray.direction.y < 0.0,
+            ray.direction.z < 0.0,
+        ];
+
+        let mut closest: Option<(Intersection, usize)> = None;
+        let mut closest_t = Float::INFINITY;
+
+        // Growable: degenerate hierarchies can be deeper than any fixed bound
+        let mut stack: Vec<usize> = Vec::with_capacity(64);
+        let mut current = 0;
+
+        loop {
+            let node = &self.nodes[current];
+            if node.bounds.hit(ray, inv_direction, closest_t).is_some() {
+                if node.count > 0 {
+                    let start = node.offset as usize;
+                    for i in start..start + node.count as usize {
+                        let Some(intersection) = self.primitives[i].intersect(ray) else {
+                            continue;
+                        };
+                        if intersection.t <= MIN_HIT_DISTANCE || intersection.t > closest_t {
+                            continue;
+                        }
+                        let order = self.original_indices[i];
+                        let is_closer = match closest {
+                            Some((_, best_order)) => {
+                                intersection.t < closest_t || order < best_order
+                            }
+                            None => true,
+                        };
+                        if is_closer {
+                            closest = Some((intersection, order));
+                            closest_t = intersection.t;
+                        }
+                    }
+                } else {
+                    // Visit the child on the near side of the split plane first
+                    let (near, far) = if direction_negative[node.axis as usize] {
+                        (node.offset as usize, current + 1)
+                    } else {
+                        (current + 1, node.offset as usize)
+                    };
+                    stack.push(far);
+                    current = near;
+                    continue;
+                }
+            }
+
+            match stack.pop() {
+                Some(next) => current = next,
+                None => break,
+            }
+        }
+
+        closest.map(|(intersection, _)| intersection)
+    }
+
+    /// Bounds of the whole hierarchy (the root node's box).
+    fn bounds(&self) -> Aabb {
+        self.nodes.first().map_or(Aabb::empty(), |node| node.bounds)
+    }
+
+    /// An aggregate has no single material; returns the material of its first primitive.
+    fn material(&self) -> Material {
+        self.primitives
+            .first()
+            .map_or(Material::diffuse_surface(), |p| p.material())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raytracer::camera::Camera;
+    use crate::raytracer::light::Light;
+    use crate::raytracer::material::Color;
+    use crate::raytracer::mesh::Triangle;
+    use crate::raytracer::raytracer::RayTracer;
+    use crate::raytracer::sphere::Sphere;
+
+    /// Small deterministic generator so test scenes are reproducible.
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next(&mut self) -> Float {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((self.0 >> 40) as Float) / ((1u64 << 24) as Float)
+        }
+
+        fn next_vec(&mut self, scale: Float) -> Vec3 {
+            Vec3::new(
+                (self.next() - 0.5) * scale,
+                (self.next() - 0.5) * scale,
+                (self.next() - 0.5) * scale,
+            )
+        }
+    }
+
+    fn random_triangles(count: usize) -> Vec<Triangle> {
+        let mut rng = Lcg(7);
+        (0..count)
+            .map(|i| {
+                let center = rng.next_vec(10.0);
+                let material = Material::matte(Color::new(0.1 * (i % 10) as Float, 0.5, 0.5), 0.8);
+                Triangle::new(
+                    center + rng.next_vec(1.0),
+                    center + rng.next_vec(1.0),
+                    center + rng.next_vec(1.0),
+                    material,
+                )
+            })
+            .collect()
+    }
+
+    fn brute_force(triangles: &[Triangle], ray: &Ray) -> Option<Intersection> {
+        let mut closest: Option<Intersection> = None;
+        for triangle in triangles {
+            if let Some(hit) = triangle.intersect(ray)
+                && hit.t > MIN_HIT_DISTANCE
+                && closest.is_none_or(|c| hit.t < c.t)
+            {
+                closest = Some(hit);
+            }
+        }
+        closest
+    }
+
+    #[test]
+    fn test_bvh_empty() {
+        let bvh: Bvh<Triangle> = Bvh::new(vec![]);
+        let ray = Ray::new(Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+
+        assert!(bvh.is_empty());
+        assert!(bvh.intersect(&ray).is_none());
+    }
+
+    #[test]
+    fn test_bvh_deeper_than_64_levels() {
+        // Chain of 100 interior nodes whose near child is the next interior node,
+        // so every level leaves its far child on the traversal stack
+        let sphere = Sphere::new(Vec3::zero(), 1.0, Material::matte(Color::white(), 1.0));
+        let bounds = sphere.bounds();
+        let depth = 100;
+        let mut nodes: Vec<BvhNode> = (0..depth)
+            .map(|level| BvhNode {
+                bounds,
+                offset: (depth + 1 + level) as u32,
+                count: 0,
+                axis: 0,
+            })
+            .collect();
+        let leaf = BvhNode {
+            bounds,
+            offset: 0,
+            count: 1,
+            axis: 0,
+        };
+        nodes.extend(std::iter::repeat_n(leaf, depth + 1));
+        let bvh = Bvh {
+            nodes,
+            primitives: vec![sphere],
+            original_indices: vec![0],
+        };
+
+        let ray = Ray::new(Vec3::new(-5.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+        let hit = bvh.intersect(&ray).unwrap();
+        assert!((hit.t - 4.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_bvh_matches_brute_force() {
+        let triangles = random_triangles(500);
+        let bvh = Bvh::new(triangles.clone());
+        assert_eq!(bvh.len(), 500);
+
+        let expected_bounds = triangles
+            .iter()
+            .fold(Aabb::empty(), |acc, t| acc.union(t.bounds()));
+        assert_eq!(bvh.bounds(), expected_bounds);
+
+        let mut rng = Lcg(42);
+        for _ in 0..2000 {
+            let ray = Ray::new(rng.next_vec(30.0), rng.next_vec(2.0));
+            let expected = brute_force(&triangles, &ray);
+            let actual = bvh.intersect(&ray);
+
+            match (expected, actual) {
+                (None, None) => {}
+                (Some(e), Some(a)) => {
+                    assert_eq!(e.t, a.t);
+                    assert_eq!(e.normal, a.normal);
+                    assert_eq!(e.material.albedo, a.material.albedo);
+                }
+                _ => panic!("BVH and brute force disagree"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_bvh_render_matches_brute_force() {
+        let camera = Camera::new(
+            Vec3::new(0.0, -20.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
+            60.0,
+            24,
+            16,
+            1,
+        );
+        let mut triangles = random_triangles(200);
+        triangles.push(Triangle::new(
+            Vec3::new(-20.0, -20.0, -6.0),
+            Vec3::new(20.0, -20.0, -6.0),
+            Vec3::new(0.0, 20.0, -6.0),
+            Material::mirror(Color::white(), 0.5),
+        ));
+        let spheres = vec![
+            Sphere::new(Vec3::new(0.0, 0.0, 0.0), 1.5, Material::glass(0.9)),
+            Sphere::new(Vec3::new(3.0, -2.0, 1.0), 1.0, Material::perfect_mirror()),
+        ];
+        let lights = [Light::new(Vec3::new(0.0, -10.0, 15.0), 2.0, Color::white())];
+        let vacuum = Material::new(Color::black(), 0.0, 0.0, 1.0, 1.0, Color::black());
+        let tracer = RayTracer::new(Color::new(0.1, 0.1, 0.2), 6, 1e-3, vacuum);
+
+        let triangle_bvh = Bvh::new(triangles.clone());
+        let sphere_bvh = Bvh::new(spheres.clone());
+
+        let brute_triangles = tracer.render(&camera, &triangles, &lights);
+        let bvh_triangles = tracer.render(&camera, &[triangle_bvh], &lights);
+        let brute_spheres = tracer.render(&camera, &spheres, &lights);
+        let bvh_spheres = tracer.render(&camera, &[sphere_bvh], &lights);
+
+        for y in 0..camera.height as usize {
+            for x in 0..camera.width as usize {
+                assert_eq!(
+                    brute_triangles.get_pixel(x, y),
+                    bvh_triangles.get_pixel(x, y)
+                );
+                assert_eq!(brute_spheres.get_pixel(x, y), bvh_spheres.get_pixel(x, y));
+            }
+        }
+    }
+}
@@ -61,13 +61,8 @@ impl Camera {
         (right, up, forward)
     }
 
-    /// Generate rays for all pixels with anti-aliasing support.
-    ///
-    /// Returns a Vec<Vec<Vec<Ray>>> where:
-    /// - First dimension: rows (y)
-    /// - Second dimension: columns (x)
-    /// - Third dimension: samples within each pixel (subdivisions x subdivisions)
-    pub fn generate_rays(&self) -> Vec<Vec<Vec<Ray>>> {
+    /// Precompute the basis and view plane size shared by all rays of this camera.
+    fn view_plane(&self) -> ViewPlane {
         let (right, up, forward) = self.build_basis();
 
         // Convert FOV from degrees to radians
@@ -77,39 +72,92 @@ impl Camera {
         let view_height = 2.0 * (fov_rad / 2.0).tan();
         let view_width = view_height * (self.width as Float) / (self.height as Float);
 
-        let mut rays = Vec::new();
-        let sub = self.subdivisions as Float;
+        ViewPlane {
+            right: right * view_width,
+            up: up * view_height,
+            forward,
+        }
+    }
 
-        for y in 0..self.height {
-            let mut row = Vec::new();
-            for x in 0..self.width {
-                let mut pixel_samples = Vec::new();
+    /// Angle in radians covered by one pixel at the center of the image.
+    /// Used to estimate the footprint of camera rays for texture filtering.
+    pub fn pixel_spread(&self) -> Float {
+        let fov_rad = self.fov_degrees * PI / 180.0;
+        2.0 * (fov_rad / 2.0).tan() / self.height as Float
+    }
 
-                // Generate samples within this pixel
-                for sy in 0..self.subdivisions {
-                    for sx in 0..self.subdivisions {
-                        // Offset within the pixel: [0, 1)
-                        let offset_x = (sx as Float + 0.5) / sub;
-                        let offset_y = (sy as Float + 0.5) / sub;
+    /// Number of sample rays generated per pixel (subdivisions x subdivisions).
+    pub fn samples_per_pixel(&self) -> u32 {
+        self.subdivisions * self.subdivisions
+    }
 
-                        // Normalize to [-0.5, 0.5] relative to image
-                        let u = (x as Float + offset_x) / (self.width as Float) - 0.5;
-                        let v = (y as Float + offset_y) / (self.height as Float) - 0.5;
+    /// Generate a single sample ray for a pixel.
+    ///
+    /// # Arguments
+    /// * `x` - Pixel column (0 = left)
+    /// * `y` - Pixel row (0 = top)
+    /// * `sample` - Sample index within the pixel, in `0..samples_per_pixel()`,
+    ///   ordered row by row over the subdivision grid
+    pub fn ray_for(&self, x: u32, y: u32, sample: u32) -> Ray {
+        self.view_plane().ray(self, x, y, sample)
+    }
 
-                        // Calculate ray direction in camera space
-                        let ray_dir = forward + right * (u * view_width) - up * (v * view_height);
+    /// Iterate over all sample rays of a pixel without allocating.
+    pub fn pixel_rays(&self, x: u32, y: u32) -> impl Iterator<Item = Ray> + '_ {
+        let view = self.view_plane();
+        (0..self.samples_per_pixel()).map(move |sample| view.ray(self, x, y, sample))
+    }
 
-                        let ray = Ray::new(self.position, ray_dir);
-                        pixel_samples.push(ray);
-                    }
-                }
+    /// Generate rays for all pixels with anti-aliasing support.
+    ///
+    /// Returns a Vec<Vec<Vec<Ray>>> where:
+    /// - First dimension: rows (y)
+    /// - Second dimension: columns (x)
+    /// - Third dimension: samples within each pixel (subdivisions x subdivisions)
+    ///
+    /// This materializes every ray of the image at once; renderers should prefer
+    /// `pixel_rays` or `ray_for`, which keep memory constant.
+    pub fn generate_rays(&self) -> Vec<Vec<Vec<Ray>>> {
+        (0..self.height)
+            .map(|y| {
+                (0..self.width)
+                    .map(|x| self.pixel_rays(x, y).collect())
+                    .collect()
+            })
+            .collect()
+    }
+}
 
-                row.push(pixel_samples);
-            }
-            rays.push(row);
-        }
+/// Camera basis scaled to the view plane at distance 1.0.
+#[derive(Copy, Clone, Debug)]
+struct ViewPlane {
+    /// Right vector scaled by the view plane width
+    right: Vec3,
+    /// Up vector scaled by the view plane height
+    up: Vec3,
+    /// Forward direction
+    forward: Vec3,
+}
+
+impl ViewPlane {
+    /// Build the sample ray for a pixel of the given camera.
+    fn ray(&self, camera: &Camera, x: u32, y: u32, sample: u32) -> Ray {
+        let sub = camera.subdivisions as Float;
+        let sx = sample % camera.subdivisions;
+        let sy = sample / camera.subdivisions;
+
+        // Offset within the pixel: [0, 1)
+        let offset_x = (sx as Float + 0.5) / sub;
+        let offset_y = (sy as Float + 0.5) / sub;
+
+        // Normalize to [-0.5, 0.5] relative to image
+        let u = (x as Float + offset_x) / (camera.width as Float) - 0.5;
+        let v = (y as Float + offset_y) / (camera.height as Float) - 0.5;
 
-        rays
+        // Calculate ray direction in camera space
+        let ray_dir = self.forward + self.right * u - self.up * v;
+
+        Ray::new(camera.position, ray_dir)
     }
 }
 
@@ -170,4 +218,51 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_ray_for_matches_generate_rays() {
+        let camera = Camera::new(
+            Vec3::new(1.0, 2.0, 3.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
+            70.0,
+            8,
+            6,
+            3,
+        );
+        assert_eq!(camera.samples_per_pixel(), 9);
+
+        let rays = camera.generate_rays();
+        for y in 0..6 {
+            for x in 0..8 {
+                let streamed: Vec<Ray> = camera.pixel_rays(x, y).collect();
+                assert_eq!(streamed.len(), 9);
+                for (sample, ray) in streamed.iter().enumerate() {
+                    let expected = rays[y as usize][x as usize][sample];
+                    assert_eq!(ray.direction, expected.direction);
+                    assert_eq!(camera.ray_for(x, y, sample as u32).direction, ray.direction);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_center_ray_points_forward() {
+        let camera = Camera::new(
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            90.0,
+            3,
+            3,
+            1,
+        );
+
+        let ray = camera.ray_for(1, 1, 0);
+        assert!((ray.direction - Vec3::new(0.0, 0.0, 1.0)).length() < 1e-6);
+
+        // Top-left pixel looks up and to the left of the forward direction
+        let corner = camera.ray_for(0, 0, 0);
+        assert!(corner.direction.y > 0.0);
+    }
 }
@@ -0,0 +1,309 @@
+//! Image-based lighting from an equirectangular environment map.
+
+use super::light::{LightSample, LightSource};
+use super::material::Color;
+use super::vector::{Float, Vec3};
+use std::f32::consts::PI;
+use std::path::Path;
+
+/// An environment light surrounding the scene, defined by an equirectangular HDR image.
+///
+/// The scene is Z-up: the top row of the image is straight up (+Z), the bottom row
+/// straight down, and the horizontal axis wraps around the horizon starting at +X.
+/// Rays escaping the scene see the image, and direct lighting importance-samples
+/// it by pixel luminance, so small bright sources like the sun are found by shadow rays.
+pub struct EnvironmentLight {
+    /// Image width in pixels
+    width: usize,
+    /// Image height in pixels
+    height: usize,
+    /// Linear radiance of each pixel, row by row from the top
+    pixels: Vec<Color>,
+    /// Distribution over rows (marginal in θ)
+    rows: Distribution1D,
+    /// Distribution over pixels within each row (conditional in φ)
+    columns: Vec<Distribution1D>,
+    /// Multiplier applied to the image radiance
+    pub scale: Float,
+    /// Rotation of the image around the up axis, in radians
+    pub rotation: Float,
+}
+
+impl EnvironmentLight {
+    /// Create an environment light from linear radiance values.
+    ///
+    /// # Arguments
+    /// * `width` - Image width in pixels
+    /// * `height` - Image height in pixels
+    /// * `pixels` - Radiance of each pixel, row by row from the top
+    ///
+    /// # Panics
+    /// Panics if the image is empty or `pixels` does not hold `width * height` values.
+    pub fn new(width: usize, height: usize, pixels: Vec<Color>) -> Self {
+        assert!(width > 0 && height > 0, "Environment map must not be empty");
+        assert_eq!(
+            pixels.len(),
+            width * height,
+            "Environment map size mismatch"
+        );
+
+        // Weight pixels by luminance and by sin θ, the solid angle their row covers
+        let columns: Vec<Distribution1D> = (0..height)
+            .map(|y| {
+                let sin_theta = (PI * (y as Float + 0.5) / height as Float).sin();
+                let row = &pixels[y * width..(y + 1) * width];
+                Distribution1D::new(
+                    row.iter()
+                        .map(|pixel| pixel.luminance().max(0.0) * sin_theta)
+                        .collect(),
+                )
+            })
+            .collect();
+        let rows = Distribution1D::new(columns.iter().map(|c| c.integral).collect());
+
+        Self {
+            width,
+            height,
+            pixels,
+            rows,
+            columns,
+            scale: 1.0,
+            rotation: 0.0,
+        }
+    }
+
+    /// Load an equirectangular Radiance `.hdr` or OpenEXR image.
+    pub fn load(path: impl AsRef<Path>) -> ::image::ImageResult<Self> {
+        let image = ::image::open(path)?.into_rgb32f();
+        let (width, height) = image.dimensions();
+        let pixels = image
+            .pixels()
+            .map(|pixel| Color::new(pixel[0], pixel[1], pixel[2]))
+            .collect();
+        Ok(Self::new(width as usize, height as usize, pixels))
+    }
+
+    /// Set the multiplier applied to the image radiance.
+    pub fn with_scale(mut self, scale: Float) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Set the rotation of the image around the up axis, in radians.
+    pub fn with_rotation(mut self, rotation: Float) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Radiance arriving from `direction` (pointing away from the scene).
+    pub fn radiance_from(&self, direction: Vec3) -> Color {
+        let (u, v) = self.direction_to_uv(direction.normalize());
+        let x = ((u * self.width as Float) as usize).min(self.width - 1);
+        let y = ((v * self.height as Float) as usize).min(self.height - 1);
+        self.pixels[y * self.width + x] * self.scale
+    }
+
+    /// Map a unit direction to image coordinates in [0, 1)².
+    fn direction_to_uv(&self, direction: Vec3) -> (Float, Float) {
+        let theta = direction.z.clamp(-1.0, 1.0).acos();
+        let phi = direction.y.atan2(direction.x) + self.rotation;
+        let u = (phi / (2.0 * PI)).rem_euclid(1.0);
+        (u, theta / PI)
+    }
+
+    /// Map image coordinates back to a unit direction.
+    fn uv_to_direction(&self, u: Float, v: Float) -> Vec3 {
+        let theta = v * PI;
+        let phi = u * 2.0 * PI - self.rotation;
+        let sin_theta = theta.sin();
+        Vec3::new(sin_theta * phi.cos(), sin_theta * phi.sin(), theta.cos())
+    }
+}
+
+impl LightSource for EnvironmentLight {
+    /// Sample a direction with probability proportional to the luminance of the image.
+    fn sample(&self, _point: Vec3, u1: Float, u2: Float) -> Option<LightSample> {
+        if self.rows.integral <= 0.0 {
+            return None;
+        }
+
+        let (v, row_pdf, y) = self.rows.sample(u1);
+        let (u, column_pdf, _) = self.columns[y].sample(u2);
+        let sin_theta = (v * PI).sin();
+        if sin_theta <= 0.0 {
+            return None;
+        }
+
+        // Convert the image-space density to solid angle: dω = 2π² sin θ du dv
+        let pdf = row_pdf * column_pdf / (2.0 * PI * PI * sin_theta);
+        if pdf <= 0.0 {
+            return None;
+        }
+
+        let direction = self.uv_to_direction(u, v);
+        Some(LightSample {
+            direction,
+            distance: Float::INFINITY,
+            radiance: self.radiance_from(direction),
+            pdf,
+        })
+    }
+
+    fn escaped(&self, direction: Vec3) -> Color {
+        self.radiance_from(direction)
+    }
+}
+
+/// Piecewise-constant 1D distribution over [0, 1) used for importance sampling.
+struct Distribution1D {
+    /// Unnormalized value of each piece
+    values: Vec<Float>,
+    /// Normalized cumulative distribution, with `values.len() + 1` entries
+    cdf: Vec<Float>,
+    /// Average of `values` over [0, 1)
+    integral: Float,
+}
+
+impl Distribution1D {
+    fn new(values: Vec<Float>) -> Self {
+        let count = values.len() as Float;
+        let mut cdf = Vec::with_capacity(values.len() + 1);
+        cdf.push(0.0);
+        for value in &values {
+            cdf.push(cdf[cdf.len() - 1] + value / count);
+        }
+
+        let integral = cdf[values.len()];
+        if integral > 0.0 {
+            for entry in &mut cdf {
+                *entry /= integral;
+            }
+        } else {
+            // Nothing to favor: fall back to uniform sampling
+            for (i, entry) in cdf.iter_mut().enumerate() {
+                *entry = i as Float / count;
+            }
+        }
+
+        Self {
+            values,
+            cdf,
+            integral,
+        }
+    }
+
+    /// Sample a position in [0, 1).
+    /// Returns the position, its probability density and the index of its piece.
+    fn sample(&self, u: Float) -> (Float, Float, usize) {
+        let count = self.values.len();
+        let index = self
+            .cdf
+            .partition_point(|&c| c <= u)
+            .saturating_sub(1)
+            .min(count - 1);
+
+        let width = self.cdf[index + 1] - self.cdf[index];
+        let offset = if width > 0.0 {
+            (u - self.cdf[index]) / width
+        } else {
+            0.0
+        };
+        let pdf = if self.integral > 0.0 {
+            self.values[index] / self.integral
+        } else {
+            1.0
+        };
+        ((index as Float + offset) / count as Float, pdf, index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raytracer::sampling::Rng;
+
+    #[test]
+    fn test_uniform_environment_irradiance() {
+        let radiance = Color::new(0.5, 0.5, 0.5);
+        let env = EnvironmentLight::new(16, 8, vec![radiance; 16 * 8]);
+        let normal = Vec3::new(0.0, 0.0, 1.0);
+        let mut rng = Rng::new(9);
+
+        let n = 20000;
+        let mut irradiance = 0.0;
+        for _ in 0..n {
+            let sample = env
+                .sample(Vec3::zero(), rng.next_float(), rng.next_float())
+                .unwrap();
+            assert_eq!(sample.radiance, radiance);
+            irradiance += sample.radiance.r * sample.direction.dot(normal).max(0.0) / sample.pdf;
+        }
+        irradiance /= n as Float;
+
+        // A uniform sky of radiance L delivers π * L onto an upward-facing surface
+        assert!((irradiance - PI * 0.5).abs() / (PI * 0.5) < 0.03);
+    }
+
+    #[test]
+    fn test_environment_importance_sampling() {
+        // A dim map with one bright pixel
+        let (width, height) = (32, 16);
+        let mut pixels = vec![Color::new(0.01, 0.01, 0.01); width * height];
+        let bright = 5 * width + 20;
+        pixels[bright] = Color::new(1000.0, 1000.0, 1000.0);
+        let env = EnvironmentLight::new(width, height, pixels);
+
+        let mut rng = Rng::new(4);
+        let n = 1000;
+        let mut hits = 0;
+        for _ in 0..n {
+            let sample = env
+                .sample(Vec3::zero(), rng.next_float(), rng.next_float())
+                .unwrap();
+            if sample.radiance.r > 100.0 {
+                hits += 1;
+            }
+            assert_eq!(env.escaped(sample.direction), sample.radiance);
+        }
+        assert!(hits > 900);
+    }
+
+    #[test]
+    fn test_environment_orientation_and_rotation() {
+        // Top half red, bottom half blue; left half of the top brighter
+        let (width, height) = (4, 2);
+        let mut pixels = vec![Color::blue(); width * height];
+        pixels[..width].fill(Color::red());
+        pixels[0] = Color::white();
+        let env = EnvironmentLight::new(width, height, pixels);
+
+        assert_eq!(env.escaped(Vec3::new(0.3, 0.2, 1.0)), Color::white());
+        assert_eq!(env.escaped(Vec3::new(-1.0, -0.2, 0.5)), Color::red());
+        assert_eq!(env.escaped(Vec3::new(0.0, 0.0, -1.0)), Color::blue());
+
+        // Rotating by half a turn brings the bright quarter to the other side
+        let rotated = env.with_rotation(PI).with_scale(2.0);
+        assert_eq!(
+            rotated.escaped(Vec3::new(-0.3, -0.2, 1.0)),
+            Color::new(2.0, 2.0, 2.0)
+        );
+    }
+
+    #[test]
+    fn test_load_hdr() {
+        let path = std::env::temp_dir().join("raytracer_environment_test.hdr");
+        let mut image = ::image::Rgb32FImage::new(4, 2);
+        image.put_pixel(1, 0, ::image::Rgb([8.0, 4.0, 2.0]));
+        image.save(&path).unwrap();
+
+        let env = EnvironmentLight::load(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        // Pixel (1, 0) covers the upper hemisphere around +Y
+        let color = env.escaped(Vec3::new(0.0, 1.0, 0.5));
+        assert!((color.r - 8.0).abs() < 0.1);
+        assert!((color.g - 4.0).abs() < 0.1);
+        assert!((color.b - 2.0).abs() < 0.1);
+        assert!(env.escaped(Vec3::new(0.0, 0.0, -1.0)).r < 1e-3);
+    }
+}
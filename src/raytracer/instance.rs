@@ -0,0 +1,224 @@
+//! Surfaces placed in the scene by an affine transform.
+
+use super::aabb::Aabb;
+use super::material::Material;
+use super::transform::Transform;
+use super::{Intersection, Ray, Surface};
+
+/// A surface moved, rotated or scaled into the scene by a transform.
+///
+/// Rays are transformed into the surface's own space, intersected there, and
+/// the hit is transformed back with distances measured along the original ray.
+/// Wrap a shared surface (such as `Arc<TriangleMesh>`) to place the same
+/// geometry many times without copying it.
+pub struct Instance<S> {
+    /// The instanced geometry, in its own object space
+    surface: S,
+    /// Object-to-world transform
+    transform: Transform,
+    /// World-space bounds, computed once
+    bounds: Aabb,
+    /// Material replacing the reflectance of the surface's own materials, if any
+    pub material: Option<Material>,
+}
+
+impl<S: Surface> Instance<S> {
+    /// Place `surface` in the scene with the given object-to-world transform.
+    pub fn new(surface: S, transform: Transform) -> Self {
+        let bounds = transform.bounds(surface.bounds());
+        Self {
+            surface,
+            transform,
+            bounds,
+            material: None,
+        }
+    }
+}
+
+impl<S: Surface> Surface for Instance<S> {
+    fn intersect(&self, ray: &Ray) -> Option<Intersection> {
+        let inverse = self.transform.inverse_matrix();
+        let direction = inverse.transform_vector(ray.direction);
+        let scale = direction.length();
+        if scale == 0.0 {
+            return None;
+        }
+
+        // The object-space ray has a unit direction too, so object distances
+        // are world distances multiplied by `scale`
+        let local_ray = Ray::new(inverse.transform_point(ray.origin), direction)
+            .with_travelled(ray.travelled * scale);
+        let local = self.surface.intersect(&local_ray)?;
+
+        let mut hit = local;
+        hit.t = local.t / scale;
+        hit.point = ray.at(hit.t);
+        hit.normal = self.transform.normal(local.normal);
+        hit.geometric_normal = self.transform.normal(local.geometric_normal);
+        hit.dpdu = self.transform.vector(local.dpdu);
+        hit.dpdv = self.transform.vector(local.dpdv);
+        if let Some(material) = self.material {
+            hit.material = Material {
+                emission: local.material.emission,
+                ..material
+            };
+        }
+        Some(hit)
+    }
+
+    fn material(&self) -> Material {
+        let own = self.surface.material();
+        self.material.map_or(own, |material| Material {
+            emission: own.emission,
+            ..material
+        })
+    }
+
+    fn bounds(&self) -> Aabb {
+        self.bounds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raytracer::bvh::Bvh;
+    use crate::raytracer::material::Color;
+    use crate::raytracer::mesh::TriangleMesh;
+    use crate::raytracer::sphere::Sphere;
+    use crate::raytracer::vector::{Float, Vec3};
+    use std::f32::consts::FRAC_PI_2;
+    use std::sync::Arc;
+
+    fn unit_sphere() -> Sphere {
+        Sphere::new(Vec3::zero(), 1.0, Material::diffuse_surface())
+    }
+
+    #[test]
+    fn test_translated_instance_matches_moved_sphere() {
+        let offset = Vec3::new(2.0, 3.0, -1.0);
+        let instance = Instance::new(unit_sphere(), Transform::translation(offset));
+        let moved = Sphere::new(offset, 1.0, Material::diffuse_surface());
+
+        let ray = Ray::new(Vec3::new(0.0, 0.0, 0.0), Vec3::new(2.0, 3.2, -1.1));
+        let a = instance.intersect(&ray).unwrap();
+        let b = moved.intersect(&ray).unwrap();
+        assert!((a.t - b.t).abs() < 1e-4);
+        assert!((a.point - b.point).length() < 1e-4);
+        assert!((a.normal - b.normal).length() < 1e-4);
+        assert_eq!(instance.bounds(), moved.bounds());
+    }
+
+    #[test]
+    fn test_scaled_instance_distance_and_normal() {
+        // Ellipsoid with semi-axes 3, 1, 1
+        let instance = Instance::new(unit_sphere(), Transform::scaling(Vec3::new(3.0, 1.0, 1.0)));
+
+        let ray = Ray::new(Vec3::new(-10.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+        let hit = instance.intersect(&ray).unwrap();
+        assert!((hit.t - 7.0).abs() < 1e-4);
+        assert!((hit.point - Vec3::new(-3.0, 0.0, 0.0)).length() < 1e-4);
+
+        // Off-axis hit: the normal is the gradient of x²/9 + y² + z² = 1
+        let ray = Ray::new(Vec3::new(1.5, -5.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+        let mut hit = instance.intersect(&ray).unwrap();
+        let p = hit.point;
+        assert!((p.x * p.x / 9.0 + p.y * p.y + p.z * p.z - 1.0).abs() < 1e-4);
+        let gradient = Vec3::new(p.x / 9.0, p.y, p.z).normalize();
+        assert!((hit.normal - gradient).length() < 1e-4);
+        hit.compute_tangent_frame();
+        assert!((hit.tangent.length() - 1.0).abs() < 1e-4);
+        assert!(hit.tangent.dot(hit.normal).abs() < 1e-4);
+
+        assert!((instance.bounds().max.x - 3.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_rotated_instance() {
+        let quad = TriangleMesh::new(
+            vec![
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(1.0, 0.0, 0.0),
+                Vec3::new(1.0, 1.0, 0.0),
+                Vec3::new(0.0, 1.0, 0.0),
+            ],
+            vec![[0, 1, 2], [0, 2, 3]],
+            Material::diffuse_surface(),
+        );
+        // Stand the quad up in the XZ plane, facing -Y
+        let instance = Instance::new(
+            quad,
+            Transform::rotation(Vec3::new(1.0, 0.0, 0.0), FRAC_PI_2),
+        );
+
+        let ray = Ray::new(Vec3::new(0.5, -2.0, 0.5), Vec3::new(0.0, 1.0, 0.0));
+        let hit = instance.intersect(&ray).unwrap();
+        assert!((hit.t - 2.0).abs() < 1e-4);
+        assert!((hit.geometric_normal.y.abs() - 1.0).abs() < 1e-4);
+
+        let miss = Ray::new(Vec3::new(0.5, 0.5, 2.0), Vec3::new(0.0, 0.0, -1.0));
+        assert!(instance.intersect(&miss).is_none());
+    }
+
+    #[test]
+    fn test_shared_mesh_instances() {
+        let mesh = Arc::new(TriangleMesh::new(
+            vec![
+                Vec3::new(-0.5, -0.5, 0.0),
+                Vec3::new(0.5, -0.5, 0.0),
+                Vec3::new(0.0, 0.5, 0.0),
+            ],
+            vec![[0, 1, 2]],
+            Material::matte(Color::new(0.2, 0.4, 0.6), 1.0),
+        ));
+
+        let instances: Vec<_> = (0..1000)
+            .map(|i| {
+                let offset = Vec3::new((i % 40) as Float * 2.0, (i / 40) as Float * 2.0, 0.0);
+                Instance::new(Arc::clone(&mesh), Transform::translation(offset))
+            })
+            .collect();
+        assert_eq!(Arc::strong_count(&mesh), 1001);
+
+        let bvh = Bvh::new(instances);
+        let ray = Ray::new(Vec3::new(30.0, 20.0, 5.0), Vec3::new(0.0, 0.0, -1.0));
+        let hit = bvh.intersect(&ray).unwrap();
+        assert!((hit.t - 5.0).abs() < 1e-4);
+        assert_eq!(hit.material.albedo, Color::new(0.2, 0.4, 0.6));
+    }
+
+    #[test]
+    fn test_material_override() {
+        let glow = Color::new(2.0, 2.0, 2.0);
+        let lamp = Material::matte(Color::new(0.2, 0.4, 0.6), 1.0).with_emission(glow);
+        let red = Material::matte(Color::new(1.0, 0.0, 0.0), 1.0).with_roughness(0.3);
+        let instances = [
+            Instance::new(unit_sphere(), Transform::identity()),
+            Instance::new(
+                Sphere::new(Vec3::zero(), 1.0, lamp),
+                Transform::translation(Vec3::new(4.0, 0.0, 0.0)),
+            ),
+        ]
+        .map(|mut instance| {
+            instance.material = Some(red);
+            instance
+        });
+        let down = Vec3::new(0.0, 0.0, -1.0);
+
+        // The override replaces the reflectance of every hit
+        let hit = instances[0]
+            .intersect(&Ray::new(Vec3::new(0.0, 0.0, 5.0), down))
+            .unwrap();
+        assert_eq!(hit.material.albedo, red.albedo);
+        assert_eq!(hit.material.roughness, 0.3);
+        assert_eq!(hit.material.emission, Color::black());
+
+        // but emissive surfaces keep glowing
+        let hit = instances[1]
+            .intersect(&Ray::new(Vec3::new(4.0, 0.0, 5.0), down))
+            .unwrap();
+        assert_eq!(hit.material.albedo, red.albedo);
+        assert_eq!(hit.material.emission, glow);
+        assert_eq!(instances[1].material().emission, glow);
+    }
+}
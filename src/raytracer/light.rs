@@ -1,23 +1,127 @@
 //! Light sources for the raytracer.
 
-use super::material::Color;
+use super::material::{Color, Material};
+use super::mesh::{MeshData, TriangleMesh};
+use super::sampling::orthonormal_basis;
+use super::sphere::Sphere;
+use super::transform::{Transform, Transformable};
 use super::vector::{Float, Vec3};
-use super::{Intersection, Ray};
+use super::{Intersection, Ray, Surface};
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+/// Luminous efficacy used to convert between watts and lumens (lm/W).
+pub const LUMENS_PER_WATT: Float = 683.0;
+
+/// A direction towards a light chosen for direct lighting.
+#[derive(Copy, Clone, Debug)]
+pub struct LightSample {
+    /// Unit direction from the shaded point towards the sampled light point
+    pub direction: Vec3,
+    /// Distance from the shaded point to the sampled light point (infinite for distant lights)
+    pub distance: Float,
+    /// Radiance emitted towards the shaded point.
+    /// For delta lights this is the irradiance on a surface facing the light.
+    pub radiance: Color,
+    /// Probability density of the direction with respect to solid angle (1 for delta lights)
+    pub pdf: Float,
+}
+
+/// Trait for anything that emits light into the scene.
+/// Lights are shared between render threads, so they must be `Send + Sync`.
+pub trait LightSource: Send + Sync {
+    /// Sample a direction from `point` towards the light for direct lighting.
+    /// Returns None if the light cannot illuminate `point`.
+    ///
+    /// # Arguments
+    /// * `point` - The point being lit
+    /// * `u1`, `u2` - Uniform random numbers in [0, 1)
+    fn sample(&self, point: Vec3, u1: Float, u2: Float) -> Option<LightSample>;
+
+    /// Whether the light is a single point or direction, so one sample is exact.
+    fn is_delta(&self) -> bool {
+        false
+    }
+
+    /// Calculate ray-light intersection for lights that have a visible surface.
+    fn intersect(&self, _ray: &Ray) -> Option<Intersection> {
+        None
+    }
+
+    /// Radiance seen along `ray` when it hits the light at `intersection`.
+    fn radiance(&self, _ray: &Ray, _intersection: &Intersection) -> Color {
+        Color::black()
+    }
+
+    /// Radiance reaching a ray that escapes the scene in `direction` (for distant lights).
+    fn escaped(&self, _direction: Vec3) -> Color {
+        Color::black()
+    }
+}
+
+// Implement LightSource for references to trait objects
+impl<'a> LightSource for &'a (dyn LightSource + 'a) {
+    fn sample(&self, point: Vec3, u1: Float, u2: Float) -> Option<LightSample> {
+        (*self).sample(point, u1, u2)
+    }
+
+    fn is_delta(&self) -> bool {
+        (*self).is_delta()
+    }
+
+    fn intersect(&self, ray: &Ray) -> Option<Intersection> {
+        (*self).intersect(ray)
+    }
+
+    fn radiance(&self, ray: &Ray, intersection: &Intersection) -> Color {
+        (*self).radiance(ray, intersection)
+    }
+
+    fn escaped(&self, direction: Vec3) -> Color {
+        (*self).escaped(direction)
+    }
+}
+
+// Implement LightSource for shared trait objects
+impl<L: LightSource + ?Sized> LightSource for Arc<L> {
+    fn sample(&self, point: Vec3, u1: Float, u2: Float) -> Option<LightSample> {
+        (**self).sample(point, u1, u2)
+    }
+
+    fn is_delta(&self) -> bool {
+        (**self).is_delta()
+    }
+
+    fn intersect(&self, ray: &Ray) -> Option<Intersection> {
+        (**self).intersect(ray)
+    }
+
+    fn radiance(&self, ray: &Ray, intersection: &Intersection) -> Color {
+        (**self).radiance(ray, intersection)
+    }
+
+    fn escaped(&self, direction: Vec3) -> Color {
+        (**self).escaped(direction)
+    }
+}
 
 /// A spherical light source.
 /// Emits light uniformly in all directions from its surface.
+///
+/// Scene units are meters and `emission` is radiance in W/(sr·m²), so the light
+/// received from it falls off with the inverse square of the distance.
 #[derive(Copy, Clone, Debug)]
 pub struct Light {
     /// Center position of the light
     pub center: Vec3,
     /// Radius of the light
     pub radius: Float,
-    /// Emission color and intensity
+    /// Emitted radiance
     pub emission: Color,
 }
 
 impl Light {
-    /// Create a new light source.
+    /// Create a new light source from its emitted radiance.
     pub fn new(center: Vec3, radius: Float, emission: Color) -> Self {
         Self {
             center,
@@ -26,6 +130,27 @@ impl Light {
         }
     }
 
+    /// Create a light source from a sphere with an emissive material.
+    /// Pass the light instead of the sphere to the renderer so it is sampled by direct lighting.
+    pub fn from_sphere(sphere: &Sphere) -> Self {
+        Self::new(sphere.center, sphere.radius, sphere.material.emission)
+    }
+
+    /// Create a light source that emits a total power of `watts`.
+    ///
+    /// # Arguments
+    /// * `center` - Center position of the light
+    /// * `radius` - Radius of the light
+    /// * `color` - Tint of the light; only its hue matters, its luminance is normalized away
+    /// * `watts` - Total emitted power
+    pub fn with_power(center: Vec3, radius: Float, color: Color, watts: Float) -> Self {
+        let mut light = Self::new(center, radius, Color::black());
+        // Φ = π * L * A for a surface emitting uniformly in all directions
+        let radiance = watts / (PI * light.surface_area());
+        light.emission = normalized_tint(color) * radiance;
+        light
+    }
+
     /// Calculate the surface normal at a given point on the light sphere.
     pub fn normal_at(&self, point: Vec3) -> Vec3 {
         (point - self.center).normalize()
@@ -40,18 +165,56 @@ impl Light {
     pub fn surface_area(&self) -> Float {
         4.0 * std::f32::consts::PI * self.radius * self.radius
     }
+}
 
-    /// Get the total luminous flux (power) of the light.
-    /// Calculated as: emission_magnitude * surface_area
-    pub fn luminous_flux(&self) -> Float {
-        let emission_magnitude = (self.emission.r + self.emission.g + self.emission.b) / 3.0;
-        emission_magnitude * self.surface_area()
+impl LightSource for Light {
+    /// Sample a point on the part of the light sphere visible from `point`.
+    ///
+    /// Directions are drawn uniformly from the cone subtended by the sphere, so the
+    /// returned pdf is 1 / (solid angle of the cone). Dividing by it weights each
+    /// sample by the light's apparent size, which falls off with the inverse square
+    /// of the distance. Returns None if `point` is inside the light.
+    fn sample(&self, point: Vec3, u1: Float, u2: Float) -> Option<LightSample> {
+        let to_center = self.center - point;
+        let dist_sq = to_center.length_squared();
+        let radius_sq = self.radius * self.radius;
+        if dist_sq <= radius_sq {
+            return None;
+        }
+
+        // Cone of directions that hit the sphere: sin(θmax) = r / d
+        let dist = dist_sq.sqrt();
+        let axis = to_center / dist;
+        let sin_theta_max_sq = radius_sq / dist_sq;
+        let cos_theta_max = (1.0 - sin_theta_max_sq).max(0.0).sqrt();
+
+        // Uniform sampling of the cone
+        let cos_theta = 1.0 - u1 * (1.0 - cos_theta_max);
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi = 2.0 * PI * u2;
+        let (tangent, bitangent) = orthonormal_basis(axis);
+        let direction = (tangent * (sin_theta * phi.cos())
+            + bitangent * (sin_theta * phi.sin())
+            + axis * cos_theta)
+            .normalize();
+
+        // Distance to the near side of the sphere along the sampled direction
+        let discriminant = (radius_sq - dist_sq * sin_theta * sin_theta).max(0.0);
+        let distance = dist * cos_theta - discriminant.sqrt();
+
+        let solid_angle = 2.0 * PI * (1.0 - cos_theta_max);
+        Some(LightSample {
+            direction,
+            distance,
+            radiance: self.emission,
+            pdf: 1.0 / solid_angle,
+        })
     }
 
     /// Calculate ray-light intersection.
     /// Returns the intersection if the ray hits this light, None otherwise.
     /// Uses the quadratic formula to solve: ||O + t*D - C||^2 = r^2
-    pub fn intersect(&self, ray: &Ray) -> Option<Intersection> {
+    fn intersect(&self, ray: &Ray) -> Option<Intersection> {
         let oc = ray.origin - self.center;
         let d = ray.direction;
 
@@ -85,17 +248,690 @@ impl Light {
         let point = ray.at(t);
         let normal = self.normal_at(point);
 
-        // Light intersections use a dummy material (lights aren't rendered as surfaces)
-        let dummy_material = crate::raytracer::material::Material::new(
-            Color::black(),
-            0.0,
-            0.0,
-            0.0,
-            1.0,
+        Some(Intersection::new(t, point, normal, emitter_material()))
+    }
+
+    fn radiance(&self, _ray: &Ray, _intersection: &Intersection) -> Color {
+        self.emission
+    }
+}
+
+/// A flat parallelogram emitter, such as a window or a ceiling panel.
+///
+/// The light is spanned by two edges from a corner. One-sided lights emit only
+/// on the side of `edge_u × edge_v`.
+#[derive(Copy, Clone, Debug)]
+pub struct QuadLight {
+    /// Corner of the quad
+    pub corner: Vec3,
+    /// First edge from the corner
+    pub edge_u: Vec3,
+    /// Second edge from the corner
+    pub edge_v: Vec3,
+    /// Emitted radiance
+    pub emission: Color,
+    /// Whether the quad emits from both faces
+    pub two_sided: bool,
+}
+
+impl QuadLight {
+    /// Create a new one-sided quad light.
+    ///
+    /// # Arguments
+    /// * `corner` - Corner of the quad
+    /// * `edge_u` - First edge from the corner
+    /// * `edge_v` - Second edge from the corner
+    /// * `emission` - Emitted radiance
+    pub fn new(corner: Vec3, edge_u: Vec3, edge_v: Vec3, emission: Color) -> Self {
+        Self {
+            corner,
+            edge_u,
+            edge_v,
+            emission,
+            two_sided: false,
+        }
+    }
+
+    /// Set whether the quad emits from both faces.
+    pub fn with_two_sided(mut self, two_sided: bool) -> Self {
+        self.two_sided = two_sided;
+        self
+    }
+
+    /// Unit normal of the emitting face.
+    pub fn normal(&self) -> Vec3 {
+        self.edge_u.cross(self.edge_v).normalize()
+    }
+
+    /// Get the area of one face of the quad.
+    pub fn area(&self) -> Float {
+        self.edge_u.cross(self.edge_v).length()
+    }
+}
+
+impl LightSource for QuadLight {
+    /// Sample a point uniformly over the area of the quad.
+    fn sample(&self, point: Vec3, u1: Float, u2: Float) -> Option<LightSample> {
+        let target = self.corner + self.edge_u * u1 + self.edge_v * u2;
+        area_sample(
+            point,
+            target,
+            self.normal(),
+            self.area(),
+            self.emission,
+            self.two_sided,
+        )
+    }
+
+    fn intersect(&self, ray: &Ray) -> Option<Intersection> {
+        let normal = self.normal();
+        let t = plane_hit(ray, self.corner, normal)?;
+        let point = ray.at(t);
+
+        // Project onto the edges to find the quad coordinates of the hit point
+        let offset = point - self.corner;
+        let w = self.edge_u.cross(self.edge_v);
+        let w = w / w.dot(w);
+        let u = offset.cross(self.edge_v).dot(w);
+        let v = self.edge_u.cross(offset).dot(w);
+        if !(0.0..=1.0).contains(&u) || !(0.0..=1.0).contains(&v) {
+            return None;
+        }
+        Some(Intersection::new(t, point, normal, emitter_material()))
+    }
+
+    fn radiance(&self, ray: &Ray, intersection: &Intersection) -> Color {
+        facing_emission(ray, intersection, self.emission, self.two_sided)
+    }
+}
+
+/// A flat circular emitter, such as a downlight or a softbox.
+/// One-sided lights emit only on the side of `normal`.
+#[derive(Copy, Clone, Debug)]
+pub struct DiskLight {
+    /// Center of the disk
+    pub center: Vec3,
+    /// Unit normal of the emitting face
+    pub normal: Vec3,
+    /// Radius of the disk
+    pub radius: Float,
+    /// Emitted radiance
+    pub emission: Color,
+    /// Whether the disk emits from both faces
+    pub two_sided: bool,
+}
+
+impl DiskLight {
+    /// Create a new one-sided disk light.
+    ///
+    /// # Arguments
+    /// * `center` - Center of the disk
+    /// * `normal` - Direction the emitting face points to
+    /// * `radius` - Radius of the disk
+    /// * `emission` - Emitted radiance
+    pub fn new(center: Vec3, normal: Vec3, radius: Float, emission: Color) -> Self {
+        Self {
+            center,
+            normal: normal.normalize(),
+            radius,
+            emission,
+            two_sided: false,
+        }
+    }
+
+    /// Set whether the disk emits from both faces.
+    pub fn with_two_sided(mut self, two_sided: bool) -> Self {
+        self.two_sided = two_sided;
+        self
+    }
+
+    /// Get the area of one face of the disk.
+    pub fn area(&self) -> Float {
+        PI * self.radius * self.radius
+    }
+}
+
+impl LightSource for DiskLight {
+    /// Sample a point uniformly over the area of the disk.
+    fn sample(&self, point: Vec3, u1: Float, u2: Float) -> Option<LightSample> {
+        let r = self.radius * u1.sqrt();
+        let phi = 2.0 * PI * u2;
+        let (tangent, bitangent) = orthonormal_basis(self.normal);
+        let target = self.center + tangent * (r * phi.cos()) + bitangent * (r * phi.sin());
+        area_sample(
+            point,
+            target,
+            self.normal,
+            self.area(),
+            self.emission,
+            self.two_sided,
+        )
+    }
+
+    fn intersect(&self, ray: &Ray) -> Option<Intersection> {
+        let t = plane_hit(ray, self.center, self.normal)?;
+        let point = ray.at(t);
+        if (point - self.center).length_squared() > self.radius * self.radius {
+            return None;
+        }
+        Some(Intersection::new(t, point, self.normal, emitter_material()))
+    }
+
+    fn radiance(&self, ray: &Ray, intersection: &Intersection) -> Color {
+        facing_emission(ray, intersection, self.emission, self.two_sided)
+    }
+}
+
+/// An infinitely small light emitting equally in all directions.
+#[derive(Copy, Clone, Debug)]
+pub struct PointLight {
+    /// Position of the light
+    pub position: Vec3,
+    /// Radiant intensity (W/sr)
+    pub intensity: Color,
+}
+
+impl PointLight {
+    /// Create a new point light from its radiant intensity.
+    pub fn new(position: Vec3, intensity: Color) -> Self {
+        Self {
+            position,
+            intensity,
+        }
+    }
+
+    /// Create a point light that emits a total power of `watts`.
+    /// Only the hue of `color` matters, its luminance is normalized away.
+    pub fn with_power(position: Vec3, color: Color, watts: Float) -> Self {
+        Self::new(position, normalized_tint(color) * (watts / (4.0 * PI)))
+    }
+}
+
+impl LightSource for PointLight {
+    fn sample(&self, point: Vec3, _u1: Float, _u2: Float) -> Option<LightSample> {
+        delta_sample(point, self.position, self.intensity)
+    }
+
+    fn is_delta(&self) -> bool {
+        true
+    }
+}
+
+/// A point light restricted to a cone, with a smooth falloff between two angles.
+#[derive(Copy, Clone, Debug)]
+pub struct SpotLight {
+    /// Position of the light
+    pub position: Vec3,
+    /// Unit direction the spot is pointing at
+    pub direction: Vec3,
+    /// Radiant intensity (W/sr) along the axis of the cone
+    pub intensity: Color,
+    /// Half-angle (radians) of the fully lit inner cone
+    pub inner_angle: Float,
+    /// Half-angle (radians) beyond which no light is emitted
+    pub outer_angle: Float,
+    /// Exponent shaping the transition between the inner and outer cone (1 = linear)
+    pub falloff: Float,
+}
+
+impl SpotLight {
+    /// Create a new spot light from its radiant intensity.
+    ///
+    /// # Arguments
+    /// * `position` - Position of the light
+    /// * `direction` - Direction the spot is pointing at
+    /// * `intensity` - Radiant intensity along the axis of the cone
+    /// * `inner_angle` - Half-angle of the fully lit inner cone, in radians
+    /// * `outer_angle` - Half-angle of the outer cone, in radians
+    pub fn new(
+        position: Vec3,
+        direction: Vec3,
+        intensity: Color,
+        inner_angle: Float,
+        outer_angle: Float,
+    ) -> Self {
+        Self {
+            position,
+            direction: direction.normalize(),
+            intensity,
+            inner_angle: inner_angle.min(outer_angle),
+            outer_angle,
+            falloff: 1.0,
+        }
+    }
+
+    /// Create a spot light that emits a total power of `watts`.
+    /// Only the hue of `color` matters, its luminance is normalized away.
+    pub fn with_power(
+        position: Vec3,
+        direction: Vec3,
+        color: Color,
+        watts: Float,
+        inner_angle: Float,
+        outer_angle: Float,
+    ) -> Self {
+        let mut light = Self::new(
+            position,
+            direction,
             Color::black(),
+            inner_angle,
+            outer_angle,
         );
-        Some(Intersection::new(t, point, normal, dummy_material))
+        light.intensity = normalized_tint(color) * (watts / light.cone_solid_angle());
+        light
+    }
+
+    /// Set the falloff exponent between the inner and outer cone.
+    pub fn with_falloff(mut self, falloff: Float) -> Self {
+        self.falloff = falloff;
+        self
+    }
+
+    /// Fraction of the axial intensity emitted in `direction` (pointing away from the light).
+    pub fn cone_factor(&self, direction: Vec3) -> Float {
+        let cos_theta = direction.dot(self.direction);
+        let cos_inner = self.inner_angle.cos();
+        let cos_outer = self.outer_angle.cos();
+        if cos_theta >= cos_inner {
+            return 1.0;
+        }
+        if cos_theta <= cos_outer {
+            return 0.0;
+        }
+        let t = (cos_theta - cos_outer) / (cos_inner - cos_outer);
+        t.powf(self.falloff)
+    }
+
+    /// Solid angle of the cone, counting the falloff region as half lit.
+    fn cone_solid_angle(&self) -> Float {
+        let cos_average = 0.5 * (self.inner_angle.cos() + self.outer_angle.cos());
+        2.0 * PI * (1.0 - cos_average)
+    }
+}
+
+impl LightSource for SpotLight {
+    fn sample(&self, point: Vec3, _u1: Float, _u2: Float) -> Option<LightSample> {
+        let factor = self.cone_factor((point - self.position).normalize());
+        if factor <= 0.0 {
+            return None;
+        }
+        delta_sample(point, self.position, self.intensity * factor)
+    }
+
+    fn is_delta(&self) -> bool {
+        true
+    }
+}
+
+/// A light infinitely far away, such as the sun.
+///
+/// With a zero angular diameter all rays arrive parallel and cast hard shadows.
+/// A positive angular diameter turns it into a small disk in the sky that casts
+/// soft shadows and can be seen by rays escaping the scene.
+#[derive(Copy, Clone, Debug)]
+pub struct DirectionalLight {
+    /// Unit direction the light travels in
+    pub direction: Vec3,
+    /// Irradiance (W/m²) on a surface facing the light
+    pub irradiance: Color,
+    /// Apparent angular diameter (radians) of the light disk
+    pub angular_diameter: Float,
+}
+
+impl DirectionalLight {
+    /// Create a new directional light.
+    ///
+    /// # Arguments
+    /// * `direction` - Direction the light travels in
+    /// * `irradiance` - Irradiance on a surface facing the light
+    pub fn new(direction: Vec3, irradiance: Color) -> Self {
+        Self {
+            direction: direction.normalize(),
+            irradiance,
+            angular_diameter: 0.0,
+        }
+    }
+
+    /// Set the apparent angular diameter of the light disk, in radians.
+    pub fn with_angular_diameter(mut self, angular_diameter: Float) -> Self {
+        self.angular_diameter = angular_diameter;
+        self
+    }
+
+    /// Cosine of the half-angle of the light disk.
+    fn cos_half_angle(&self) -> Float {
+        (0.5 * self.angular_diameter).cos()
+    }
+
+    /// Solid angle covered by the light disk.
+    fn solid_angle(&self) -> Float {
+        2.0 * PI * (1.0 - self.cos_half_angle())
+    }
+}
+
+impl LightSource for DirectionalLight {
+    fn sample(&self, _point: Vec3, u1: Float, u2: Float) -> Option<LightSample> {
+        let axis = -self.direction;
+        let solid_angle = self.solid_angle();
+        if solid_angle <= 0.0 {
+            return Some(LightSample {
+                direction: axis,
+                distance: Float::INFINITY,
+                radiance: self.irradiance,
+                pdf: 1.0,
+            });
+        }
+
+        // Uniform sampling of the cone covered by the disk
+        let cos_theta = 1.0 - u1 * (1.0 - self.cos_half_angle());
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi = 2.0 * PI * u2;
+        let (tangent, bitangent) = orthonormal_basis(axis);
+        let direction = (tangent * (sin_theta * phi.cos())
+            + bitangent * (sin_theta * phi.sin())
+            + axis * cos_theta)
+            .normalize();
+
+        Some(LightSample {
+            direction,
+            distance: Float::INFINITY,
+            radiance: self.irradiance * (1.0 / solid_angle),
+            pdf: 1.0 / solid_angle,
+        })
+    }
+
+    fn is_delta(&self) -> bool {
+        self.angular_diameter <= 0.0
+    }
+
+    fn escaped(&self, direction: Vec3) -> Color {
+        let solid_angle = self.solid_angle();
+        if solid_angle > 0.0 && (-direction).dot(self.direction) >= self.cos_half_angle() {
+            self.irradiance * (1.0 / solid_angle)
+        } else {
+            Color::black()
+        }
+    }
+}
+
+/// A triangle mesh whose faces glow with the emission of their materials.
+///
+/// Shadow rays pick an emissive face with probability proportional to its area,
+/// then a uniform point on that face. One-sided meshes emit only from the front
+/// face given by the triangle winding.
+///
+/// The light owns its emissive faces: they are drawn, and block shadow rays, as
+/// part of the light, so they must not also be added as a surface (their emission
+/// would be counted twice). Use `split` to keep the rest of a mesh as a surface.
+pub struct MeshLight {
+    /// The emissive faces of the original mesh
+    mesh: TriangleMesh,
+    /// Faces of `mesh` with a non-zero area, which can be sampled
+    faces: Vec<u32>,
+    /// Running total of the areas of `faces`
+    cumulative_areas: Vec<Float>,
+    /// Whether faces emit from both sides
+    pub two_sided: bool,
+}
+
+impl MeshLight {
+    /// Split a mesh into a light made of its emissive faces and a surface made of
+    /// the remaining faces (None if every face emits).
+    pub fn split(mesh: TriangleMesh) -> (Self, Option<TriangleMesh>) {
+        let data = mesh.data();
+        let (emissive, dark): (Vec<_>, Vec<_>) = data
+            .faces
+            .iter()
+            .partition(|face| data.materials[face.material as usize].emission.luminance() > 0.0);
+        let surface = (!dark.is_empty()).then(|| {
+            TriangleMesh::from_data(MeshData {
+                faces: dark,
+                ..data.clone()
+            })
+        });
+        let mesh = TriangleMesh::from_data(MeshData {
+            faces: emissive,
+            ..data.clone()
+        });
+
+        let mut faces = Vec::new();
+        let mut cumulative_areas = Vec::new();
+        let mut total = 0.0;
+        for face_idx in 0..mesh.face_count() {
+            let (v0, v1, v2) = mesh.data().face_positions(face_idx);
+            let area = 0.5 * (v1 - v0).cross(v2 - v0).length();
+            if area > 0.0 {
+                total += area;
+                faces.push(face_idx as u32);
+                cumulative_areas.push(total);
+            }
+        }
+
+        let light = Self {
+            mesh,
+            faces,
+            cumulative_areas,
+            two_sided: false,
+        };
+        (light, surface)
+    }
+
+    /// Set whether faces emit from both sides.
+    pub fn with_two_sided(mut self, two_sided: bool) -> Self {
+        self.two_sided = two_sided;
+        self
+    }
+
+    /// Get the emissive faces.
+    pub fn mesh(&self) -> &TriangleMesh {
+        &self.mesh
+    }
+
+    /// Get the total area of the emissive faces.
+    pub fn area(&self) -> Float {
+        self.cumulative_areas.last().copied().unwrap_or(0.0)
+    }
+}
+
+impl LightSource for MeshLight {
+    fn sample(&self, point: Vec3, u1: Float, u2: Float) -> Option<LightSample> {
+        let total = self.area();
+        if total <= 0.0 {
+            return None;
+        }
+
+        // Pick a face by area, then reuse u1 within the chosen face's slice
+        let target_area = u1 * total;
+        let index = self
+            .cumulative_areas
+            .partition_point(|&cumulative| cumulative <= target_area)
+            .min(self.faces.len() - 1);
+        let start = if index == 0 {
+            0.0
+        } else {
+            self.cumulative_areas[index - 1]
+        };
+        let face_area = self.cumulative_areas[index] - start;
+        let u1 = ((target_area - start) / face_area).clamp(0.0, 1.0);
+
+        // Uniform point on the triangle
+        let data = self.mesh.data();
+        let face = self.faces[index] as usize;
+        let (v0, v1, v2) = data.face_positions(face);
+        let su = u1.sqrt();
+        let target = v0 * (1.0 - su) + v1 * (su * (1.0 - u2)) + v2 * (su * u2);
+        let normal = (v1 - v0).cross(v2 - v0).normalize();
+        let emission = data.materials[data.faces[face].material as usize].emission;
+
+        // Face pick probability (area / total) times 1 / area gives an area pdf of 1 / total
+        area_sample(point, target, normal, total, emission, self.two_sided)
+    }
+
+    fn intersect(&self, ray: &Ray) -> Option<Intersection> {
+        self.mesh.intersect(ray)
+    }
+
+    fn radiance(&self, ray: &Ray, intersection: &Intersection) -> Color {
+        if self.two_sided || ray.direction.dot(intersection.geometric_normal) < 0.0 {
+            intersection.material.emission
+        } else {
+            Color::black()
+        }
+    }
+}
+
+// Lights keep their radiance (or intensity) when moved; scaling area lights
+// changes their size and therefore their power.
+impl Transformable for Light {
+    fn transformed(&self, transform: &Transform) -> Self {
+        Self {
+            center: transform.point(self.center),
+            radius: self.radius * transform.scale_factor(),
+            ..*self
+        }
+    }
+}
+
+impl Transformable for QuadLight {
+    fn transformed(&self, transform: &Transform) -> Self {
+        Self {
+            corner: transform.point(self.corner),
+            edge_u: transform.vector(self.edge_u),
+            edge_v: transform.vector(self.edge_v),
+            ..*self
+        }
+    }
+}
+
+impl Transformable for DiskLight {
+    fn transformed(&self, transform: &Transform) -> Self {
+        Self {
+            center: transform.point(self.center),
+            normal: transform.normal(self.normal),
+            radius: self.radius * transform.scale_factor(),
+            ..*self
+        }
+    }
+}
+
+impl Transformable for PointLight {
+    fn transformed(&self, transform: &Transform) -> Self {
+        Self {
+            position: transform.point(self.position),
+            ..*self
+        }
+    }
+}
+
+impl Transformable for SpotLight {
+    fn transformed(&self, transform: &Transform) -> Self {
+        Self {
+            position: transform.point(self.position),
+            direction: transform.vector(self.direction).normalize(),
+            ..*self
+        }
+    }
+}
+
+impl Transformable for DirectionalLight {
+    fn transformed(&self, transform: &Transform) -> Self {
+        Self {
+            direction: transform.vector(self.direction).normalize(),
+            ..*self
+        }
+    }
+}
+
+/// Material reported for light hits (lights aren't rendered as surfaces).
+fn emitter_material() -> Material {
+    Material::new(Color::black(), 0.0, 0.0, 0.0, 1.0, Color::black())
+}
+
+/// Distance along a ray to the plane through `origin` with the given normal.
+fn plane_hit(ray: &Ray, origin: Vec3, normal: Vec3) -> Option<Float> {
+    let denom = ray.direction.dot(normal);
+    if denom.abs() < 1e-8 {
+        return None;
+    }
+    let t = (origin - ray.origin).dot(normal) / denom;
+    (t > 0.0).then_some(t)
+}
+
+/// Radiance leaving a flat emitter towards a ray, black when seeing the back of a one-sided light.
+fn facing_emission(
+    ray: &Ray,
+    intersection: &Intersection,
+    emission: Color,
+    two_sided: bool,
+) -> Color {
+    if two_sided || ray.direction.dot(intersection.normal) < 0.0 {
+        emission
+    } else {
+        Color::black()
+    }
+}
+
+/// Direct lighting sample for a point chosen uniformly on a flat emitter.
+/// Converts the area pdf 1 / A to solid angle: pdf = d² / (A * cos θ_light).
+fn area_sample(
+    point: Vec3,
+    target: Vec3,
+    normal: Vec3,
+    area: Float,
+    emission: Color,
+    two_sided: bool,
+) -> Option<LightSample> {
+    let to_light = target - point;
+    let distance_sq = to_light.length_squared();
+    if distance_sq <= 0.0 || area <= 0.0 {
+        return None;
+    }
+    let distance = distance_sq.sqrt();
+    let direction = to_light / distance;
+
+    let cos_light = -direction.dot(normal);
+    let cos_light = if two_sided {
+        cos_light.abs()
+    } else {
+        cos_light
+    };
+    if cos_light <= 0.0 {
+        return None;
+    }
+
+    Some(LightSample {
+        direction,
+        distance,
+        radiance: emission,
+        pdf: distance_sq / (area * cos_light),
+    })
+}
+
+/// Scale a color so that its luminance is 1 (black stays black).
+fn normalized_tint(color: Color) -> Color {
+    let luminance = color.luminance();
+    if luminance > 0.0 {
+        color * (1.0 / luminance)
+    } else {
+        Color::black()
+    }
+}
+
+/// Direct lighting sample for a point-like emitter with the given radiant intensity.
+fn delta_sample(point: Vec3, position: Vec3, intensity: Color) -> Option<LightSample> {
+    let to_light = position - point;
+    let distance_sq = to_light.length_squared();
+    if distance_sq <= 0.0 {
+        return None;
     }
+    let distance = distance_sq.sqrt();
+    Some(LightSample {
+        direction: to_light / distance,
+        distance,
+        // Irradiance I / d² falls off with the inverse square of the distance
+        radiance: intensity * (1.0 / distance_sq),
+        pdf: 1.0,
+    })
 }
 
 #[cfg(test)]
@@ -161,4 +997,306 @@ mod tests {
         let light = Light::new(Vec3::new(0.0, 0.0, 0.0), 1.0, emission);
         assert_eq!(light.emission, emission);
     }
+
+    #[test]
+    fn test_light_sample_hits_sphere() {
+        let light = Light::new(Vec3::new(0.0, 0.0, 10.0), 2.0, Color::white());
+        let point = Vec3::new(1.0, 0.0, 0.0);
+
+        for i in 0..16 {
+            for j in 0..16 {
+                let u1 = (i as Float + 0.5) / 16.0;
+                let u2 = (j as Float + 0.5) / 16.0;
+                let sample = light.sample(point, u1, u2).expect("Expected sample");
+
+                // The sampled point lies on the light surface
+                let on_light = point + sample.direction * sample.distance;
+                assert!(((on_light - light.center).length() - light.radius).abs() < 1e-3);
+
+                // And is the first point of the light along that direction
+                let hit = light.intersect(&Ray::new(point, sample.direction)).unwrap();
+                assert!((hit.t - sample.distance).abs() < 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn test_light_sample_pdf_follows_inverse_square() {
+        let light = Light::new(Vec3::new(0.0, 0.0, 0.0), 0.1, Color::white());
+
+        let near = light.sample(Vec3::new(1.0, 0.0, 0.0), 0.5, 0.5).unwrap();
+        let far = light.sample(Vec3::new(2.0, 0.0, 0.0), 0.5, 0.5).unwrap();
+
+        // Twice as far: a quarter of the solid angle, four times the pdf
+        assert!((far.pdf / near.pdf - 4.0).abs() < 0.01);
+        assert!(light.sample(Vec3::new(0.05, 0.0, 0.0), 0.5, 0.5).is_none());
+    }
+
+    #[test]
+    fn test_light_power_round_trip() {
+        let center = Vec3::new(0.0, 0.0, 0.0);
+        let light = Light::with_power(center, 0.5, Color::new(1.0, 0.8, 0.6), 100.0);
+        // Φ = π * L * A
+        let watts = PI * light.emission.luminance() * light.surface_area();
+        assert!((watts - 100.0).abs() < 1e-3);
+        assert!(light.emission.r > light.emission.b);
+
+        // Same power on a bigger sphere spreads over more area: lower radiance
+        let big = Light::with_power(center, 1.0, Color::new(1.0, 0.8, 0.6), 100.0);
+        assert!(big.emission.r < light.emission.r);
+    }
+
+    #[test]
+    fn test_light_irradiance_from_power() {
+        let light = Light::with_power(Vec3::new(0.0, 0.0, 0.0), 0.25, Color::white(), 1000.0);
+
+        for distance in [2.0, 5.0] {
+            let point = Vec3::new(0.0, 0.0, -distance);
+            let normal = Vec3::new(0.0, 0.0, 1.0);
+            let n = 32;
+            let mut irradiance = 0.0;
+            for i in 0..n {
+                for j in 0..n {
+                    let u1 = (i as Float + 0.5) / n as Float;
+                    let u2 = (j as Float + 0.5) / n as Float;
+                    let sample = light.sample(point, u1, u2).unwrap();
+                    irradiance += sample.radiance.g * sample.direction.dot(normal) / sample.pdf;
+                }
+            }
+            irradiance /= (n * n) as Float;
+
+            // A sphere emitting Φ watts delivers Φ / (4π d²) at distance d
+            let expected = 1000.0 / (4.0 * PI * distance * distance);
+            assert!((irradiance - expected).abs() / expected < 0.01);
+        }
+    }
+
+    #[test]
+    fn test_point_light_inverse_square() {
+        let light = PointLight::with_power(Vec3::new(0.0, 0.0, 0.0), Color::white(), 100.0);
+        // Φ = 4π * I for a light emitting uniformly in all directions
+        assert!((4.0 * PI * light.intensity.luminance() - 100.0).abs() < 1e-3);
+        assert!(light.is_delta());
+
+        let near = light.sample(Vec3::new(1.0, 0.0, 0.0), 0.5, 0.5).unwrap();
+        let far = light.sample(Vec3::new(2.0, 0.0, 0.0), 0.5, 0.5).unwrap();
+        assert_eq!(near.direction, Vec3::new(-1.0, 0.0, 0.0));
+        assert!((near.radiance.g / far.radiance.g - 4.0).abs() < 1e-4);
+        assert!(
+            light
+                .intersect(&Ray::new(Vec3::new(1.0, 0.0, 0.0), near.direction))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_spot_light_cone() {
+        let spot = SpotLight::new(
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, -1.0),
+            Color::white(),
+            0.3,
+            0.6,
+        );
+        let down = Vec3::new(0.0, 0.0, -1.0);
+        assert_eq!(spot.cone_factor(down), 1.0);
+        assert_eq!(spot.cone_factor(Vec3::new(1.0, 0.0, 0.0)), 0.0);
+
+        let between = Vec3::new(0.45_f32.sin(), 0.0, -0.45_f32.cos());
+        let linear = spot.cone_factor(between);
+        assert!(linear > 0.0 && linear < 1.0);
+        assert!(spot.with_falloff(2.0).cone_factor(between) < linear);
+
+        assert!(spot.sample(Vec3::new(0.0, 0.0, -1.0), 0.5, 0.5).is_some());
+        assert!(spot.sample(Vec3::new(0.0, 0.0, 1.0), 0.5, 0.5).is_none());
+    }
+
+    #[test]
+    fn test_directional_light() {
+        let direction = Vec3::new(0.0, -1.0, 0.0);
+        let sun = DirectionalLight::new(direction, Color::white());
+        let sample = sun.sample(Vec3::new(5.0, 0.0, 5.0), 0.3, 0.7).unwrap();
+        assert_eq!(sample.direction, Vec3::new(0.0, 1.0, 0.0));
+        assert_eq!(sample.distance, Float::INFINITY);
+        assert_eq!(sun.escaped(Vec3::new(0.0, 1.0, 0.0)), Color::black());
+
+        // A sun disk: samples stay in the cone and integrate to the same irradiance
+        let disk = sun.with_angular_diameter(0.1);
+        assert!(!disk.is_delta());
+        let sample = disk.sample(Vec3::new(0.0, 0.0, 0.0), 0.9, 0.2).unwrap();
+        assert!(sample.direction.dot(Vec3::new(0.0, 1.0, 0.0)) >= (0.05_f32).cos() - 1e-5);
+        assert!((sample.radiance.r / sample.pdf - 1.0).abs() < 1e-3);
+        assert!(disk.escaped(Vec3::new(0.0, 1.0, 0.0)).r > 0.0);
+        assert_eq!(disk.escaped(Vec3::new(1.0, 0.0, 0.0)), Color::black());
+    }
+
+    #[test]
+    fn test_quad_light_intersect() {
+        // 2x2 ceiling panel at z = 3 facing down
+        let quad = QuadLight::new(
+            Vec3::new(-1.0, -1.0, 3.0),
+            Vec3::new(0.0, 2.0, 0.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            Color::white(),
+        );
+        assert_eq!(quad.normal(), Vec3::new(0.0, 0.0, -1.0));
+        assert!((quad.area() - 4.0).abs() < 1e-5);
+
+        let up = Ray::new(Vec3::new(0.5, 0.5, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        let hit = quad.intersect(&up).expect("Expected hit");
+        assert!((hit.t - 3.0).abs() < 1e-5);
+        assert_eq!(quad.radiance(&up, &hit), Color::white());
+
+        let outside = Ray::new(Vec3::new(1.5, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        assert!(quad.intersect(&outside).is_none());
+
+        // Seen from above, only a two-sided panel emits
+        let down = Ray::new(Vec3::new(0.5, 0.5, 5.0), Vec3::new(0.0, 0.0, -1.0));
+        let hit = quad.intersect(&down).unwrap();
+        assert_eq!(quad.radiance(&down, &hit), Color::black());
+        let both = quad.with_two_sided(true);
+        assert_eq!(both.radiance(&down, &hit), Color::white());
+        assert!(quad.sample(Vec3::new(0.0, 0.0, 5.0), 0.5, 0.5).is_none());
+        assert!(both.sample(Vec3::new(0.0, 0.0, 5.0), 0.5, 0.5).is_some());
+    }
+
+    #[test]
+    fn test_disk_light_irradiance() {
+        let radius = 1.0;
+        let height = 2.0;
+        let disk = DiskLight::new(
+            Vec3::new(0.0, 0.0, height),
+            Vec3::new(0.0, 0.0, -1.0),
+            radius,
+            Color::white(),
+        );
+        let point = Vec3::new(0.0, 0.0, 0.0);
+        let normal = Vec3::new(0.0, 0.0, 1.0);
+
+        let n = 64;
+        let mut irradiance = 0.0;
+        for i in 0..n {
+            for j in 0..n {
+                let u1 = (i as Float + 0.5) / n as Float;
+                let u2 = (j as Float + 0.5) / n as Float;
+                let sample = disk.sample(point, u1, u2).unwrap();
+                let hit = disk.intersect(&Ray::new(point, sample.direction)).unwrap();
+                assert!((hit.t - sample.distance).abs() < 1e-3);
+                irradiance += sample.radiance.r * sample.direction.dot(normal) / sample.pdf;
+            }
+        }
+        irradiance /= (n * n) as Float;
+
+        // Irradiance below the center of a disk: π * L * r² / (r² + h²)
+        let expected = PI * radius * radius / (radius * radius + height * height);
+        assert!((irradiance - expected).abs() / expected < 0.01);
+    }
+
+    #[test]
+    fn test_light_from_emissive_sphere() {
+        let material = Material::emissive(Color::new(2.0, 2.0, 2.0));
+        let sphere = Sphere::new(Vec3::new(1.0, 2.0, 3.0), 0.5, material);
+        let light = Light::from_sphere(&sphere);
+        assert_eq!(light.center, sphere.center);
+        assert_eq!(light.radius, sphere.radius);
+        assert_eq!(light.emission, Color::new(2.0, 2.0, 2.0));
+    }
+
+    #[test]
+    fn test_mesh_light_samples_by_area() {
+        use crate::raytracer::mesh::{MeshData, MeshFace};
+
+        // Two emissive triangles at z = 2 facing down, the second three times larger,
+        // plus a dark triangle that must never be sampled
+        let mut data = MeshData::new(
+            vec![
+                Vec3::new(0.0, 0.0, 2.0),
+                Vec3::new(0.0, 1.0, 2.0),
+                Vec3::new(1.0, 0.0, 2.0),
+                Vec3::new(-4.0, 0.0, 2.0),
+                Vec3::new(-4.0, 1.0, 2.0),
+                Vec3::new(-1.0, 0.0, 2.0),
+                Vec3::new(5.0, 0.0, 2.0),
+                Vec3::new(5.0, 1.0, 2.0),
+                Vec3::new(6.0, 0.0, 2.0),
+            ],
+            vec![[0, 1, 2], [3, 4, 5], [6, 7, 8]],
+            Material::emissive(Color::white()),
+        );
+        data.materials.push(Material::diffuse_surface());
+        data.faces[2] = MeshFace::new([6, 7, 8], 1);
+        let (light, _) = MeshLight::split(TriangleMesh::from_data(data));
+
+        assert!((light.area() - 2.0).abs() < 1e-5);
+
+        let point = Vec3::new(0.0, 0.0, 0.0);
+        let n = 1000;
+        let mut on_large = 0;
+        for i in 0..n {
+            let u1 = (i as Float + 0.5) / n as Float;
+            let sample = light.sample(point, u1, 0.3).expect("Expected sample");
+            let target = point + sample.direction * sample.distance;
+            assert!(target.x < 4.0);
+            assert!((target.z - 2.0).abs() < 1e-4);
+            if target.x < 0.0 {
+                on_large += 1;
+            }
+
+            let hit = light.intersect(&Ray::new(point, sample.direction)).unwrap();
+            assert!((hit.t - sample.distance).abs() < 1e-3);
+        }
+        assert!((on_large as Float / n as Float - 0.75).abs() < 0.01);
+
+        // Faces point down: visible from below, nothing to sample from above
+        let up = Ray::new(Vec3::new(0.2, 0.2, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        let hit = light.intersect(&up).unwrap();
+        assert_eq!(light.radiance(&up, &hit), Color::white());
+        assert!(light.sample(Vec3::new(0.2, 0.2, 5.0), 0.1, 0.1).is_none());
+
+        // The dark triangle is not part of the light
+        let dark = Ray::new(Vec3::new(5.2, 0.2, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        assert!(light.intersect(&dark).is_none());
+    }
+
+    #[test]
+    fn test_mesh_light_split_keeps_dark_faces_as_surface() {
+        use crate::raytracer::mesh::{MeshData, MeshFace};
+
+        let mut data = MeshData::new(
+            vec![
+                Vec3::new(0.0, 0.0, 2.0),
+                Vec3::new(0.0, 1.0, 2.0),
+                Vec3::new(1.0, 0.0, 2.0),
+                Vec3::new(5.0, 0.0, 2.0),
+                Vec3::new(5.0, 1.0, 2.0),
+                Vec3::new(6.0, 0.0, 2.0),
+            ],
+            vec![[0, 1, 2], [3, 4, 5]],
+            Material::emissive(Color::white()),
+        );
+        data.materials.push(Material::diffuse_surface());
+        data.faces[1] = MeshFace::new([3, 4, 5], 1);
+
+        let (light, surface) = MeshLight::split(TriangleMesh::from_data(data));
+        let surface = surface.expect("Expected the dark face as a surface");
+        assert_eq!(light.mesh().face_count(), 1);
+        assert_eq!(surface.face_count(), 1);
+
+        let lamp = Ray::new(Vec3::new(0.2, 0.2, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        let dark = Ray::new(Vec3::new(5.2, 0.2, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        assert!(light.intersect(&lamp).is_some() && surface.intersect(&lamp).is_none());
+        assert!(light.intersect(&dark).is_none() && surface.intersect(&dark).is_some());
+
+        // A fully emissive mesh leaves nothing behind
+        let lamp_only = TriangleMesh::new(
+            vec![
+                Vec3::new(0.0, 0.0, 2.0),
+                Vec3::new(0.0, 1.0, 2.0),
+                Vec3::new(1.0, 0.0, 2.0),
+            ],
+            vec![[0, 1, 2]],
+            Material::emissive(Color::white()),
+        );
+        assert!(MeshLight::split(lamp_only).1.is_none());
+    }
 }
@@ -1,6 +1,7 @@
 //! Material definitions for the raytracer.
 
 use super::vector::Float;
+use std::fmt;
 
 /// Color represented in RGB format.
 ///
@@ -64,6 +65,11 @@ impl Color {
             b: 1.0,
         }
     }
+
+    /// Relative luminance of the color (Rec. 709 weights).
+    pub fn luminance(&self) -> Float {
+        0.2126 * self.r + 0.7152 * self.g + 0.0722 * self.b
+    }
 }
 
 // Operator implementations for Color
@@ -97,6 +103,50 @@ impl Mul for Color {
     }
 }
 
+/// How a material splits transmitted light between reflection and refraction.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum Fresnel {
+    /// Fixed rates: `transmission_rate` is always refracted
+    #[default]
+    None,
+    /// Schlick's approximation of the Fresnel reflectance
+    Schlick,
+    /// Exact Fresnel equations for unpolarized light
+    Exact,
+}
+
+impl Fresnel {
+    /// Fraction of light reflected at a dielectric interface.
+    /// Returns 1.0 on total internal reflection.
+    ///
+    /// # Arguments
+    /// * `cos_i` - Cosine of the angle between the incident ray and the surface normal
+    /// * `ratio` - Ratio of refractive indices n_incident / n_transmitted
+    pub fn reflectance(&self, cos_i: Float, ratio: Float) -> Float {
+        let cos_i = cos_i.abs().min(1.0);
+        let sin_t_sq = ratio * ratio * (1.0 - cos_i * cos_i);
+        if sin_t_sq > 1.0 {
+            return 1.0;
+        }
+        let cos_t = (1.0 - sin_t_sq).sqrt();
+
+        match self {
+            Fresnel::None => 0.0,
+            Fresnel::Schlick => {
+                let r0 = ((1.0 - ratio) / (1.0 + ratio)).powi(2);
+                // Use the angle on the optically thinner side
+                let cos = if ratio > 1.0 { cos_t } else { cos_i };
+                r0 + (1.0 - r0) * (1.0 - cos).powi(5)
+            }
+            Fresnel::Exact => {
+                let rs = (ratio * cos_i - cos_t) / (ratio * cos_i + cos_t);
+                let rp = (cos_i - ratio * cos_t) / (cos_i + ratio * cos_t);
+                0.5 * (rs * rs + rp * rp)
+            }
+        }
+    }
+}
+
 /// Material properties for rendering.
 ///
 /// Uses several components to define how light interacts:
@@ -105,6 +155,14 @@ impl Mul for Color {
 /// - specular_rate: Portion of light reflected sharply (mirror-like surface)
 /// - transmission_rate: Portion of light transmitted through (transparency)
 /// - absorption: Absorption coefficient per channel for Beer's law attenuation
+/// - emission: Radiance emitted by the surface itself (black for non-emissive surfaces)
+/// - fresnel: Whether transmission is split into reflection and refraction by view angle
+/// - roughness: Microfacet roughness of specular reflection and transmission (0 = smooth)
+/// - specular_tint: Color of specular reflection (white for dielectrics, base color for metals)
+/// - coat, coat_roughness: Clear lacquer layer on top of the surface
+/// - sheen: Extra diffuse reflection at grazing angles (cloth, velvet)
+///
+/// `PrincipledMaterial` offers a more intuitive way to build these parameters.
 #[derive(Copy, Clone, Debug)]
 pub struct Material {
     /// Surface color (albedo) for diffuse reflection (0.0 to 1.0 per channel).
@@ -122,8 +180,79 @@ pub struct Material {
     /// Used to simulate light absorption when passing through the material.
     /// Higher values = stronger absorption. (0, 0, 0) = no absorption (vacuum/air).
     pub absorption: Color,
+    /// Radiance emitted by the surface, added wherever a ray hits it.
+    pub emission: Color,
+    /// Fresnel model applied to the transmitted portion.
+    /// With a model other than `None`, `transmission_rate` is split between
+    /// reflection and refraction depending on the angle of incidence.
+    pub fresnel: Fresnel,
+    /// Roughness (0.0 to 1.0) of the GGX microfacet distribution.
+    /// 0 keeps perfectly sharp reflection and refraction; higher values blur them
+    /// (brushed metal, frosted glass).
+    pub roughness: Float,
+    /// Color filter applied to the `specular_rate` part of reflection.
+    pub specular_tint: Color,
+    /// Strength (0.0 to 1.0) of a clear coat with refractive index `CLEARCOAT_IOR`.
+    /// The coat reflects by the Fresnel equations and lets the rest through to the base.
+    pub coat: Float,
+    /// Roughness (0.0 to 1.0) of the clear coat.
+    pub coat_roughness: Float,
+    /// Sheen color added to the diffuse reflectance towards grazing angles.
+    pub sheen: Color,
+}
+
+/// Refractive index of the clear coat layer (typical lacquer or varnish).
+pub const CLEARCOAT_IOR: Float = 1.5;
+
+/// Slack allowed on energy checks to absorb floating point rounding.
+const ENERGY_TOLERANCE: Float = 1e-4;
+
+/// Error produced when a material would reflect or transmit more light than it receives.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MaterialError {
+    /// A rate or weight lies outside [0, 1] or is not a number
+    RateOutOfRange {
+        /// Name of the offending field
+        field: &'static str,
+        /// Its value
+        value: Float,
+    },
+    /// `diffuse_rate + specular_rate + transmission_rate` exceeds 1
+    EnergyGain {
+        /// Sum of the three rates
+        total: Float,
+    },
+    /// A reflectance color has a channel outside [0, 1]
+    ColorOutOfRange {
+        /// Name of the offending field
+        field: &'static str,
+        /// Its value
+        color: Color,
+    },
 }
 
+impl fmt::Display for MaterialError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MaterialError::RateOutOfRange { field, value } => {
+                write!(f, "{} must be between 0 and 1, got {}", field, value)
+            }
+            MaterialError::EnergyGain { total } => write!(
+                f,
+                "diffuse, specular and transmission rates sum to {} (more than 1)",
+                total
+            ),
+            MaterialError::ColorOutOfRange { field, color } => write!(
+                f,
+                "{} channels must be between 0 and 1, got ({}, {}, {})",
+                field, color.r, color.g, color.b
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MaterialError {}
+
 impl Material {
     /// Create a new material with specified properties.
     ///
@@ -149,9 +278,184 @@ impl Material {
             transmission_rate: transmission_rate.max(0.0).min(1.0),
             refractive_index,
             absorption,
+            emission: Color::black(),
+            fresnel: Fresnel::None,
+            roughness: 0.0,
+            specular_tint: Color::white(),
+            coat: 0.0,
+            coat_roughness: 0.0,
+            sheen: Color::black(),
         }
     }
 
+    /// Set the color filter of specular reflection.
+    pub fn with_specular_tint(mut self, specular_tint: Color) -> Self {
+        self.specular_tint = specular_tint;
+        self
+    }
+
+    /// Add a clear coat layer.
+    ///
+    /// # Arguments
+    /// * `coat` - Strength of the coat (0.0-1.0)
+    /// * `roughness` - Microfacet roughness of the coat (0.0-1.0)
+    pub fn with_coat(mut self, coat: Float, roughness: Float) -> Self {
+        self.coat = coat.clamp(0.0, 1.0);
+        self.coat_roughness = roughness.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the sheen color added to diffuse reflection at grazing angles.
+    pub fn with_sheen(mut self, sheen: Color) -> Self {
+        self.sheen = sheen;
+        self
+    }
+
+    /// Create a new material, rejecting parameters that would create energy.
+    ///
+    /// Unlike `new`, rates are not clamped: each must lie in [0, 1] and their sum must
+    /// not exceed 1. See `validate` for the full set of checks.
+    ///
+    /// # Arguments
+    /// * `albedo` - Surface color (0.0-1.0 per channel)
+    /// * `diffuse_rate` - Diffuse (matte) reflection rate (0.0-1.0)
+    /// * `specular_rate` - Specular (mirror) reflection rate (0.0-1.0)
+    /// * `transmission_rate` - Transmission (transparency) rate (0.0-1.0)
+    /// * `refractive_index` - Refractive index for refraction
+    /// * `absorption` - Absorption coefficient per channel (Beer's law)
+    pub fn try_new(
+        albedo: Color,
+        diffuse_rate: Float,
+        specular_rate: Float,
+        transmission_rate: Float,
+        refractive_index: Float,
+        absorption: Color,
+    ) -> Result<Self, MaterialError> {
+        let material = Self {
+            diffuse_rate,
+            specular_rate,
+            transmission_rate,
+            ..Self::new(
+                albedo,
+                diffuse_rate,
+                specular_rate,
+                transmission_rate,
+                refractive_index,
+                absorption,
+            )
+        };
+        material.validate()?;
+        Ok(material)
+    }
+
+    /// Sum of the diffuse, specular and transmission rates.
+    pub fn total_rate(&self) -> Float {
+        self.diffuse_rate + self.specular_rate + self.transmission_rate
+    }
+
+    /// Check that the material does not reflect or transmit more light than it receives.
+    ///
+    /// Rates and the coat must lie in [0, 1], the rates must sum to at most 1, and the
+    /// albedo (plus sheen) and specular tint must not exceed 1 in any channel.
+    /// Emission is not checked: emitters are allowed to add light.
+    pub fn validate(&self) -> Result<(), MaterialError> {
+        let rates = [
+            ("diffuse_rate", self.diffuse_rate),
+            ("specular_rate", self.specular_rate),
+            ("transmission_rate", self.transmission_rate),
+            ("coat", self.coat),
+        ];
+        for (field, value) in rates {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(MaterialError::RateOutOfRange { field, value });
+            }
+        }
+
+        let total = self.total_rate();
+        if total > 1.0 + ENERGY_TOLERANCE {
+            return Err(MaterialError::EnergyGain { total });
+        }
+
+        let colors = [
+            ("albedo", self.albedo),
+            ("albedo + sheen", self.albedo + self.sheen),
+            ("specular_tint", self.specular_tint),
+        ];
+        for (field, color) in colors {
+            let in_range = |c: Float| (0.0..=1.0 + ENERGY_TOLERANCE).contains(&c);
+            if !(in_range(color.r) && in_range(color.g) && in_range(color.b)) {
+                return Err(MaterialError::ColorOutOfRange { field, color });
+            }
+        }
+        Ok(())
+    }
+
+    /// Return a copy that conserves energy: rates are scaled down so they sum to at
+    /// most 1, and reflectance colors are clamped to [0, 1].
+    pub fn normalized(mut self) -> Self {
+        let clamp = |c: Color| {
+            Color::new(
+                c.r.clamp(0.0, 1.0),
+                c.g.clamp(0.0, 1.0),
+                c.b.clamp(0.0, 1.0),
+            )
+        };
+
+        self.diffuse_rate = self.diffuse_rate.clamp(0.0, 1.0);
+        self.specular_rate = self.specular_rate.clamp(0.0, 1.0);
+        self.transmission_rate = self.transmission_rate.clamp(0.0, 1.0);
+        self.coat = self.coat.clamp(0.0, 1.0);
+        let total = self.total_rate();
+        if total > 1.0 {
+            self.diffuse_rate /= total;
+            self.specular_rate /= total;
+            self.transmission_rate /= total;
+        }
+
+        self.albedo = clamp(self.albedo);
+        self.specular_tint = clamp(self.specular_tint);
+        // Sheen only gets the headroom the albedo leaves
+        let headroom = Color::new(
+            1.0 - self.albedo.r,
+            1.0 - self.albedo.g,
+            1.0 - self.albedo.b,
+        );
+        self.sheen = Color::new(
+            self.sheen.r.clamp(0.0, headroom.r),
+            self.sheen.g.clamp(0.0, headroom.g),
+            self.sheen.b.clamp(0.0, headroom.b),
+        );
+        self
+    }
+
+    /// Set the microfacet roughness of specular reflection and transmission.
+    pub fn with_roughness(mut self, roughness: Float) -> Self {
+        self.roughness = roughness.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the Fresnel model used to split transmitted light.
+    pub fn with_fresnel(mut self, fresnel: Fresnel) -> Self {
+        self.fresnel = fresnel;
+        self
+    }
+
+    /// Create a clear dielectric (glass, water) whose reflection follows the exact Fresnel equations.
+    pub fn dielectric(refractive_index: Float) -> Self {
+        Self::transparent(Color::white(), 1.0, refractive_index).with_fresnel(Fresnel::Exact)
+    }
+
+    /// Set the radiance emitted by the surface, making it glow.
+    pub fn with_emission(mut self, emission: Color) -> Self {
+        self.emission = emission;
+        self
+    }
+
+    /// Create a black surface that only emits light.
+    pub fn emissive(emission: Color) -> Self {
+        Self::matte(Color::black(), 0.0).with_emission(emission)
+    }
+
     /// Create a purely diffuse (matte) material.
     pub fn matte(albedo: Color, diffuse_rate: Float) -> Self {
         Self::new(albedo, diffuse_rate, 0.0, 0.0, 1.0, Color::black())
@@ -214,6 +518,229 @@ impl Material {
     }
 }
 
+/// Principled (Disney/OpenPBR style) material description.
+///
+/// Parameters are intuitive and energy conserving: metallic and transmission blend
+/// between a diffuse dielectric, a metal and a glass, and the remaining lobes split
+/// what is left. Convert into a `Material` for rendering with `Material::from`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PrincipledMaterial {
+    /// Diffuse color of dielectrics, reflection color of metals
+    pub base_color: Color,
+    /// Blend between dielectric (0.0) and metal (1.0)
+    pub metallic: Float,
+    /// Microfacet roughness of reflection and transmission (0.0 = mirror-like)
+    pub roughness: Float,
+    /// Dielectric reflectance at normal incidence, with 0.5 = 4% (0.0 to 1.0 → 0% to 8%)
+    pub specular: Float,
+    /// Portion of the dielectric that transmits light (glass)
+    pub transmission: Float,
+    /// Strength of the clear coat layer
+    pub clearcoat: Float,
+    /// Roughness of the clear coat layer
+    pub clearcoat_roughness: Float,
+    /// Strength of the grazing-angle sheen
+    pub sheen: Float,
+    /// Blend of the sheen color between white (0.0) and the base color hue (1.0)
+    pub sheen_tint: Float,
+    /// Refractive index used for transmission
+    pub ior: Float,
+    /// Radiance emitted by the surface
+    pub emission: Color,
+}
+
+impl Default for PrincipledMaterial {
+    fn default() -> Self {
+        Self {
+            base_color: Color::new(0.8, 0.8, 0.8),
+            metallic: 0.0,
+            roughness: 0.5,
+            specular: 0.5,
+            transmission: 0.0,
+            clearcoat: 0.0,
+            clearcoat_roughness: 0.03,
+            sheen: 0.0,
+            sheen_tint: 0.5,
+            ior: 1.5,
+            emission: Color::black(),
+        }
+    }
+}
+
+impl PrincipledMaterial {
+    /// Create a principled material with the given base color and default parameters.
+    pub fn new(base_color: Color) -> Self {
+        Self {
+            base_color,
+            ..Self::default()
+        }
+    }
+
+    /// Create a metal of the given color.
+    pub fn metal(base_color: Color, roughness: Float) -> Self {
+        Self::new(base_color)
+            .with_metallic(1.0)
+            .with_roughness(roughness)
+    }
+
+    /// Create a clear glass with the given refractive index.
+    pub fn glass(ior: Float) -> Self {
+        Self::new(Color::white())
+            .with_transmission(1.0)
+            .with_roughness(0.0)
+            .with_ior(ior)
+    }
+
+    /// Set how metallic the surface is (0.0-1.0).
+    pub fn with_metallic(mut self, metallic: Float) -> Self {
+        self.metallic = metallic.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the microfacet roughness (0.0-1.0).
+    pub fn with_roughness(mut self, roughness: Float) -> Self {
+        self.roughness = roughness.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the dielectric specular level (0.0-1.0, 0.5 = 4% reflectance).
+    pub fn with_specular(mut self, specular: Float) -> Self {
+        self.specular = specular.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the transmission of the dielectric part (0.0-1.0).
+    pub fn with_transmission(mut self, transmission: Float) -> Self {
+        self.transmission = transmission.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Add a clear coat layer of the given strength and roughness (0.0-1.0).
+    pub fn with_clearcoat(mut self, clearcoat: Float, roughness: Float) -> Self {
+        self.clearcoat = clearcoat.clamp(0.0, 1.0);
+        self.clearcoat_roughness = roughness.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Add a grazing-angle sheen of the given strength, tinted towards the base color by `tint`.
+    pub fn with_sheen(mut self, sheen: Float, tint: Float) -> Self {
+        self.sheen = sheen.max(0.0);
+        self.sheen_tint = tint.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the refractive index.
+    pub fn with_ior(mut self, ior: Float) -> Self {
+        self.ior = ior;
+        self
+    }
+
+    /// Set the emitted radiance.
+    pub fn with_emission(mut self, emission: Color) -> Self {
+        self.emission = emission;
+        self
+    }
+}
+
+impl From<PrincipledMaterial> for Material {
+    /// Map the principled parameters onto diffuse, specular and transmission lobes
+    /// whose rates sum to 1.
+    fn from(principled: PrincipledMaterial) -> Self {
+        let metallic = principled.metallic.clamp(0.0, 1.0);
+        let transmission = principled.transmission.clamp(0.0, 1.0);
+        let reflectance = 0.08 * principled.specular.clamp(0.0, 1.0);
+
+        // Opaque dielectric: diffuse base under a thin specular layer
+        let opaque = (1.0 - metallic) * (1.0 - transmission);
+        let metal_rate = metallic;
+        let dielectric_specular = opaque * reflectance;
+        let specular_rate = metal_rate + dielectric_specular;
+        let diffuse_rate = opaque * (1.0 - reflectance);
+        let transmission_rate = (1.0 - metallic) * transmission;
+
+        // Metals reflect their base color, dielectrics reflect white
+        let specular_tint = if specular_rate > 0.0 {
+            (principled.base_color * metal_rate + Color::white() * dielectric_specular)
+                * (1.0 / specular_rate)
+        } else {
+            Color::white()
+        };
+
+        let luminance = principled.base_color.luminance();
+        let hue = if luminance > 0.0 {
+            principled.base_color * (1.0 / luminance)
+        } else {
+            Color::white()
+        };
+        let sheen_color =
+            Color::white() * (1.0 - principled.sheen_tint) + hue * principled.sheen_tint;
+
+        Material::new(
+            principled.base_color,
+            diffuse_rate,
+            specular_rate,
+            transmission_rate,
+            principled.ior,
+            Color::black(),
+        )
+        .with_fresnel(Fresnel::Exact)
+        .with_roughness(principled.roughness)
+        .with_specular_tint(specular_tint)
+        .with_coat(principled.clearcoat, principled.clearcoat_roughness)
+        .with_sheen(sheen_color * principled.sheen)
+        .with_emission(principled.emission)
+    }
+}
+
+impl From<Material> for PrincipledMaterial {
+    /// Describe an existing material (such as the `matte`, `mirror`, `glass` and `metal`
+    /// presets) with principled parameters.
+    ///
+    /// Rates are normalized by their sum, which is folded into the base color.
+    /// Legacy specular reflection is untinted, so a material that mixes diffuse and
+    /// specular becomes a metal tinted by its albedo. Absorption is not represented.
+    fn from(material: Material) -> Self {
+        let total = material.diffuse_rate + material.specular_rate + material.transmission_rate;
+        let mut principled = Self::new(Color::black())
+            .with_specular(0.0)
+            .with_roughness(material.roughness)
+            .with_ior(material.refractive_index)
+            .with_clearcoat(material.coat, material.coat_roughness)
+            .with_sheen(material.sheen.luminance(), 0.0)
+            .with_emission(material.emission);
+        if total <= 0.0 {
+            return principled;
+        }
+
+        let scale = total.min(1.0);
+        if material.transmission_rate > 0.0 {
+            // Dielectric: specular becomes the reflectance of the part that does not
+            // transmit. Reflectance above the principled maximum of 8% goes to a metallic
+            // share, so it never falls back to the diffuse lobe (clear glass stays clear).
+            let diffuse = material.diffuse_rate / total;
+            let specular = material.specular_rate / total;
+            let transmission = material.transmission_rate / total;
+            let reflectance = specular / (diffuse + specular).max(Float::MIN_POSITIVE);
+            if reflectance <= 0.08 {
+                principled.specular = reflectance / 0.08;
+                principled.transmission = transmission;
+            } else {
+                principled.specular = 1.0;
+                principled.metallic = specular - diffuse * 0.08 / 0.92;
+                principled.transmission = (transmission / (1.0 - principled.metallic)).min(1.0);
+            }
+        } else {
+            principled.metallic = material.specular_rate / total;
+        }
+        principled.base_color = if material.diffuse_rate > 0.0 {
+            material.albedo * scale
+        } else {
+            material.specular_tint * scale
+        };
+        principled
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -255,4 +782,168 @@ mod tests {
         let result = c1 * c2;
         assert_eq!(result, Color::new(0.1, 0.3, 0.4));
     }
+
+    #[test]
+    fn test_fresnel_reflectance() {
+        // Glass seen head-on reflects about 4%
+        let head_on = Fresnel::Exact.reflectance(1.0, 1.0 / 1.5);
+        assert!((head_on - 0.04).abs() < 1e-3);
+        assert!((Fresnel::Schlick.reflectance(1.0, 1.0 / 1.5) - head_on).abs() < 1e-4);
+
+        // Reflection grows towards grazing angles
+        let grazing = Fresnel::Exact.reflectance(0.05, 1.0 / 1.5);
+        assert!(grazing > 0.7);
+        let schlick = Fresnel::Schlick.reflectance(0.05, 1.0 / 1.5);
+        assert!((schlick - grazing).abs() < 0.05);
+
+        // Inside the glass beyond the critical angle everything is reflected
+        assert_eq!(Fresnel::Exact.reflectance(0.5, 1.5), 1.0);
+        assert_eq!(Fresnel::Schlick.reflectance(0.5, 1.5), 1.0);
+        assert_eq!(Fresnel::None.reflectance(1.0, 1.0 / 1.5), 0.0);
+    }
+
+    #[test]
+    fn test_principled_conversion_conserves_energy() {
+        let cases = [
+            PrincipledMaterial::new(Color::new(0.8, 0.2, 0.1)),
+            PrincipledMaterial::metal(Color::new(1.0, 0.8, 0.3), 0.2),
+            PrincipledMaterial::glass(1.5),
+            PrincipledMaterial::new(Color::white())
+                .with_metallic(0.3)
+                .with_transmission(0.5)
+                .with_specular(1.0),
+        ];
+        for principled in cases {
+            let material = Material::from(principled);
+            let total = material.diffuse_rate + material.specular_rate + material.transmission_rate;
+            assert!((total - 1.0).abs() < 1e-5);
+        }
+
+        // Plastic: 4% white specular over the colored diffuse base
+        let plastic = Material::from(PrincipledMaterial::new(Color::new(0.8, 0.2, 0.1)));
+        assert!((plastic.specular_rate - 0.04).abs() < 1e-6);
+        assert_eq!(plastic.specular_tint, Color::white());
+        assert_eq!(plastic.fresnel, Fresnel::Exact);
+
+        // Metals reflect their base color and have no diffuse lobe
+        let gold = Material::from(PrincipledMaterial::metal(Color::new(1.0, 0.8, 0.3), 0.2));
+        assert_eq!(gold.diffuse_rate, 0.0);
+        assert_eq!(gold.specular_tint, Color::new(1.0, 0.8, 0.3));
+        assert_eq!(gold.roughness, 0.2);
+
+        // Coat and sheen carry over
+        let velvet = Material::from(
+            PrincipledMaterial::new(Color::new(0.5, 0.0, 0.0))
+                .with_clearcoat(1.0, 0.1)
+                .with_sheen(1.0, 0.0),
+        );
+        assert_eq!(velvet.coat, 1.0);
+        assert_eq!(velvet.coat_roughness, 0.1);
+        assert_eq!(velvet.sheen, Color::white());
+    }
+
+    #[test]
+    fn test_principled_from_presets() {
+        let matte = PrincipledMaterial::from(Material::matte(Color::new(0.5, 0.6, 0.7), 0.8));
+        assert_eq!(matte.metallic, 0.0);
+        assert_eq!(matte.transmission, 0.0);
+        let back = Material::from(matte);
+        assert!((back.diffuse_rate - 1.0).abs() < 1e-6);
+        assert!((back.albedo.g - 0.48).abs() < 1e-6);
+
+        let mirror = PrincipledMaterial::from(Material::perfect_mirror());
+        assert_eq!(mirror.metallic, 1.0);
+        assert_eq!(mirror.base_color, Color::white());
+        let back = Material::from(mirror);
+        assert!((back.specular_rate - 1.0).abs() < 1e-6);
+        assert_eq!(back.specular_tint, Color::white());
+
+        let glass = PrincipledMaterial::from(Material::dielectric(1.33));
+        assert_eq!(glass.transmission, 1.0);
+        assert_eq!(glass.ior, 1.33);
+        let back = Material::from(glass);
+        assert_eq!(back.transmission_rate, 1.0);
+        assert_eq!(back.fresnel, Fresnel::Exact);
+
+        // Reflecting glass keeps its reflection specular, without a diffuse haze
+        let back = Material::from(PrincipledMaterial::from(Material::glass(0.9)));
+        assert_eq!(back.diffuse_rate, 0.0);
+        assert!((back.specular_rate - 0.1).abs() < 1e-6);
+        assert!((back.transmission_rate - 0.9).abs() < 1e-6);
+        assert_eq!(back.specular_tint, Color::white());
+
+        // Frosted glass with a little reflection uses the dielectric reflectance
+        let frosted = Material::new(Color::white(), 0.38, 0.02, 0.6, 1.5, Color::black());
+        let principled = PrincipledMaterial::from(frosted);
+        assert_eq!(principled.metallic, 0.0);
+        let back = Material::from(principled);
+        assert!((back.diffuse_rate - 0.38).abs() < 1e-6);
+        assert!((back.specular_rate - 0.02).abs() < 1e-6);
+        assert!((back.transmission_rate - 0.6).abs() < 1e-6);
+
+        let steel = PrincipledMaterial::from(Material::metal(Color::new(0.6, 0.6, 0.6), 0.6, 0.2));
+        assert!((steel.metallic - 0.75).abs() < 1e-6);
+        assert!((steel.base_color.r - 0.48).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_material_validation() {
+        assert!(Material::diffuse_surface().validate().is_ok());
+        assert!(Material::glass(0.9).validate().is_ok());
+        assert!(
+            Material::from(PrincipledMaterial::metal(Color::white(), 0.3))
+                .validate()
+                .is_ok()
+        );
+
+        let bright = Material::try_new(Color::white(), 1.0, 1.0, 1.0, 1.5, Color::black());
+        assert_eq!(
+            bright.unwrap_err(),
+            MaterialError::EnergyGain { total: 3.0 }
+        );
+
+        let negative = Material::try_new(Color::white(), -0.5, 0.5, 0.0, 1.0, Color::black());
+        assert!(matches!(
+            negative,
+            Err(MaterialError::RateOutOfRange {
+                field: "diffuse_rate",
+                ..
+            })
+        ));
+
+        let glowing_albedo = Material::matte(Color::new(2.0, 0.5, 0.5), 1.0);
+        assert!(matches!(
+            glowing_albedo.validate(),
+            Err(MaterialError::ColorOutOfRange {
+                field: "albedo",
+                ..
+            })
+        ));
+
+        // Emission is allowed to add light
+        assert!(
+            Material::emissive(Color::new(10.0, 10.0, 10.0))
+                .validate()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_material_normalized() {
+        let mut material = Material::matte(Color::new(1.5, 0.5, 0.2), 1.0);
+        material.specular_rate = 1.0;
+        material.transmission_rate = 2.0;
+        let material = material.with_sheen(Color::new(0.5, 0.8, 0.1));
+
+        let fixed = material.normalized();
+        assert!(fixed.validate().is_ok());
+        assert!((fixed.total_rate() - 1.0).abs() < 1e-6);
+        assert!((fixed.diffuse_rate - 1.0 / 3.0).abs() < 1e-6);
+        assert_eq!(fixed.albedo, Color::new(1.0, 0.5, 0.2));
+        assert_eq!(fixed.sheen, Color::new(0.0, 0.5, 0.1));
+
+        // Valid materials are left alone
+        let glass = Material::glass(0.9);
+        assert_eq!(glass.normalized().total_rate(), glass.total_rate());
+    }
 }
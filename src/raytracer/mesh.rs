@@ -1,8 +1,12 @@
 //! Mesh objects and primitives for the raytracer.
 
+use super::aabb::Aabb;
+use super::bvh::Bvh;
 use super::material::Material;
+use super::transform::{Transform, Transformable};
 use super::vector::{Float, Vec3};
 use super::{Intersection, Ray, Surface};
+use std::sync::Arc;
 
 const EPSILON: Float = 1e-8;
 
@@ -16,21 +20,52 @@ pub struct Triangle {
     pub v1: Vec3,
     /// Third vertex position
     pub v2: Vec3,
+    /// Optional per-vertex normals for smooth shading (None = flat shading)
+    pub normals: Option<[Vec3; 3]>,
+    /// Optional per-vertex texture coordinates (None = (0, 0), (1, 0) and (0, 1))
+    pub uvs: Option<[[Float; 2]; 3]>,
     /// Material of the triangle
     pub material: Material,
 }
 
 impl Triangle {
-    /// Create a new triangle from three vertices and a material.
+    /// Create a new flat-shaded triangle from three vertices and a material.
     pub fn new(v0: Vec3, v1: Vec3, v2: Vec3, material: Material) -> Self {
         Self {
             v0,
             v1,
             v2,
+            normals: None,
+            uvs: None,
             material,
         }
     }
 
+    /// Create a smooth-shaded triangle with a normal at each vertex.
+    /// Normals are interpolated across the face for shading.
+    pub fn with_normals(
+        v0: Vec3,
+        v1: Vec3,
+        v2: Vec3,
+        normals: [Vec3; 3],
+        material: Material,
+    ) -> Self {
+        Self {
+            v0,
+            v1,
+            v2,
+            normals: Some(normals.map(Vec3::normalize)),
+            uvs: None,
+            material,
+        }
+    }
+
+    /// Set texture coordinates at the three vertices.
+    pub fn with_uvs(mut self, uvs: [[Float; 2]; 3]) -> Self {
+        self.uvs = Some(uvs);
+        self
+    }
+
     /// Calculate the surface normal without normalization (faster if only direction matters).
     pub fn normal_unnormalized(&self) -> Vec3 {
         let edge1 = self.v1 - self.v0;
@@ -79,70 +114,403 @@ impl Triangle {
         // All should have the same sign for point to be inside
         (d0 >= 0.0 && d1 >= 0.0 && d2 >= 0.0) || (d0 <= 0.0 && d1 <= 0.0 && d2 <= 0.0)
     }
+}
+
+impl Surface for Triangle {
+    /// Calculate ray-triangle intersection using the Möller-Trumbore algorithm.
+    /// Returns the intersection if the ray hits this triangle, None otherwise.
+    /// With vertex normals, the shading normal is interpolated from the barycentric coordinates.
+    fn intersect(&self, ray: &Ray) -> Option<Intersection> {
+        let (t, u, v) = intersect_triangle(ray, self.v0, self.v1, self.v2)?;
+        let point = ray.at(t);
+        let (uv, dpdu, dpdv) = uv_frame([self.v0, self.v1, self.v2], self.uvs, u, v);
+        let intersection =
+            Intersection::new(t, point, self.normal(), self.material).with_uv(uv, dpdu, dpdv);
+        Some(match self.normals {
+            Some(normals) => intersection.with_shading_normal(interpolate_normal(normals, u, v)),
+            None => intersection,
+        })
+    }
+
+    /// Get the material of this triangle.
+    fn material(&self) -> Material {
+        self.material
+    }
 
     /// Get the bounding box (AABB) of the triangle.
-    /// Returns (min_point, max_point).
-    pub fn bounds(&self) -> (Vec3, Vec3) {
-        let min_x = self.v0.x.min(self.v1.x).min(self.v2.x);
-        let min_y = self.v0.y.min(self.v1.y).min(self.v2.y);
-        let min_z = self.v0.z.min(self.v1.z).min(self.v2.z);
+    fn bounds(&self) -> Aabb {
+        triangle_bounds(self.v0, self.v1, self.v2)
+    }
+}
+
+/// Ray-triangle intersection using the Möller-Trumbore algorithm.
+/// Returns (t, u, v) where u and v are the barycentric weights of `v1` and `v2`,
+/// or None if the ray misses the triangle.
+pub(crate) fn intersect_triangle(
+    ray: &Ray,
+    v0: Vec3,
+    v1: Vec3,
+    v2: Vec3,
+) -> Option<(Float, Float, Float)> {
+    let edge1 = v1 - v0;
+    let edge2 = v2 - v0;
+    let ray_cross_edge2 = ray.direction.cross(edge2);
+    let det = edge1.dot(ray_cross_edge2);
+
+    // If determinant is near zero, ray lies in the plane of the triangle
+    if det.abs() < EPSILON {
+        return None;
+    }
+
+    let inv_det = 1.0 / det;
+    let s = ray.origin - v0;
+    let u = inv_det * s.dot(ray_cross_edge2);
+
+    // u should be in [0, 1] for intersection
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+
+    let s_cross_edge1 = s.cross(edge1);
+    let v = inv_det * ray.direction.dot(s_cross_edge1);
+
+    // v should be in [0, 1] and u + v should be <= 1 for intersection
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+
+    let t = inv_det * edge2.dot(s_cross_edge1);
+
+    if t > 0.0 { Some((t, u, v)) } else { None }
+}
+
+/// Interpolate vertex normals with barycentric weights (u for the second, v for the third vertex).
+fn interpolate_normal(normals: [Vec3; 3], u: Float, v: Float) -> Vec3 {
+    (normals[0] * (1.0 - u - v) + normals[1] * u + normals[2] * v).normalize()
+}
 
-        let max_x = self.v0.x.max(self.v1.x).max(self.v2.x);
-        let max_y = self.v0.y.max(self.v1.y).max(self.v2.y);
-        let max_z = self.v0.z.max(self.v1.z).max(self.v2.z);
+/// Default texture coordinates of a triangle without UVs.
+const DEFAULT_UVS: [[Float; 2]; 3] = [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]];
+
+/// Interpolate texture coordinates at barycentric weights (u, v) and compute the
+/// derivatives of the position with respect to them.
+fn uv_frame(
+    positions: [Vec3; 3],
+    uvs: Option<[[Float; 2]; 3]>,
+    u: Float,
+    v: Float,
+) -> ([Float; 2], Vec3, Vec3) {
+    let uvs = uvs.unwrap_or(DEFAULT_UVS);
+    let w = 1.0 - u - v;
+    let uv = [
+        uvs[0][0] * w + uvs[1][0] * u + uvs[2][0] * v,
+        uvs[0][1] * w + uvs[1][1] * u + uvs[2][1] * v,
+    ];
+
+    // Solve dp1 = du1 * dpdu + dv1 * dpdv and dp2 = du2 * dpdu + dv2 * dpdv
+    let dp1 = positions[1] - positions[0];
+    let dp2 = positions[2] - positions[0];
+    let (du1, dv1) = (uvs[1][0] - uvs[0][0], uvs[1][1] - uvs[0][1]);
+    let (du2, dv2) = (uvs[2][0] - uvs[0][0], uvs[2][1] - uvs[0][1]);
+    let det = du1 * dv2 - dv1 * du2;
+    if det.abs() < EPSILON {
+        // Degenerate UVs: fall back to the triangle edges
+        return (uv, dp1, dp2);
+    }
+    let inv_det = 1.0 / det;
+    let dpdu = (dp1 * dv2 - dp2 * dv1) * inv_det;
+    let dpdv = (dp2 * du1 - dp1 * du2) * inv_det;
+    (uv, dpdu, dpdv)
+}
+
+/// Bounding box of the triangle spanned by three vertices.
+fn triangle_bounds(v0: Vec3, v1: Vec3, v2: Vec3) -> Aabb {
+    let min = Vec3::new(
+        v0.x.min(v1.x).min(v2.x),
+        v0.y.min(v1.y).min(v2.y),
+        v0.z.min(v1.z).min(v2.z),
+    );
+    let max = Vec3::new(
+        v0.x.max(v1.x).max(v2.x),
+        v0.y.max(v1.y).max(v2.y),
+        v0.z.max(v1.z).max(v2.z),
+    );
+    Aabb::new(min, max)
+}
+
+/// A single face of a `MeshData`, referencing shared buffers by index.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MeshFace {
+    /// Indices into `MeshData::positions`
+    pub positions: [u32; 3],
+    /// Indices into `MeshData::normals` (None if the face has no vertex normals)
+    pub normals: Option<[u32; 3]>,
+    /// Indices into `MeshData::uvs` (None if the face has no texture coordinates)
+    pub uvs: Option<[u32; 3]>,
+    /// Index into `MeshData::materials`
+    pub material: u32,
+}
 
+impl MeshFace {
+    /// Create a face with positions only, using the given material id.
+    pub fn new(positions: [u32; 3], material: u32) -> Self {
+        Self {
+            positions,
+            normals: None,
+            uvs: None,
+            material,
+        }
+    }
+}
+
+/// Shared vertex, normal, UV and material buffers of a triangle mesh.
+///
+/// Faces index into the buffers separately for positions, normals and UVs
+/// (like Wavefront OBJ), so shared vertices are stored only once.
+#[derive(Clone, Debug, Default)]
+pub struct MeshData {
+    /// Vertex positions
+    pub positions: Vec<Vec3>,
+    /// Vertex normals
+    pub normals: Vec<Vec3>,
+    /// Texture coordinates
+    pub uvs: Vec<[Float; 2]>,
+    /// Triangle faces
+    pub faces: Vec<MeshFace>,
+    /// Materials referenced by `MeshFace::material`
+    pub materials: Vec<Material>,
+}
+
+impl MeshData {
+    /// Create mesh data from positions and triangle indices, all faces sharing one material.
+    pub fn new(positions: Vec<Vec3>, indices: Vec<[u32; 3]>, material: Material) -> Self {
+        Self {
+            positions,
+            normals: Vec::new(),
+            uvs: Vec::new(),
+            faces: indices.into_iter().map(|i| MeshFace::new(i, 0)).collect(),
+            materials: vec![material],
+        }
+    }
+
+    /// Get the three vertex positions of a face.
+    pub fn face_positions(&self, face: usize) -> (Vec3, Vec3, Vec3) {
+        let [i0, i1, i2] = self.faces[face].positions;
         (
-            Vec3::new(min_x, min_y, min_z),
-            Vec3::new(max_x, max_y, max_z),
+            self.positions[i0 as usize],
+            self.positions[i1 as usize],
+            self.positions[i2 as usize],
         )
     }
+
+    /// Get the three vertex normals of a face, if it has any.
+    pub fn face_normals(&self, face: usize) -> Option<[Vec3; 3]> {
+        self.faces[face]
+            .normals
+            .map(|indices| indices.map(|i| self.normals[i as usize]))
+    }
+
+    /// Get the three texture coordinates of a face, if it has any.
+    pub fn face_uvs(&self, face: usize) -> Option<[[Float; 2]; 3]> {
+        self.faces[face]
+            .uvs
+            .map(|indices| indices.map(|i| self.uvs[i as usize]))
+    }
+
+    /// Generate smooth vertex normals for faces that have none.
+    /// Each position gets the area-weighted average of the normals of the faces around it.
+    pub fn compute_smooth_normals(&mut self) {
+        let mut accumulated = vec![Vec3::zero(); self.positions.len()];
+        for face in self.faces.iter().filter(|f| f.normals.is_none()) {
+            let [i0, i1, i2] = face.positions.map(|i| i as usize);
+            let p = (self.positions[i0], self.positions[i1], self.positions[i2]);
+            // Unnormalized cross product: its length is twice the face area
+            let weighted = (p.1 - p.0).cross(p.2 - p.0);
+            for i in [i0, i1, i2] {
+                accumulated[i] = accumulated[i] + weighted;
+            }
+        }
+
+        let base = self.normals.len() as u32;
+        self.normals
+            .extend(accumulated.into_iter().map(Vec3::normalize));
+        for face in self.faces.iter_mut().filter(|f| f.normals.is_none()) {
+            face.normals = Some(face.positions.map(|i| base + i));
+        }
+    }
+
+    /// Check that every face references existing buffer entries.
+    /// Returns a description of the first invalid reference found.
+    pub fn validate(&self) -> Result<(), String> {
+        let check = |indices: [u32; 3], len: usize, buffer: &str, face: usize| match indices
+            .iter()
+            .find(|&&i| i as usize >= len)
+        {
+            Some(i) => Err(format!(
+                "face {} references {} index {} but only {} exist",
+                face, buffer, i, len
+            )),
+            None => Ok(()),
+        };
+
+        for (face_idx, face) in self.faces.iter().enumerate() {
+            check(face.positions, self.positions.len(), "position", face_idx)?;
+            if let Some(normals) = face.normals {
+                check(normals, self.normals.len(), "normal", face_idx)?;
+            }
+            if let Some(uvs) = face.uvs {
+                check(uvs, self.uvs.len(), "uv", face_idx)?;
+            }
+            if face.material as usize >= self.materials.len() {
+                return Err(format!(
+                    "face {} references material {} but only {} exist",
+                    face_idx,
+                    face.material,
+                    self.materials.len()
+                ));
+            }
+        }
+        Ok(())
+    }
 }
 
-impl Surface for Triangle {
-    /// Calculate ray-triangle intersection using the Möller-Trumbore algorithm.
-    /// Returns the intersection if the ray hits this triangle, None otherwise.
+impl Transformable for MeshData {
+    fn transformed(&self, transform: &Transform) -> Self {
+        Self {
+            positions: self.positions.iter().map(|&p| transform.point(p)).collect(),
+            normals: self.normals.iter().map(|&n| transform.normal(n)).collect(),
+            ..self.clone()
+        }
+    }
+}
+
+/// A lightweight handle to one face of a shared mesh.
+/// Used as the primitive type of the mesh's internal BVH.
+#[derive(Clone, Debug)]
+pub struct MeshTriangle {
+    /// Shared mesh buffers
+    mesh: Arc<MeshData>,
+    /// Index of the face within the mesh
+    face: u32,
+}
+
+impl MeshTriangle {
+    /// Index of the face within its mesh.
+    pub fn face(&self) -> usize {
+        self.face as usize
+    }
+}
+
+impl Surface for MeshTriangle {
     fn intersect(&self, ray: &Ray) -> Option<Intersection> {
-        let edge1 = self.v1 - self.v0;
-        let edge2 = self.v2 - self.v0;
-        let ray_cross_edge2 = ray.direction.cross(edge2);
-        let det = edge1.dot(ray_cross_edge2);
+        let (v0, v1, v2) = self.mesh.face_positions(self.face as usize);
+        let (t, u, v) = intersect_triangle(ray, v0, v1, v2)?;
+        let point = ray.at(t);
+        let normal = (v1 - v0).cross(v2 - v0).normalize();
+        let uvs = self.mesh.face_uvs(self.face as usize);
+        let (uv, dpdu, dpdv) = uv_frame([v0, v1, v2], uvs, u, v);
+        let intersection =
+            Intersection::new(t, point, normal, self.material()).with_uv(uv, dpdu, dpdv);
+        Some(match self.mesh.face_normals(self.face as usize) {
+            Some(normals) => intersection.with_shading_normal(interpolate_normal(normals, u, v)),
+            None => intersection,
+        })
+    }
 
-        // If determinant is near zero, ray lies in the plane of the triangle
-        if det.abs() < EPSILON {
-            return None;
-        }
+    fn material(&self) -> Material {
+        let face = &self.mesh.faces[self.face as usize];
+        self.mesh.materials[face.material as usize]
+    }
 
-        let inv_det = 1.0 / det;
-        let s = ray.origin - self.v0;
-        let u = inv_det * s.dot(ray_cross_edge2);
+    fn bounds(&self) -> Aabb {
+        let (v0, v1, v2) = self.mesh.face_positions(self.face as usize);
+        triangle_bounds(v0, v1, v2)
+    }
+}
 
-        // u should be in [0, 1] for intersection
-        if u < 0.0 || u > 1.0 {
-            return None;
+/// A triangle mesh with indexed vertex buffers shared by all faces.
+///
+/// Stores each vertex once and each face as a handful of indices, and
+/// accelerates intersection with an internal BVH over its faces.
+/// The whole mesh acts as a single `Surface`.
+pub struct TriangleMesh {
+    /// Shared mesh buffers
+    data: Arc<MeshData>,
+    /// Hierarchy over the faces of the mesh
+    bvh: Bvh<MeshTriangle>,
+}
+
+impl TriangleMesh {
+    /// Create a mesh from positions and triangle indices with a single material.
+    ///
+    /// # Panics
+    /// Panics if an index is out of range.
+    pub fn new(positions: Vec<Vec3>, indices: Vec<[u32; 3]>, material: Material) -> Self {
+        Self::from_data(MeshData::new(positions, indices, material))
+    }
+
+    /// Create a mesh from prepared mesh buffers.
+    ///
+    /// # Panics
+    /// Panics if a face references a missing position, normal, UV or material.
+    pub fn from_data(data: MeshData) -> Self {
+        if let Err(message) = data.validate() {
+            panic!("Invalid mesh data: {}", message);
         }
 
-        let s_cross_edge1 = s.cross(edge1);
-        let v = inv_det * ray.direction.dot(s_cross_edge1);
+        let data = Arc::new(data);
+        let triangles = (0..data.faces.len() as u32)
+            .map(|face| MeshTriangle {
+                mesh: Arc::clone(&data),
+                face,
+            })
+            .collect();
 
-        // v should be in [0, 1] and u + v should be <= 1 for intersection
-        if v < 0.0 || u + v > 1.0 {
-            return None;
+        Self {
+            bvh: Bvh::new(triangles),
+            data,
         }
+    }
 
-        let t = inv_det * edge2.dot(s_cross_edge1);
+    /// Get the shared mesh buffers.
+    pub fn data(&self) -> &MeshData {
+        &self.data
+    }
 
-        if t > 0.0 {
-            let point = ray.at(t);
-            let normal = self.normal();
-            Some(Intersection::new(t, point, normal, self.material))
-        } else {
-            None
+    /// Number of triangles in the mesh.
+    pub fn face_count(&self) -> usize {
+        self.data.faces.len()
+    }
+
+    /// Build a standalone `Triangle` copy of one face.
+    pub fn triangle(&self, face: usize) -> Triangle {
+        let (v0, v1, v2) = self.data.face_positions(face);
+        let material = self.data.materials[self.data.faces[face].material as usize];
+        let triangle = match self.data.face_normals(face) {
+            Some(normals) => Triangle::with_normals(v0, v1, v2, normals, material),
+            None => Triangle::new(v0, v1, v2, material),
+        };
+        Triangle {
+            uvs: self.data.face_uvs(face),
+            ..triangle
         }
     }
+}
 
-    /// Get the material of this triangle.
+impl Surface for TriangleMesh {
+    /// Find the closest face hit by the ray using the mesh BVH.
+    fn intersect(&self, ray: &Ray) -> Option<Intersection> {
+        self.bvh.intersect(ray)
+    }
+
+    /// Get the material of the first face of the mesh.
     fn material(&self) -> Material {
-        self.material
+        self.bvh.material()
+    }
+
+    /// Get the bounding box (AABB) of the whole mesh.
+    fn bounds(&self) -> Aabb {
+        self.bvh.bounds()
     }
 }
 
@@ -244,4 +612,175 @@ mod tests {
         let retrieved_material = triangle.material();
         assert_eq!(retrieved_material.albedo, Color::white());
     }
+
+    #[test]
+    fn test_triangle_bounds() {
+        let material = Material::matte(Color::white(), 0.8);
+        let v0 = Vec3::new(0.0, -1.0, 2.0);
+        let v1 = Vec3::new(1.0, 0.0, -2.0);
+        let v2 = Vec3::new(-3.0, 1.0, 0.0);
+        let triangle = Triangle::new(v0, v1, v2, material);
+
+        let bounds = triangle.bounds();
+        assert_eq!(bounds.min, Vec3::new(-3.0, -1.0, -2.0));
+        assert_eq!(bounds.max, Vec3::new(1.0, 1.0, 2.0));
+    }
+
+    /// Unit square in the z = 0 plane made of two triangles sharing an edge.
+    fn unit_square(material: Material) -> TriangleMesh {
+        let positions = vec![
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(1.0, 1.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+        ];
+        TriangleMesh::new(positions, vec![[0, 1, 2], [0, 2, 3]], material)
+    }
+
+    #[test]
+    fn test_mesh_creation() {
+        let mesh = unit_square(Material::matte(Color::white(), 0.8));
+
+        assert_eq!(mesh.face_count(), 2);
+        assert_eq!(mesh.data().positions.len(), 4);
+        assert_eq!(mesh.triangle(1).v2, Vec3::new(0.0, 1.0, 0.0));
+
+        let bounds = mesh.bounds();
+        assert_eq!(bounds.min, Vec3::new(0.0, 0.0, 0.0));
+        assert_eq!(bounds.max, Vec3::new(1.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn test_mesh_intersect() {
+        let mesh = unit_square(Material::matte(Color::white(), 0.8));
+
+        let hit = Ray::new(Vec3::new(0.25, 0.75, 2.0), Vec3::new(0.0, 0.0, -1.0));
+        let intersection = mesh.intersect(&hit).expect("Expected intersection");
+        assert!((intersection.t - 2.0).abs() < 1e-5);
+        assert_eq!(intersection.normal, Vec3::new(0.0, 0.0, 1.0));
+
+        let miss = Ray::new(Vec3::new(1.5, 0.5, 2.0), Vec3::new(0.0, 0.0, -1.0));
+        assert!(mesh.intersect(&miss).is_none());
+    }
+
+    #[test]
+    fn test_mesh_matches_triangles() {
+        let mesh = unit_square(Material::matte(Color::white(), 0.8));
+        let ray = Ray::new(Vec3::new(0.8, 0.1, 1.0), Vec3::new(-0.1, 0.2, -1.0));
+
+        let from_mesh = mesh.intersect(&ray).expect("Expected intersection");
+        let from_triangle = mesh
+            .triangle(0)
+            .intersect(&ray)
+            .expect("Expected intersection");
+        assert_eq!(from_mesh.t, from_triangle.t);
+        assert_eq!(from_mesh.point, from_triangle.point);
+    }
+
+    #[test]
+    fn test_mesh_per_face_materials() {
+        let mut data = MeshData::new(
+            vec![
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(1.0, 0.0, 0.0),
+                Vec3::new(1.0, 1.0, 0.0),
+                Vec3::new(0.0, 1.0, 0.0),
+            ],
+            vec![[0, 1, 2], [0, 2, 3]],
+            Material::matte(Color::red(), 0.8),
+        );
+        data.materials.push(Material::matte(Color::blue(), 0.8));
+        data.faces[1].material = 1;
+        let mesh = TriangleMesh::from_data(data);
+
+        let ray = Ray::new(Vec3::new(0.2, 0.8, 1.0), Vec3::new(0.0, 0.0, -1.0));
+        let intersection = mesh.intersect(&ray).expect("Expected intersection");
+        assert_eq!(intersection.material.albedo, Color::blue());
+    }
+
+    #[test]
+    fn test_mesh_data_validate() {
+        let mut data = MeshData::new(
+            vec![Vec3::zero(), Vec3::zero(), Vec3::zero()],
+            vec![[0, 1, 2]],
+            Material::diffuse_surface(),
+        );
+        assert!(data.validate().is_ok());
+
+        data.faces[0].positions = [0, 1, 3];
+        assert!(data.validate().is_err());
+
+        data.faces[0].positions = [0, 1, 2];
+        data.faces[0].material = 1;
+        assert!(data.validate().is_err());
+    }
+
+    #[test]
+    fn test_triangle_smooth_normal() {
+        let material = Material::matte(Color::white(), 0.8);
+        let triangle = Triangle::with_normals(
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            [
+                Vec3::new(0.0, 0.0, 1.0),
+                Vec3::new(1.0, 0.0, 1.0),
+                Vec3::new(0.0, 1.0, 1.0),
+            ],
+            material,
+        );
+
+        // At the first vertex the shading normal equals its vertex normal
+        let ray = Ray::new(Vec3::new(0.0, 0.0, 1.0), Vec3::new(0.0, 0.0, -1.0));
+        let hit = triangle.intersect(&ray).expect("Expected intersection");
+        assert!((hit.normal - Vec3::new(0.0, 0.0, 1.0)).length() < 1e-5);
+
+        // Towards the second vertex it tilts, while the geometric normal stays flat
+        let ray = Ray::new(Vec3::new(0.9, 0.05, 1.0), Vec3::new(0.0, 0.0, -1.0));
+        let hit = triangle.intersect(&ray).expect("Expected intersection");
+        assert!(hit.normal.x > 0.5);
+        assert!((hit.normal.length() - 1.0).abs() < 1e-5);
+        assert_eq!(hit.geometric_normal, Vec3::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn test_triangle_geometric_normal_follows_shading_side() {
+        let material = Material::matte(Color::white(), 0.8);
+        let down = Vec3::new(0.0, 0.0, -1.0);
+        let triangle = Triangle::with_normals(
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            [down, down, down],
+            material,
+        );
+
+        let ray = Ray::new(Vec3::new(0.2, 0.2, 1.0), Vec3::new(0.0, 0.0, -1.0));
+        let hit = triangle.intersect(&ray).expect("Expected intersection");
+        assert_eq!(hit.normal, down);
+        assert_eq!(hit.geometric_normal, down);
+    }
+
+    #[test]
+    fn test_mesh_compute_smooth_normals() {
+        // Two faces folded along the shared edge (0, 2)
+        let mut data = MeshData::new(
+            vec![
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(1.0, 0.0, 0.0),
+                Vec3::new(0.0, 1.0, 0.0),
+                Vec3::new(-1.0, 0.0, 1.0),
+            ],
+            vec![[0, 1, 2], [0, 2, 3]],
+            Material::diffuse_surface(),
+        );
+        data.compute_smooth_normals();
+        assert!(data.validate().is_ok());
+
+        let normals = data.face_normals(0).expect("Expected normals");
+        // Shared vertex normal lies between both face normals
+        assert!(normals[0].x > 0.0 && normals[0].z > 0.0);
+        // Unshared vertex keeps its face normal
+        assert!((normals[1] - Vec3::new(0.0, 0.0, 1.0)).length() < 1e-5);
+    }
 }
@@ -1,16 +1,32 @@
 //! Raytracer module with geometric types, camera, mesh primitives, and rendering utilities.
 
+pub mod aabb;
+pub mod bvh;
 pub mod camera;
+pub mod environment;
 pub mod image;
+pub mod instance;
 pub mod light;
 pub mod material;
 pub mod mesh;
+pub mod microfacet;
+pub mod obj;
 pub mod raytracer;
+pub mod sampling;
+pub mod scene;
+pub mod scene_file;
+pub mod scene_graph;
+pub mod sky;
 pub mod sphere;
+pub mod texture;
+pub mod transform;
 pub mod vector;
 
-use crate::raytracer::material::Material;
+use crate::raytracer::aabb::Aabb;
+use crate::raytracer::material::{Color, Material};
+use crate::raytracer::sampling::orthonormal_basis;
 use crate::raytracer::vector::{Float, Vec3};
+use std::sync::Arc;
 
 /// A ray in 3D space, defined by an origin point and a direction vector.
 #[derive(Copy, Clone, Debug)]
@@ -19,6 +35,8 @@ pub struct Ray {
     pub origin: Vec3,
     /// Direction vector of the ray (should be normalized)
     pub direction: Vec3,
+    /// Distance the path travelled before this ray's origin (0 for camera rays)
+    pub travelled: Float,
 }
 
 impl Ray {
@@ -27,9 +45,16 @@ impl Ray {
         Self {
             origin,
             direction: direction.normalize(),
+            travelled: 0.0,
         }
     }
 
+    /// Set the distance the path travelled before this ray's origin.
+    pub fn with_travelled(mut self, travelled: Float) -> Self {
+        self.travelled = travelled;
+        self
+    }
+
     /// Get a point along the ray at parameter t.
     /// point(t) = origin + t * direction
     pub fn at(&self, t: Float) -> Vec3 {
@@ -44,22 +69,104 @@ pub struct Intersection {
     pub t: Float,
     /// Position of the intersection point
     pub point: Vec3,
-    /// Surface normal at the intersection point
+    /// Shading normal at the intersection point (interpolated for smooth surfaces)
     pub normal: Vec3,
+    /// True geometric normal of the surface, used to offset secondary ray origins
+    pub geometric_normal: Vec3,
     /// Material at the intersection point
     pub material: Material,
+    /// Texture coordinates at the intersection point
+    pub uv: [Float; 2],
+    /// Partial derivative of the position with respect to the texture coordinate u
+    pub dpdu: Vec3,
+    /// Partial derivative of the position with respect to the texture coordinate v
+    pub dpdv: Vec3,
+    /// Unit tangent perpendicular to the shading normal, along increasing u
+    /// (zero until `compute_tangent_frame` is called)
+    pub tangent: Vec3,
+    /// Unit bitangent completing the shading frame, towards increasing v
+    /// (zero until `compute_tangent_frame` is called)
+    pub bitangent: Vec3,
 }
 
 impl Intersection {
-    /// Create a new intersection.
+    /// Create a new intersection whose shading normal equals the geometric normal.
+    /// The tangent frame is left empty: surfaces report many candidate hits per ray,
+    /// and only the closest one needs a frame.
     pub fn new(t: Float, point: Vec3, normal: Vec3, material: Material) -> Self {
         Self {
             t,
             point,
             normal,
+            geometric_normal: normal,
             material,
+            uv: [0.0, 0.0],
+            dpdu: Vec3::zero(),
+            dpdv: Vec3::zero(),
+            tangent: Vec3::zero(),
+            bitangent: Vec3::zero(),
+        }
+    }
+
+    /// Set the texture coordinates and their position derivatives.
+    pub fn with_uv(mut self, uv: [Float; 2], dpdu: Vec3, dpdv: Vec3) -> Self {
+        self.uv = uv;
+        self.dpdu = dpdu;
+        self.dpdv = dpdv;
+        self
+    }
+
+    /// Replace the shading normal, keeping the geometric normal.
+    /// The geometric normal is flipped if needed so both lie in the same hemisphere.
+    pub fn with_shading_normal(mut self, normal: Vec3) -> Self {
+        if normal.dot(self.geometric_normal) < 0.0 {
+            self.geometric_normal = -self.geometric_normal;
         }
+        self.normal = normal;
+        self
     }
+
+    /// Tilt the shading normal (normal or bump mapping), keeping the geometric normal.
+    /// The new normal is flipped if needed to stay on the side of the previous one.
+    pub fn perturb_normal(&mut self, normal: Vec3) {
+        let normal = normal.normalize();
+        self.normal = if normal.dot(self.normal) < 0.0 {
+            -normal
+        } else {
+            normal
+        };
+    }
+
+    /// Build the tangent frame around the shading normal, aligned with `dpdu` and
+    /// `dpdv` (Gram-Schmidt), or arbitrary if the hit has no texture derivatives.
+    pub fn compute_tangent_frame(&mut self) {
+        let tangent = self.dpdu - self.normal * self.normal.dot(self.dpdu);
+        if tangent.length_squared() > 1e-12 {
+            self.tangent = tangent.normalize();
+            let bitangent = self.normal.cross(self.tangent);
+            // Mirrored UV mappings flip the bitangent
+            self.bitangent = if bitangent.dot(self.dpdv) < 0.0 {
+                -bitangent
+            } else {
+                bitangent
+            };
+        } else {
+            (self.tangent, self.bitangent) = orthonormal_basis(self.normal);
+        }
+    }
+}
+
+/// Kind of interaction that produced a branched ray.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BranchKind {
+    /// Diffuse (matte) scattering
+    Diffuse,
+    /// Mirror-like reflection, including total internal reflection
+    Specular,
+    /// Rough (microfacet) reflection, also covered by direct lighting
+    Glossy,
+    /// Refraction through the surface
+    Transmission,
 }
 
 /// A branched ray generated from a ray-surface interaction.
@@ -68,21 +175,29 @@ impl Intersection {
 pub struct BranchedRay {
     /// The new ray after interaction
     pub ray: Ray,
+    /// Kind of interaction that produced this branch
+    pub kind: BranchKind,
     /// Weight of this branch (reflection/transmission rate)
     pub weight: Float,
+    /// Color filter of this branch (white except for tinted reflections such as metals)
+    pub tint: Color,
     /// Material the ray is passing through after the interaction
     pub passing_material: Material,
 }
 
 /// Trait for ray-surface intersection detection.
 /// Any geometry that can be intersected by rays should implement this trait.
-pub trait Surface {
+/// Surfaces are shared between render threads, so they must be `Send + Sync`.
+pub trait Surface: Send + Sync {
     /// Calculate ray-surface intersection.
     /// Returns the closest intersection if the ray hits this surface, None otherwise.
     fn intersect(&self, ray: &Ray) -> Option<Intersection>;
 
     /// Get the material of this surface.
     fn material(&self) -> Material;
+
+    /// Get the axis-aligned bounding box enclosing this surface.
+    fn bounds(&self) -> Aabb;
 }
 
 // Implement Surface for references to trait objects
@@ -94,4 +209,23 @@ impl<'a> Surface for &'a (dyn Surface + 'a) {
     fn material(&self) -> Material {
         (*self).material()
     }
+
+    fn bounds(&self) -> Aabb {
+        (*self).bounds()
+    }
+}
+
+// Implement Surface for shared trait objects
+impl<S: Surface + ?Sized> Surface for Arc<S> {
+    fn intersect(&self, ray: &Ray) -> Option<Intersection> {
+        (**self).intersect(ray)
+    }
+
+    fn material(&self) -> Material {
+        (**self).material()
+    }
+
+    fn bounds(&self) -> Aabb {
+        (**self).bounds()
+    }
 }
@@ -0,0 +1,661 @@
+//! Wavefront OBJ/MTL importer producing triangle meshes.
+
+use super::material::{Color, Fresnel, Material};
+use super::mesh::{MeshData, MeshFace, TriangleMesh};
+use super::vector::{Float, Vec3};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Error produced while loading OBJ or MTL files.
+#[derive(Debug)]
+pub enum ObjError {
+    /// A file could not be read
+    Io {
+        /// Path of the file that failed to load
+        path: PathBuf,
+        /// Underlying I/O error
+        error: std::io::Error,
+    },
+    /// A line could not be parsed
+    Parse {
+        /// Name of the file containing the error
+        file: String,
+        /// Line number (1-based)
+        line: usize,
+        /// Description of the problem
+        message: String,
+    },
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjError::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+            ObjError::Parse {
+                file,
+                line,
+                message,
+            } => write!(f, "{}:{}: {}", file, line, message),
+        }
+    }
+}
+
+impl std::error::Error for ObjError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ObjError::Io { error, .. } => Some(error),
+            ObjError::Parse { .. } => None,
+        }
+    }
+}
+
+/// A named group (`g` or `o` statement) of an OBJ file converted to a mesh.
+pub struct ObjGroup {
+    /// Group or object name ("default" for faces outside any group)
+    pub name: String,
+    /// Triangulated geometry of the group
+    pub mesh: TriangleMesh,
+}
+
+/// Load an OBJ file and the MTL libraries it references.
+/// Material libraries are resolved relative to the OBJ file's directory.
+///
+/// # Returns
+/// One mesh per non-empty group, in file order
+pub fn load_obj(path: impl AsRef<Path>) -> Result<Vec<ObjGroup>, ObjError> {
+    let path = path.as_ref();
+    let source = read_file(path)?;
+    let directory = path.parent().unwrap_or(Path::new(""));
+
+    parse_obj(&source, &file_name(path), |library| {
+        let mtl_path = directory.join(library);
+        let mtl_source = read_file(&mtl_path)?;
+        parse_mtl(&mtl_source, &file_name(&mtl_path))
+    })
+}
+
+/// Parse OBJ source text.
+///
+/// # Arguments
+/// * `source` - Contents of the OBJ file
+/// * `file` - File name used in error messages
+/// * `load_library` - Called with each `mtllib` name; returns the materials it defines
+pub fn parse_obj(
+    source: &str,
+    file: &str,
+    mut load_library: impl FnMut(&str) -> Result<HashMap<String, Material>, ObjError>,
+) -> Result<Vec<ObjGroup>, ObjError> {
+    let mut positions: Vec<Vec3> = Vec::new();
+    let mut normals: Vec<Vec3> = Vec::new();
+    let mut uvs: Vec<[Float; 2]> = Vec::new();
+    let mut library: HashMap<String, Material> = HashMap::new();
+
+    let mut groups: Vec<GroupBuilder> = vec![GroupBuilder::new("default")];
+    let mut current_material: Option<String> = None;
+
+    for (line_idx, raw_line) in source.lines().enumerate() {
+        let line_number = line_idx + 1;
+        let error = |message: String| ObjError::Parse {
+            file: file.to_string(),
+            line: line_number,
+            message,
+        };
+
+        let line = raw_line.split('#').next().unwrap_or("").trim();
+        let mut tokens = line.split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            continue;
+        };
+        let args: Vec<&str> = tokens.collect();
+
+        match keyword {
+            "v" => positions.push(parse_vec3(&args).map_err(error)?),
+            "vn" => normals.push(parse_vec3(&args).map_err(error)?),
+            "vt" => {
+                if args.is_empty() {
+                    return Err(error("texture coordinate needs at least 1 value".into()));
+                }
+                let u = parse_float(args[0]).map_err(error)?;
+                let v = match args.get(1) {
+                    Some(value) => parse_float(value).map_err(error)?,
+                    None => 0.0,
+                };
+                uvs.push([u, v]);
+            }
+            "f" => {
+                if args.len() < 3 {
+                    return Err(error(format!(
+                        "face needs at least 3 vertices, found {}",
+                        args.len()
+                    )));
+                }
+                let counts = (positions.len(), uvs.len(), normals.len());
+                let vertices = args
+                    .iter()
+                    .map(|token| parse_face_vertex(token, counts))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(error)?;
+
+                let group = groups.last_mut().expect("at least one group");
+                let material = group.material_id(current_material.as_deref());
+                // Fan triangulation around the first vertex
+                for i in 1..vertices.len() - 1 {
+                    group.push_triangle(
+                        [vertices[0], vertices[i], vertices[i + 1]],
+                        material,
+                        (&positions, &uvs, &normals),
+                    );
+                }
+            }
+            "g" | "o" => {
+                let name = if args.is_empty() {
+                    "default".to_string()
+                } else {
+                    args.join(" ")
+                };
+                groups.push(GroupBuilder::new(&name));
+            }
+            "usemtl" => {
+                if args.is_empty() {
+                    return Err(error("usemtl needs a material name".into()));
+                }
+                current_material = Some(args.join(" "));
+            }
+            "mtllib" => {
+                for name in &args {
+                    library.extend(load_library(name)?);
+                }
+            }
+            // Smoothing groups, lines, curves etc. do not affect triangle meshes
+            _ => {}
+        }
+    }
+
+    Ok(groups
+        .into_iter()
+        .filter(|group| !group.faces.is_empty())
+        .map(|group| group.build(&library))
+        .collect())
+}
+
+/// Parse MTL source text into materials keyed by name.
+///
+/// Supported statements are mapped onto `Material` fields:
+/// - `Kd` → albedo
+/// - `Ks` → specular_rate (channel average)
+/// - `Ni` → refractive_index
+/// - `d` / `Tr` → transmission_rate (as 1 - dissolve)
+/// - `Tf` → transmission_rate (channel average) for refractive `illum` models (4, 6, 7, 9)
+/// - `illum` 5 / 7 → exact Fresnel reflection
+/// - `Pr` → roughness (PBR extension)
+/// - `Ke` → emission
+///
+/// The remaining energy goes to diffuse_rate.
+pub fn parse_mtl(source: &str, file: &str) -> Result<HashMap<String, Material>, ObjError> {
+    let mut materials = HashMap::new();
+    let mut current: Option<(String, MtlBuilder)> = None;
+
+    for (line_idx, raw_line) in source.lines().enumerate() {
+        let line_number = line_idx + 1;
+        let error = |message: String| ObjError::Parse {
+            file: file.to_string(),
+            line: line_number,
+            message,
+        };
+
+        let line = raw_line.split('#').next().unwrap_or("").trim();
+        let mut tokens = line.split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            continue;
+        };
+        let args: Vec<&str> = tokens.collect();
+
+        if keyword == "newmtl" {
+            if args.is_empty() {
+                return Err(error("newmtl needs a material name".into()));
+            }
+            if let Some((name, builder)) = current.take() {
+                materials.insert(name, builder.build());
+            }
+            current = Some((args.join(" "), MtlBuilder::default()));
+            continue;
+        }
+
+        let Some((_, builder)) = current.as_mut() else {
+            return Err(error(format!("'{}' before any newmtl", keyword)));
+        };
+
+        match keyword {
+            "Kd" => builder.diffuse = Some(parse_color(&args).map_err(error)?),
+            "Ks" => builder.specular = Some(parse_color(&args).map_err(error)?),
+            "Tf" => builder.transmission_filter = Some(parse_color(&args).map_err(error)?),
+            "Ke" => builder.emission = Some(parse_color(&args).map_err(error)?),
+            "Ni" => builder.refractive_index = Some(parse_single(&args).map_err(error)?),
+            "Pr" => builder.roughness = Some(parse_single(&args).map_err(error)?),
+            "d" => builder.dissolve = Some(parse_single(&args).map_err(error)?),
+            "Tr" => builder.dissolve = Some(1.0 - parse_single(&args).map_err(error)?),
+            "illum" => builder.illum = parse_single(&args).map_err(error)? as u32,
+            // Ambient, texture maps etc. are not used by the renderer
+            _ => {}
+        }
+    }
+
+    if let Some((name, builder)) = current {
+        materials.insert(name, builder.build());
+    }
+    Ok(materials)
+}
+
+/// Raw MTL values collected for one material.
+#[derive(Default)]
+struct MtlBuilder {
+    diffuse: Option<Color>,
+    specular: Option<Color>,
+    transmission_filter: Option<Color>,
+    emission: Option<Color>,
+    refractive_index: Option<Float>,
+    roughness: Option<Float>,
+    dissolve: Option<Float>,
+    illum: u32,
+}
+
+impl MtlBuilder {
+    /// Convert the collected MTL values into a `Material`.
+    fn build(&self) -> Material {
+        let average = |c: Color| (c.r + c.g + c.b) / 3.0;
+
+        let albedo = self.diffuse.unwrap_or(Color::white());
+        let specular_rate = self.specular.map_or(0.0, average);
+        let refractive = matches!(self.illum, 4 | 6 | 7 | 9);
+        let transmission_rate = match (refractive, self.transmission_filter) {
+            (true, Some(filter)) => average(filter),
+            _ => 1.0 - self.dissolve.unwrap_or(1.0),
+        }
+        .clamp(0.0, 1.0);
+        let specular_rate = specular_rate.min(1.0 - transmission_rate);
+        let diffuse_rate = (1.0 - specular_rate - transmission_rate).max(0.0);
+
+        Material::new(
+            albedo,
+            diffuse_rate,
+            specular_rate,
+            transmission_rate,
+            self.refractive_index.unwrap_or(1.0),
+            Color::black(),
+        )
+        .with_emission(self.emission.unwrap_or(Color::black()))
+        .with_roughness(self.roughness.unwrap_or(0.0))
+        .with_fresnel(if matches!(self.illum, 5 | 7) {
+            Fresnel::Exact
+        } else {
+            Fresnel::None
+        })
+    }
+}
+
+/// Faces of one group, with buffers compacted to the vertices the group uses.
+struct GroupBuilder {
+    name: String,
+    data: MeshData,
+    faces: Vec<MeshFace>,
+    material_names: Vec<Option<String>>,
+    position_map: HashMap<usize, u32>,
+    uv_map: HashMap<usize, u32>,
+    normal_map: HashMap<usize, u32>,
+}
+
+/// Zero-based (position, uv, normal) indices of one face vertex.
+type FaceVertex = (usize, Option<usize>, Option<usize>);
+
+impl GroupBuilder {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            data: MeshData::default(),
+            faces: Vec::new(),
+            material_names: Vec::new(),
+            position_map: HashMap::new(),
+            uv_map: HashMap::new(),
+            normal_map: HashMap::new(),
+        }
+    }
+
+    /// Get the local material id for a `usemtl` name, registering it if new.
+    fn material_id(&mut self, name: Option<&str>) -> u32 {
+        let name = name.map(str::to_string);
+        match self.material_names.iter().position(|n| *n == name) {
+            Some(id) => id as u32,
+            None => {
+                self.material_names.push(name);
+                (self.material_names.len() - 1) as u32
+            }
+        }
+    }
+
+    /// Append a triangle, copying referenced vertices into the group's buffers.
+    fn push_triangle(
+        &mut self,
+        vertices: [FaceVertex; 3],
+        material: u32,
+        buffers: (&[Vec3], &[[Float; 2]], &[Vec3]),
+    ) {
+        let (positions, uvs, normals) = buffers;
+        let remap = |map: &mut HashMap<usize, u32>, index: usize, len: usize| {
+            *map.entry(index).or_insert(len as u32)
+        };
+
+        let mut face = MeshFace::new([0; 3], material);
+        let has_uvs = vertices.iter().all(|v| v.1.is_some());
+        let has_normals = vertices.iter().all(|v| v.2.is_some());
+        let mut uv_indices = [0; 3];
+        let mut normal_indices = [0; 3];
+
+        for (corner, &(p, uv, n)) in vertices.iter().enumerate() {
+            face.positions[corner] = remap(&mut self.position_map, p, self.data.positions.len());
+            if face.positions[corner] as usize == self.data.positions.len() {
+                self.data.positions.push(positions[p]);
+            }
+            if let (true, Some(uv)) = (has_uvs, uv) {
+                uv_indices[corner] = remap(&mut self.uv_map, uv, self.data.uvs.len());
+                if uv_indices[corner] as usize == self.data.uvs.len() {
+                    self.data.uvs.push(uvs[uv]);
+                }
+            }
+            if let (true, Some(n)) = (has_normals, n) {
+                normal_indices[corner] = remap(&mut self.normal_map, n, self.data.normals.len());
+                if normal_indices[corner] as usize == self.data.normals.len() {
+                    self.data.normals.push(normals[n]);
+                }
+            }
+        }
+
+        face.uvs = has_uvs.then_some(uv_indices);
+        face.normals = has_normals.then_some(normal_indices);
+        self.faces.push(face);
+    }
+
+    /// Resolve material names and build the mesh.
+    /// Unknown or missing materials fall back to `Material::diffuse_surface()`.
+    fn build(mut self, library: &HashMap<String, Material>) -> ObjGroup {
+        self.data.materials = self
+            .material_names
+            .iter()
+            .map(|name| {
+                name.as_ref()
+                    .and_then(|n| library.get(n).copied())
+                    .unwrap_or(Material::diffuse_surface())
+            })
+            .collect();
+        self.data.faces = self.faces;
+
+        ObjGroup {
+            name: self.name,
+            mesh: TriangleMesh::from_data(self.data),
+        }
+    }
+}
+
+/// Read a whole file, wrapping errors with the path.
+fn read_file(path: &Path) -> Result<String, ObjError> {
+    std::fs::read_to_string(path).map_err(|error| ObjError::Io {
+        path: path.to_path_buf(),
+        error,
+    })
+}
+
+/// File name used in error messages.
+fn file_name(path: &Path) -> String {
+    path.display().to_string()
+}
+
+fn parse_float(token: &str) -> Result<Float, String> {
+    token
+        .parse::<Float>()
+        .map_err(|_| format!("invalid number '{}'", token))
+}
+
+fn parse_single(args: &[&str]) -> Result<Float, String> {
+    match args.first() {
+        Some(token) => parse_float(token),
+        None => Err("expected a value".into()),
+    }
+}
+
+fn parse_vec3(args: &[&str]) -> Result<Vec3, String> {
+    if args.len() < 3 {
+        return Err(format!("expected 3 values, found {}", args.len()));
+    }
+    Ok(Vec3::new(
+        parse_float(args[0])?,
+        parse_float(args[1])?,
+        parse_float(args[2])?,
+    ))
+}
+
+fn parse_color(args: &[&str]) -> Result<Color, String> {
+    // A single value sets all three channels
+    if args.len() == 1 {
+        let value = parse_float(args[0])?;
+        return Ok(Color::new(value, value, value));
+    }
+    let v = parse_vec3(args)?;
+    Ok(Color::new(v.x, v.y, v.z))
+}
+
+/// Resolve a 1-based (or negative, relative) OBJ index to a zero-based index.
+fn resolve_index(token: &str, count: usize, kind: &str) -> Result<usize, String> {
+    let index: i64 = token
+        .parse()
+        .map_err(|_| format!("invalid {} index '{}'", kind, token))?;
+    let resolved = if index > 0 {
+        index - 1
+    } else if index < 0 {
+        count as i64 + index
+    } else {
+        return Err(format!("{} index must not be 0", kind));
+    };
+    if resolved < 0 || resolved >= count as i64 {
+        return Err(format!(
+            "{} index {} out of range ({} defined)",
+            kind, index, count
+        ));
+    }
+    Ok(resolved as usize)
+}
+
+/// Parse a face vertex of the form `v`, `v/vt`, `v//vn` or `v/vt/vn`.
+fn parse_face_vertex(token: &str, counts: (usize, usize, usize)) -> Result<FaceVertex, String> {
+    let (position_count, uv_count, normal_count) = counts;
+    let mut parts = token.split('/');
+    let position = resolve_index(parts.next().unwrap_or(""), position_count, "vertex")?;
+    let uv = match parts.next() {
+        Some("") | None => None,
+        Some(t) => Some(resolve_index(t, uv_count, "texture coordinate")?),
+    };
+    let normal = match parts.next() {
+        Some("") | None => None,
+        Some(t) => Some(resolve_index(t, normal_count, "normal")?),
+    };
+    Ok((position, uv, normal))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raytracer::Ray;
+    use crate::raytracer::Surface;
+
+    fn no_library(_: &str) -> Result<HashMap<String, Material>, ObjError> {
+        Ok(HashMap::new())
+    }
+
+    #[test]
+    fn test_parse_quad_fan_triangulation() {
+        let source = "
+            v 0 0 0
+            v 1 0 0
+            v 1 1 0
+            v 0 1 0
+            f 1 2 3 4
+        ";
+        let groups = parse_obj(source, "quad.obj", no_library).unwrap();
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].name, "default");
+        assert_eq!(groups[0].mesh.face_count(), 2);
+        assert_eq!(groups[0].mesh.data().faces[1].positions, [0, 2, 3]);
+    }
+
+    #[test]
+    fn test_parse_negative_indices_and_groups() {
+        let source = "
+            o first
+            v 0 0 0
+            v 1 0 0
+            v 0 1 0
+            f -3 -2 -1
+            g second
+            v 0 0 1
+            v 1 0 1
+            v 0 1 1
+            vn 0 0 1
+            vt 0 0
+            f -3/1/1 -2/1/1 -1/1/1
+        ";
+        let groups = parse_obj(source, "groups.obj", no_library).unwrap();
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].name, "first");
+        assert_eq!(groups[1].name, "second");
+
+        // Each group keeps only the vertices it uses
+        let second = groups[1].mesh.data();
+        assert_eq!(second.positions.len(), 3);
+        assert_eq!(second.positions[0], Vec3::new(0.0, 0.0, 1.0));
+        assert_eq!(second.faces[0].normals, Some([0, 0, 0]));
+        assert_eq!(second.faces[0].uvs, Some([0, 0, 0]));
+
+        // First group had no normals
+        assert_eq!(groups[0].mesh.data().faces[0].normals, None);
+    }
+
+    #[test]
+    fn test_parse_missing_normals_still_intersect() {
+        let source = "
+            v 0 0 0
+            v 1 0 0
+            v 0 1 0
+            f 1//  2 3
+        ";
+        let groups = parse_obj(source, "flat.obj", no_library).unwrap();
+        let ray = Ray::new(Vec3::new(0.2, 0.2, 1.0), Vec3::new(0.0, 0.0, -1.0));
+        let hit = groups[0]
+            .mesh
+            .intersect(&ray)
+            .expect("Expected intersection");
+
+        assert_eq!(hit.normal, Vec3::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn test_parse_errors_report_line() {
+        let source = "v 0 0 0\nv 1 0 0\nf 1 2 5\n";
+        let error = match parse_obj(source, "broken.obj", no_library) {
+            Err(error) => error,
+            Ok(_) => panic!("Expected error"),
+        };
+
+        match error {
+            ObjError::Parse { line, .. } => assert_eq!(line, 3),
+            other => panic!("Unexpected error: {}", other),
+        }
+        assert!(parse_obj("f 0 1 2", "zero.obj", no_library).is_err());
+        assert!(parse_obj("v 1 2", "short.obj", no_library).is_err());
+    }
+
+    #[test]
+    fn test_parse_mtl_mapping() {
+        let source = "
+            newmtl red
+            Kd 0.8 0.1 0.1
+            Ks 0.2 0.2 0.2
+
+            newmtl glass
+            Kd 1 1 1
+            Ni 1.5
+            d 0.1
+
+            newmtl lamp
+            Ke 4 3 2
+
+            newmtl crystal
+            Tf 1 1 1
+            Ni 2.4
+            illum 7
+            Pr 0.25
+        ";
+        let materials = parse_mtl(source, "test.mtl").unwrap();
+
+        let red = materials["red"];
+        assert_eq!(red.albedo, Color::new(0.8, 0.1, 0.1));
+        assert!((red.specular_rate - 0.2).abs() < 1e-5);
+        assert!((red.diffuse_rate - 0.8).abs() < 1e-5);
+
+        let glass = materials["glass"];
+        assert_eq!(glass.refractive_index, 1.5);
+        assert!((glass.transmission_rate - 0.9).abs() < 1e-5);
+        assert!(glass.diffuse_rate + glass.specular_rate + glass.transmission_rate <= 1.0 + 1e-5);
+        assert_eq!(glass.emission, Color::black());
+
+        assert_eq!(materials["lamp"].emission, Color::new(4.0, 3.0, 2.0));
+        assert_eq!(materials["crystal"].fresnel, Fresnel::Exact);
+        assert_eq!(materials["crystal"].roughness, 0.25);
+        assert_eq!(glass.fresnel, Fresnel::None);
+    }
+
+    #[test]
+    fn test_usemtl_assigns_face_materials() {
+        let source = "
+            mtllib scene.mtl
+            v 0 0 0
+            v 1 0 0
+            v 0 1 0
+            usemtl blue
+            f 1 2 3
+            usemtl missing
+            f 1 3 2
+        ";
+        let groups = parse_obj(source, "scene.obj", |name| {
+            assert_eq!(name, "scene.mtl");
+            parse_mtl("newmtl blue\nKd 0 0 1\n", name)
+        })
+        .unwrap();
+
+        let data = groups[0].mesh.data();
+        assert_eq!(data.materials.len(), 2);
+        assert_eq!(data.materials[0].albedo, Color::blue());
+        assert_eq!(data.materials[1].albedo, Color::white());
+        assert_eq!(data.faces[1].material, 1);
+    }
+
+    #[test]
+    fn test_load_obj_from_disk() {
+        let directory = std::env::temp_dir().join("raytracer_obj_test");
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("tri.mtl"), "newmtl green\nKd 0 1 0\n").unwrap();
+        std::fs::write(
+            directory.join("tri.obj"),
+            "mtllib tri.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\nusemtl green\nf 1 2 3\n",
+        )
+        .unwrap();
+
+        let groups = load_obj(directory.join("tri.obj")).unwrap();
+        assert_eq!(groups[0].mesh.material().albedo, Color::green());
+
+        assert!(matches!(
+            load_obj(directory.join("missing.obj")),
+            Err(ObjError::Io { .. })
+        ));
+    }
+}
@@ -1,14 +1,54 @@
 //! Main raytracer engine for color computation and ray tracing.
 
 use super::camera::Camera;
+use super::environment::EnvironmentLight;
 use super::image::Image;
-use super::light::Light;
-use super::material::{Color, Material};
+use super::light::LightSource;
+use super::material::{CLEARCOAT_IOR, Color, Fresnel, Material};
+use super::mesh::Triangle;
+use super::microfacet::Ggx;
+use super::sampling::{Rng, cosine_sample_hemisphere};
 use super::vector::{Float, Vec3};
-use super::{Intersection, Ray, Surface};
+use super::{BranchKind, BranchedRay, Intersection, Ray, Surface};
+use std::f32::consts::PI;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Light transport algorithm used by `RayTracer`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Integrator {
+    /// Deterministic tracer that follows every diffuse, specular and transmitted branch
+    Branching,
+    /// Monte Carlo path tracer that follows one randomly chosen branch per bounce,
+    /// with cosine-weighted diffuse sampling for indirect illumination
+    PathTracing,
+}
+
+/// Result of a white furnace test of one material.
+#[derive(Copy, Clone, Debug)]
+pub struct FurnaceResult {
+    /// Largest fraction of incoming light returned by the material (any channel, any view angle)
+    pub albedo: Float,
+    /// Standard error of `albedo` due to Monte Carlo noise
+    pub error: Float,
+    /// Cosine between the view direction and the surface normal where `albedo` was found
+    pub cos_theta: Float,
+}
+
+impl FurnaceResult {
+    /// Tolerance for systematic error before a material is considered to create energy
+    pub const TOLERANCE: Float = 0.01;
+
+    /// Whether the material returned more light than it received, beyond three
+    /// standard errors of noise.
+    pub fn gains_energy(&self) -> bool {
+        self.albedo - 3.0 * self.error > 1.0 + Self::TOLERANCE
+    }
+}
 
 /// Main raytracer engine.
 /// Responsible for computing ray colors through the scene.
+#[derive(Copy, Clone, Debug)]
 pub struct RayTracer {
     /// Background color (for rays that don't hit anything)
     pub background_color: Color,
@@ -18,6 +58,18 @@ pub struct RayTracer {
     pub min_weight: Float,
     /// Default material for vacuum/air (used for rays not inside any object)
     pub vacuum_material: Material,
+    /// Number of worker threads used by `render` (0 = one per available CPU core)
+    pub threads: usize,
+    /// Edge length in pixels of the square tiles distributed to worker threads
+    pub tile_size: usize,
+    /// Light transport algorithm
+    pub integrator: Integrator,
+    /// Number of times each camera sample ray is traced (useful for path tracing)
+    pub samples_per_pixel: u32,
+    /// Seed for the random numbers used by Monte Carlo sampling
+    pub seed: u64,
+    /// Number of shadow rays per light for direct lighting (more = smoother penumbrae)
+    pub light_samples: u32,
 }
 
 impl RayTracer {
@@ -33,11 +85,126 @@ impl RayTracer {
             max_depth,
             min_weight,
             vacuum_material,
+            threads: 0,
+            tile_size: 32,
+            integrator: Integrator::Branching,
+            samples_per_pixel: 1,
+            seed: 0,
+            light_samples: 1,
+        }
+    }
+
+    /// Number of worker threads `render` will use.
+    pub fn worker_count(&self) -> usize {
+        if self.threads > 0 {
+            self.threads
+        } else {
+            thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1)
+        }
+    }
+
+    /// Run a white furnace test on a material.
+    ///
+    /// The material covers an infinite plane inside a uniform white environment light,
+    /// with no other surfaces or lights. Paths are traced with the configured integrator
+    /// from several view angles; everything they bring back was reflected or transmitted
+    /// by the material, so an energy-conserving material never returns more than 1.
+    /// The environment is both sampled by direct lighting and seen by escaping rays, so
+    /// an integrator that counts light twice fails the test too. Emission is ignored.
+    ///
+    /// # Arguments
+    /// * `material` - The material to test
+    /// * `samples` - Number of paths traced per view angle
+    pub fn furnace_test(&self, material: Material, samples: u32) -> FurnaceResult {
+        let plane = Triangle::new(
+            Vec3::new(-1e4, -1e4, 0.0),
+            Vec3::new(1e4, -1e4, 0.0),
+            Vec3::new(0.0, 1e4, 0.0),
+            material.with_emission(Color::black()),
+        );
+        let furnace = RayTracer {
+            background_color: Color::black(),
+            max_depth: self.max_depth.max(2),
+            ..*self
+        };
+        let environment = EnvironmentLight::new(8, 4, vec![Color::white(); 32]);
+
+        let samples = samples.max(2);
+        let mut result = FurnaceResult {
+            albedo: 0.0,
+            error: 0.0,
+            cos_theta: 1.0,
+        };
+        for cos_theta in [1.0 as Float, 0.8, 0.6, 0.4, 0.2, 0.05] {
+            let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+            let direction = Vec3::new(sin_theta, 0.0, -cos_theta);
+            let ray = Ray::new(-direction, direction);
+
+            let mut total = Color::black();
+            let mut total_sq = Color::black();
+            for sample in 0..samples {
+                let mut rng = Rng::for_sample(self.seed, 0, sample as u64);
+                let color = furnace.trace_sample(
+                    &ray,
+                    &[plane],
+                    std::slice::from_ref(&environment),
+                    &mut rng,
+                );
+                total = total + color;
+                total_sq = total_sq + color * color;
+            }
+
+            let n = samples as Float;
+            let mean = total * (1.0 / n);
+            // Brightest channel with its mean square, for the error estimate
+            let channels = [
+                (mean.r, total_sq.r / n),
+                (mean.g, total_sq.g / n),
+                (mean.b, total_sq.b / n),
+            ];
+            let (albedo, mean_sq) = channels
+                .into_iter()
+                .max_by(|a, b| a.0.total_cmp(&b.0))
+                .expect("three channels");
+            if albedo > result.albedo {
+                let variance = (mean_sq - albedo * albedo).max(0.0) * n / (n - 1.0);
+                result = FurnaceResult {
+                    albedo,
+                    error: (variance / n).sqrt(),
+                    cos_theta,
+                };
+            }
         }
+        result
+    }
+
+    /// White furnace mode: test named materials and return those that reflect
+    /// more light than they receive, with their furnace results.
+    ///
+    /// # Arguments
+    /// * `materials` - Materials to check, with names used in the report
+    /// * `samples` - Number of paths traced per view angle
+    pub fn white_furnace<'a>(
+        &self,
+        materials: impl IntoIterator<Item = (&'a str, Material)>,
+        samples: u32,
+    ) -> Vec<(&'a str, FurnaceResult)> {
+        materials
+            .into_iter()
+            .map(|(name, material)| (name, self.furnace_test(material, samples)))
+            .filter(|(_, result)| result.gains_energy())
+            .collect()
     }
 
     /// Render a complete image from the camera viewpoint.
-    /// Generates rays for each pixel and traces them through the scene.
+    /// Generates rays for each pixel on demand and traces them through the scene,
+    /// so memory use does not grow with the number of samples.
+    ///
+    /// The image is split into `tile_size` × `tile_size` tiles which worker threads
+    /// pick up from a shared atomic counter. Every pixel is computed independently,
+    /// so the result does not depend on the number of threads.
     ///
     /// # Arguments
     /// * `camera` - The camera defining viewpoint and image resolution
@@ -46,27 +213,104 @@ impl RayTracer {
     ///
     /// # Returns
     /// An Image containing the rendered HDR pixels
-    pub fn render(&self, camera: &Camera, surfaces: &[impl Surface], lights: &[Light]) -> Image {
-        let rays = camera.generate_rays();
-        let pixels = rays
-            .iter()
-            .map(|row| {
-                row.iter()
-                    .map(|pixel_samples| {
-                        // Average all samples for this pixel
-                        let mut pixel_color = Color::new(0.0, 0.0, 0.0);
-                        for sample_ray in pixel_samples {
-                            pixel_color =
-                                pixel_color + self.trace_ray(sample_ray, surfaces, lights);
+    pub fn render(
+        &self,
+        camera: &Camera,
+        surfaces: &[impl Surface],
+        lights: &[impl LightSource],
+    ) -> Image {
+        let width = camera.width as usize;
+        let height = camera.height as usize;
+
+        let tile_size = self.tile_size.max(1);
+        let tiles_x = width.div_ceil(tile_size);
+        let tiles_y = height.div_ceil(tile_size);
+        let tile_count = tiles_x * tiles_y;
+        let tile_rect = |tile: usize| {
+            let x0 = (tile % tiles_x) * tile_size;
+            let y0 = (tile / tiles_x) * tile_size;
+            (
+                x0,
+                y0,
+                (x0 + tile_size).min(width),
+                (y0 + tile_size).min(height),
+            )
+        };
+
+        // === PARALLEL TILE RENDERING ===
+        let next_tile = AtomicUsize::new(0);
+        let worker_count = self.worker_count().min(tile_count).max(1);
+        let rendered_tiles: Vec<(usize, Vec<Color>)> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..worker_count)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut finished = Vec::new();
+                        loop {
+                            let tile = next_tile.fetch_add(1, Ordering::Relaxed);
+                            if tile >= tile_count {
+                                break;
+                            }
+                            let (x0, y0, x1, y1) = tile_rect(tile);
+                            let mut colors = Vec::with_capacity((x1 - x0) * (y1 - y0));
+                            for y in y0..y1 {
+                                for x in x0..x1 {
+                                    colors.push(self.render_pixel(
+                                        camera, x as u32, y as u32, surfaces, lights,
+                                    ));
+                                }
+                            }
+                            finished.push((tile, colors));
                         }
-                        pixel_color * (1.0 / pixel_samples.len() as Float)
+                        finished
                     })
-                    .collect()
-            })
-            .collect();
+                })
+                .collect();
+
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().expect("render worker panicked"))
+                .collect()
+        });
+
+        // === ASSEMBLE TILES ===
+        let mut pixels = vec![vec![Color::black(); width]; height];
+        for (tile, colors) in rendered_tiles {
+            let (x0, y0, x1, _) = tile_rect(tile);
+            let tile_width = x1 - x0;
+            for (i, color) in colors.into_iter().enumerate() {
+                pixels[y0 + i / tile_width][x0 + i % tile_width] = color;
+            }
+        }
         Image::from_pixels(pixels)
     }
 
+    /// Compute the color of one pixel by averaging all of its sample rays.
+    fn render_pixel(
+        &self,
+        camera: &Camera,
+        x: u32,
+        y: u32,
+        surfaces: &[impl Surface],
+        lights: &[impl LightSource],
+    ) -> Color {
+        let pixel_index = y as u64 * camera.width as u64 + x as u64;
+        let repeats = self.samples_per_pixel.max(1);
+
+        let mut pixel_color = Color::new(0.0, 0.0, 0.0);
+        let mut sample_count = 0;
+        for (camera_sample, sample_ray) in camera.pixel_rays(x, y).enumerate() {
+            for repeat in 0..repeats {
+                // One generator per sample keeps results independent of tile scheduling
+                let sample = camera_sample as u64 * repeats as u64 + repeat as u64;
+                let mut rng = Rng::for_sample(self.seed, pixel_index, sample);
+                pixel_color =
+                    pixel_color + self.trace_sample(&sample_ray, surfaces, lights, &mut rng);
+                sample_count += 1;
+            }
+        }
+        pixel_color * (1.0 / sample_count as Float)
+    }
+
     /// Trace a ray through the scene and compute its color.
     /// Recursively traces rays through reflections, refractions, and diffuse scattering.
     ///
@@ -77,8 +321,31 @@ impl RayTracer {
     ///
     /// # Returns
     /// The computed color of the ray
-    fn trace_ray(&self, ray: &Ray, surfaces: &[impl Surface], lights: &[Light]) -> Color {
-        self.trace_ray_recursive(ray, surfaces, lights, 0, 1.0, self.vacuum_material)
+    #[cfg(test)]
+    fn trace_ray(
+        &self,
+        ray: &Ray,
+        surfaces: &[impl Surface],
+        lights: &[impl LightSource],
+    ) -> Color {
+        self.trace_sample(ray, surfaces, lights, &mut Rng::new(self.seed))
+    }
+
+    /// Trace one sample ray with the configured integrator.
+    fn trace_sample(
+        &self,
+        ray: &Ray,
+        surfaces: &[impl Surface],
+        lights: &[impl LightSource],
+        rng: &mut Rng,
+    ) -> Color {
+        match self.integrator {
+            Integrator::Branching => {
+                let vacuum = self.vacuum_material;
+                self.trace_ray_recursive(ray, surfaces, lights, 0, 1.0, vacuum, true, rng)
+            }
+            Integrator::PathTracing => self.trace_path(ray, surfaces, lights, rng),
+        }
     }
 
     /// Internal recursive implementation of trace_ray.
@@ -91,14 +358,20 @@ impl RayTracer {
     /// * `depth` - Current recursion depth
     /// * `current_weight` - Current weight of the ray
     /// * `passing_material` - Material the ray is currently passing through
+    /// * `count_light_emission` - Whether lights hit by the ray add their emission
+    ///   (false after diffuse and glossy bounces, whose lights were sampled by direct lighting)
+    /// * `rng` - Random number generator for light sampling
+    #[allow(clippy::too_many_arguments)]
     fn trace_ray_recursive(
         &self,
         ray: &Ray,
         surfaces: &[impl Surface],
-        lights: &[Light],
+        lights: &[impl LightSource],
         depth: usize,
         current_weight: Float,
         passing_material: Material,
+        count_light_emission: bool,
+        rng: &mut Rng,
     ) -> Color {
         // Stop tracing if depth exceeded or weight too small
         if depth >= self.max_depth || current_weight < self.min_weight {
@@ -108,66 +381,45 @@ impl RayTracer {
         // Find closest intersection with all surfaces
         let closest_intersection = self.find_closest_intersection(ray, surfaces);
 
-        // Find closest intersection with all lights
-        let mut closest_light_intersection: Option<(super::Intersection, usize)> = None;
-        let mut closest_light_t = Float::INFINITY;
-        for (light_idx, light) in lights.iter().enumerate() {
-            if let Some(light_intersection) = light.intersect(ray) {
-                if light_intersection.t > 1e-5 && light_intersection.t < closest_light_t {
-                    closest_light_intersection = Some((light_intersection, light_idx));
-                    closest_light_t = light_intersection.t;
-                }
+        // Check if a light is closer than the closest surface
+        if let Some((light_intersection, light_idx)) =
+            self.find_closest_light(ray, lights, closest_intersection.as_ref())
+        {
+            // Ray hit the light first
+            if !count_light_emission {
+                return Color::black();
             }
-        }
+            let light = &lights[light_idx];
 
-        // Check if light is closer than surface
-        if let Some((light_intersection, light_idx)) = closest_light_intersection {
-            if closest_intersection.is_none()
-                || light_intersection.t < closest_intersection.as_ref().unwrap().t
-            {
-                // Ray hit the light first
-                let light = &lights[light_idx];
-
-                // Apply Beer's law absorption for the distance traveled
-                let distance = light_intersection.t;
-                let attenuation = Color::new(
-                    (-passing_material.absorption.r * distance).exp(),
-                    (-passing_material.absorption.g * distance).exp(),
-                    (-passing_material.absorption.b * distance).exp(),
-                );
+            // Apply Beer's law absorption for the distance traveled
+            let attenuation = Self::beer_attenuation(passing_material, light_intersection.t);
 
-                // Return the light emission attenuated by the material
-                return light.emission * attenuation;
-            }
+            // Return the light emission attenuated by the material
+            return light.radiance(ray, &light_intersection) * attenuation;
         }
 
         let intersection = match closest_intersection {
             Some(intersection) => intersection,
             None => {
-                // Ray didn't hit anything; return background
-                return self.background_color;
+                // Ray didn't hit anything; return background and distant lights
+                if !count_light_emission {
+                    return self.background_color;
+                }
+                return self.background_color + Self::escaped_radiance(ray, lights);
             }
         };
 
         // Apply Beer's law absorption: I = I0 * e^(-absorption * distance)
         // Compute attenuation factor for the ray distance traveled
-        let distance = intersection.t;
-        let attenuation = Color::new(
-            (-passing_material.absorption.r * distance).exp(),
-            (-passing_material.absorption.g * distance).exp(),
-            (-passing_material.absorption.b * distance).exp(),
-        );
+        let attenuation = Self::beer_attenuation(passing_material, intersection.t);
 
         // === DIRECT LIGHTING ===
         // Compute light contribution from all light sources
-        let mut direct_color = Color::black();
-        for light in lights {
-            direct_color = direct_color + self.compute_direct_light(&intersection, light, surfaces);
-        }
+        let direct_color = self.compute_direct_lighting(ray, &intersection, surfaces, lights, rng);
 
         // === INDIRECT LIGHTING (RAY BRANCHING) ===
         // Generate branched rays for reflection/refraction/diffuse
-        let branched_rays = self.branch_rays(ray, &intersection, passing_material);
+        let branched_rays = self.branch_rays(ray, &intersection, passing_material, rng);
 
         let mut indirect_color = Color::black();
         for branched in &branched_rays {
@@ -179,21 +431,189 @@ impl RayTracer {
                 depth + 1,
                 weight,
                 branched.passing_material,
+                // Lights reached through diffuse or glossy bounces were sampled by direct lighting
+                !matches!(branched.kind, BranchKind::Diffuse | BranchKind::Glossy),
+                rng,
             );
             // Modulate by material albedo (contribution already includes all recursive effects)
             // indirect_color = indirect_color + (material.albedo * contribution);
-            indirect_color = indirect_color + contribution * branched.weight;
+            indirect_color = indirect_color + contribution * branched.tint * branched.weight;
         }
 
-        // Apply Beer's law attenuation to both direct and indirect lighting
-        let result = (direct_color + indirect_color) * attenuation;
+        // Apply Beer's law attenuation to emitted, direct and indirect lighting
+        let emitted = intersection.material.emission;
+        let result = (emitted + direct_color + indirect_color) * attenuation;
 
         // Combine direct and indirect lighting
         result
     }
 
+    /// Trace a single random path through the scene (Monte Carlo path tracing).
+    ///
+    /// At every hit, direct lighting is added and one branch from `branch_rays` is chosen
+    /// with probability proportional to its weight. Diffuse branches are redirected with
+    /// cosine-weighted hemisphere sampling and tinted by the albedo, which produces
+    /// indirect illumination and color bleeding. Paths whose throughput falls below
+    /// `min_weight` are terminated by Russian roulette.
+    fn trace_path(
+        &self,
+        ray: &Ray,
+        surfaces: &[impl Surface],
+        lights: &[impl LightSource],
+        rng: &mut Rng,
+    ) -> Color {
+        let mut radiance = Color::black();
+        let mut throughput = Color::white();
+        let mut ray = *ray;
+        let mut passing_material = self.vacuum_material;
+        // Lights reached by a diffuse bounce were already counted by direct lighting
+        let mut count_light_emission = true;
+
+        for _depth in 0..self.max_depth {
+            let closest_intersection = self.find_closest_intersection(&ray, surfaces);
+
+            if let Some((light_intersection, light_idx)) =
+                self.find_closest_light(&ray, lights, closest_intersection.as_ref())
+            {
+                if count_light_emission {
+                    let attenuation =
+                        Self::beer_attenuation(passing_material, light_intersection.t);
+                    let emitted = lights[light_idx].radiance(&ray, &light_intersection);
+                    radiance = radiance + throughput * emitted * attenuation;
+                }
+                break;
+            }
+
+            let Some(intersection) = closest_intersection else {
+                radiance = radiance + throughput * self.background_color;
+                if count_light_emission {
+                    radiance = radiance + throughput * Self::escaped_radiance(&ray, lights);
+                }
+                break;
+            };
+
+            throughput = throughput * Self::beer_attenuation(passing_material, intersection.t);
+
+            // Glowing surfaces are not sampled by direct lighting, so always count them
+            radiance = radiance + throughput * intersection.material.emission;
+
+            // === DIRECT LIGHTING ===
+            let direct_color =
+                self.compute_direct_lighting(&ray, &intersection, surfaces, lights, rng);
+            radiance = radiance + throughput * direct_color;
+
+            // === CHOOSE ONE BRANCH ===
+            let branches = self.branch_rays(&ray, &intersection, passing_material, rng);
+            let total_weight: Float = branches.iter().map(|b| b.weight).sum();
+            if total_weight <= 0.0 {
+                break;
+            }
+            let mut pick = rng.next_float() * total_weight;
+            let mut chosen = branches[branches.len() - 1];
+            for branch in &branches {
+                if pick < branch.weight {
+                    chosen = *branch;
+                    break;
+                }
+                pick -= branch.weight;
+            }
+
+            // Branch weight divided by its selection probability
+            throughput = throughput * chosen.tint * total_weight;
+            // Lights reached through diffuse or glossy bounces were sampled by direct lighting
+            count_light_emission = !matches!(chosen.kind, BranchKind::Diffuse | BranchKind::Glossy);
+
+            if chosen.kind == BranchKind::Diffuse {
+                // Lambertian BRDF (albedo / π) times cos θ over the cosine pdf (cos θ / π)
+                let direction = cosine_sample_hemisphere(
+                    chosen.ray.direction,
+                    rng.next_float(),
+                    rng.next_float(),
+                );
+                chosen.ray =
+                    Ray::new(chosen.ray.origin, direction).with_travelled(chosen.ray.travelled);
+                throughput = throughput
+                    * Self::diffuse_reflectance(intersection.material, -ray.direction, direction);
+            }
+
+            // === RUSSIAN ROULETTE ===
+            let max_throughput = throughput.r.max(throughput.g).max(throughput.b);
+            if max_throughput < self.min_weight {
+                let survival = max_throughput / self.min_weight;
+                if rng.next_float() >= survival {
+                    break;
+                }
+                throughput = throughput * (1.0 / survival);
+            }
+
+            ray = chosen.ray;
+            passing_material = chosen.passing_material;
+        }
+
+        radiance
+    }
+
+    /// Beer's law transmittance e^(-absorption * distance) through a material.
+    fn beer_attenuation(material: Material, distance: Float) -> Color {
+        Color::new(
+            (-material.absorption.r * distance).exp(),
+            (-material.absorption.g * distance).exp(),
+            (-material.absorption.b * distance).exp(),
+        )
+    }
+
+    /// Sum the radiance of distant lights seen by a ray that escapes the scene.
+    fn escaped_radiance(ray: &Ray, lights: &[impl LightSource]) -> Color {
+        let mut radiance = Color::black();
+        for light in lights {
+            radiance = radiance + light.escaped(ray.direction);
+        }
+        radiance
+    }
+
+    /// Find the closest light hit by a ray, if it is closer than the closest surface hit.
+    /// Returns the light intersection and the index of the light.
+    fn find_closest_light(
+        &self,
+        ray: &Ray,
+        lights: &[impl LightSource],
+        closest_surface: Option<&Intersection>,
+    ) -> Option<(Intersection, usize)> {
+        let mut closest = None;
+        let mut closest_t = closest_surface.map_or(Float::INFINITY, |hit| hit.t);
+
+        for (light_idx, light) in lights.iter().enumerate() {
+            if let Some(light_intersection) = light.intersect(ray)
+                && light_intersection.t > 1e-5
+                && light_intersection.t < closest_t
+            {
+                closest = Some((light_intersection, light_idx));
+                closest_t = light_intersection.t;
+            }
+        }
+
+        closest
+    }
+
+    /// Sum the direct lighting contribution of all lights at an intersection.
+    fn compute_direct_lighting(
+        &self,
+        ray: &Ray,
+        intersection: &Intersection,
+        surfaces: &[impl Surface],
+        lights: &[impl LightSource],
+        rng: &mut Rng,
+    ) -> Color {
+        let mut direct_color = Color::black();
+        for light in lights {
+            direct_color = direct_color
+                + self.compute_direct_light(ray, intersection, light, surfaces, lights, rng);
+        }
+        direct_color
+    }
+
     /// Find the closest intersection of a ray with all surfaces.
-    /// Returns the intersection, or None if no hit.
+    /// Returns the intersection with its tangent frame, or None if no hit.
     fn find_closest_intersection(
         &self,
         ray: &Ray,
@@ -213,48 +633,202 @@ impl RayTracer {
             }
         }
 
-        closest
+        closest.map(|mut intersection: Intersection| {
+            intersection.compute_tangent_frame();
+            intersection
+        })
     }
 
     /// Compute direct lighting contribution from a single light source.
-    /// Implements Lambertian diffuse reflection using cosine law (N · L).
+    /// Implements Lambertian diffuse reflection using cosine law (N · L),
+    /// plus microfacet reflection for rough materials.
+    ///
+    /// Shoots `light_samples` shadow rays towards points sampled on the visible part
+    /// of the light and averages the unoccluded ones, so partially hidden
+    /// lights cast soft penumbrae. Each sample is weighted by its solid-angle pdf.
+    /// Only light arriving on the side of the surface facing the incident `ray` is reflected.
     fn compute_direct_light(
         &self,
+        ray: &Ray,
         intersection: &Intersection,
-        light: &Light,
+        light: &impl LightSource,
         surfaces: &[impl Surface],
+        lights: &[impl LightSource],
+        rng: &mut Rng,
     ) -> Color {
-        // Vector from intersection point to light center
-        let to_light = (light.center - intersection.point).normalize();
+        // A single sample is exact for point-like lights
+        let sample_count = if light.is_delta() {
+            1
+        } else {
+            self.light_samples.max(1)
+        };
+        let view_side = -ray.direction.dot(intersection.geometric_normal);
+        let coat_transmittance = self.coat_transmittance(ray, intersection);
+        let mut total = Color::black();
 
-        // Lambertian cosine law: only lit if facing the light
-        // Use absolute value of dot product to handle both sides of the surface
-        // let cos_theta = to_light.dot(intersection.normal).clamp(0.0, 1.0);
-        let cos_theta = to_light.dot(intersection.normal).abs();
+        for _ in 0..sample_count {
+            let Some(sample) = light.sample(intersection.point, rng.next_float(), rng.next_float())
+            else {
+                continue;
+            };
+
+            // Light from behind the surface does not reach the viewer's side
+            if sample.direction.dot(intersection.geometric_normal) * view_side <= 0.0 {
+                continue;
+            }
 
+            // Lambertian cosine law: only lit if facing the light
+            // Use absolute value of dot product to handle both sides of the surface
+            let cos_theta = sample.direction.dot(intersection.normal).abs();
+            if cos_theta <= 0.0 || sample.pdf <= 0.0 {
+                continue;
+            }
+
+            if self.is_occluded(
+                intersection,
+                sample.direction,
+                sample.distance,
+                surfaces,
+                lights,
+            ) {
+                continue;
+            }
+
+            // Lambertian diffuse reflection estimator:
+            // (diffuse_rate * albedo / π) * radiance * cos_theta / pdf
+            let material = intersection.material;
+            let diffuse = Self::diffuse_reflectance(material, -ray.direction, sample.direction)
+                * (material.diffuse_rate * coat_transmittance * cos_theta / PI);
+            let glossy = self.glossy_reflection(ray, intersection, sample.direction);
+            total = total + (diffuse + glossy) * sample.radiance * (1.0 / sample.pdf);
+        }
+
+        total * (1.0 / sample_count as Float)
+    }
+
+    /// Microfacet reflection BRDF times cos θ towards `direction`, for rough materials
+    /// and rough clear coats.
+    /// Returns black for smooth materials, whose reflection is only reached by branching.
+    fn glossy_reflection(&self, ray: &Ray, intersection: &Intersection, direction: Vec3) -> Color {
+        let material = intersection.material;
+        let is_entering = ray.direction.dot(intersection.geometric_normal) < 0.0;
+        let (normal, ratio) = if is_entering {
+            let ratio = self.vacuum_material.refractive_index / material.refractive_index;
+            (intersection.normal, ratio)
+        } else {
+            let ratio = material.refractive_index / self.vacuum_material.refractive_index;
+            (-intersection.normal, ratio)
+        };
+
+        let (tangent, bitangent) = (intersection.tangent, intersection.bitangent);
+        let to_local = |v: Vec3| Vec3::new(v.dot(tangent), v.dot(bitangent), v.dot(normal));
+        let wo = to_local(-ray.direction);
+        let wi = to_local(direction);
+
+        let mut glossy = Color::black();
+        if material.roughness > 0.0 {
+            let brdf = Ggx::new(material.roughness).reflection(wo, wi);
+            if brdf > 0.0 {
+                // Same split as branch_rays: tinted specular part plus the
+                // Fresnel-reflected part of transmission
+                let half = (wo + wi).normalize();
+                let reflectance = material.fresnel.reflectance(wo.dot(half), ratio);
+                let reflect_rate = material.specular_tint * material.specular_rate
+                    + Color::white() * (material.transmission_rate * reflectance);
+                glossy = reflect_rate * (brdf * wi.z * self.coat_transmittance(ray, intersection));
+            }
+        }
+
+        if is_entering && material.coat > 0.0 && material.coat_roughness > 0.0 {
+            let brdf = Ggx::new(material.coat_roughness).reflection(wo, wi);
+            if brdf > 0.0 {
+                let half = (wo + wi).normalize();
+                let reflectance = self.coat_reflectance(material, wo.dot(half));
+                glossy = glossy + Color::white() * (reflectance * brdf * wi.z);
+            }
+        }
+
+        glossy
+    }
+
+    /// Diffuse reflectance for light arriving from `wi` and leaving towards `wo`:
+    /// the albedo plus the sheen, which grows towards grazing angles.
+    fn diffuse_reflectance(material: Material, wo: Vec3, wi: Vec3) -> Color {
+        if material.sheen == Color::black() {
+            return material.albedo;
+        }
+        let half = wo + wi;
+        if half.length() <= 0.0 {
+            return material.albedo;
+        }
+        let cos_d = wi.dot(half.normalize()).clamp(0.0, 1.0);
+        material.albedo + material.sheen * (1.0 - cos_d).powi(5)
+    }
+
+    /// Fresnel reflectance of a material's clear coat for a given cosine of incidence.
+    fn coat_reflectance(&self, material: Material, cos_i: Float) -> Float {
+        let ratio = self.vacuum_material.refractive_index / CLEARCOAT_IOR;
+        material.coat * Fresnel::Exact.reflectance(cos_i, ratio)
+    }
+
+    /// Fraction of light passing through the clear coat to the base lobes
+    /// (1 for uncoated materials and rays arriving from inside).
+    fn coat_transmittance(&self, ray: &Ray, intersection: &Intersection) -> Float {
+        let material = intersection.material;
+        if material.coat <= 0.0 || ray.direction.dot(intersection.geometric_normal) >= 0.0 {
+            return 1.0;
+        }
+        let cos_i = (-ray.direction.dot(intersection.normal)).clamp(0.0, 1.0);
+        1.0 - self.coat_reflectance(material, cos_i)
+    }
+
+    /// Check whether any surface, or the geometry of any light, blocks the path from
+    /// an intersection towards a light.
+    ///
+    /// # Arguments
+    /// * `intersection` - The shaded point
+    /// * `direction` - Unit direction towards the light
+    /// * `distance` - Distance to the light along `direction`
+    /// * `surfaces` - Array of surfaces in the scene
+    /// * `lights` - Array of light sources in the scene
+    fn is_occluded(
+        &self,
+        intersection: &Intersection,
+        direction: Vec3,
+        distance: Float,
+        surfaces: &[impl Surface],
+        lights: &[impl LightSource],
+    ) -> bool {
         // Shadow ray: trace toward the light to check visibility
-        // Apply offset to avoid self-intersection (shadow acne)
+        // Offset along the geometric normal, on the light's side, to avoid self-intersection
+        // (shadow acne) without leaking through the surface at grazing angles
         const OFFSET_EPS: Float = 1e-4;
-        let shadow_origin = intersection.point + to_light * OFFSET_EPS;
-        let shadow_ray = Ray::new(shadow_origin, to_light);
+        let geometric_normal = intersection.geometric_normal;
+        let offset_normal = if direction.dot(geometric_normal) >= 0.0 {
+            geometric_normal
+        } else {
+            -geometric_normal
+        };
+        let shadow_origin = intersection.point + offset_normal * OFFSET_EPS;
+        let shadow_ray = Ray::new(shadow_origin, direction);
 
         // Check if there's any surface blocking the direct path to light
-        // We only check surfaces, not the light itself
-        for surface in surfaces {
-            if let Some(shadow_hit) = surface.intersect(&shadow_ray) {
-                // Check if we hit something before the light
-                // Light is at distance: (light.center - intersection.point).length()
-                let dist_to_light = (light.center - intersection.point).length();
-                if shadow_hit.t < dist_to_light - 1e-5 {
-                    // Blocked by another surface
-                    return Color::black();
-                }
-            }
-        }
+        let blocked_by_surface = surfaces.iter().any(|surface| {
+            surface
+                .intersect(&shadow_ray)
+                .is_some_and(|shadow_hit| shadow_hit.t < distance - 1e-5)
+        });
 
-        // Lambertian diffuse reflection formula:
-        // diffuse_color = object_color * light_color * cos_theta
-        intersection.material.albedo * light.emission * cos_theta
+        // Light geometry blocks too, including other parts of the sampled light.
+        // The sampled point itself is reached near `distance` from the offset origin,
+        // so allow for the offset and rounding.
+        let light_distance = (distance - OFFSET_EPS) * (1.0 - 1e-4);
+        blocked_by_surface
+            || lights.iter().any(|light| {
+                light
+                    .intersect(&shadow_ray)
+                    .is_some_and(|light_hit| light_hit.t < light_distance)
+            })
     }
 
     /// Generate branched rays after ray-surface interaction.
@@ -264,32 +838,40 @@ impl RayTracer {
     /// * `ray` - The incident ray
     /// * `intersection` - The intersection point
     /// * `incoming_material` - Material the ray is currently passing through (incident side)
+    /// * `rng` - Random number generator for sampling rough surfaces
     ///
     /// This method handles:
     /// - Diffuse reflection (Lambertian scattering)
     /// - Specular reflection (mirror-like reflection)
     /// - Transmission/Refraction (dielectric materials with Snell's law)
+    /// - Rough reflection and refraction around a sampled GGX microfacet normal
     fn branch_rays(
         &self,
         ray: &Ray,
         intersection: &Intersection,
         incoming_material: Material,
-    ) -> Vec<super::BranchedRay> {
+        rng: &mut Rng,
+    ) -> Vec<BranchedRay> {
         let surface_material = intersection.material;
         let mut branched = Vec::new();
+        // Light reflected by a clear coat never reaches the base lobes
+        let coat_transmittance = self.coat_transmittance(ray, intersection);
 
         // Offset to avoid self-intersection (shadow acne)
         const OFFSET_EPS: Float = 1e-4;
 
         // Determine if ray is coming from outside or inside the surface
-        // This is crucial for proper reflection/refraction calculation
-        let is_entering = ray.direction.dot(intersection.normal) < 0.0;
+        // This is crucial for proper reflection/refraction calculation.
+        // The geometric normal decides the side; the shading normal only bends directions.
+        let is_entering = ray.direction.dot(intersection.geometric_normal) < 0.0;
 
-        // Outward-facing normal: should point toward the incoming ray direction
-        let normal = if is_entering {
-            intersection.normal // Ray hits from outside; use normal as-is
+        // Outward-facing normals: should point toward the incoming ray direction
+        let (normal, geometric_normal) = if is_entering {
+            // Ray hits from outside; use normals as-is
+            (intersection.normal, intersection.geometric_normal)
         } else {
-            -intersection.normal // Ray is inside; flip normal to point outward
+            // Ray is inside; flip normals to point outward
+            (-intersection.normal, -intersection.geometric_normal)
         };
 
         // === DIFFUSE REFLECTION ===
@@ -299,16 +881,38 @@ impl RayTracer {
             // Direct lighting is computed separately in compute_direct_light
             // This ray just continues the path for indirect effects
             let diffuse_dir = normal;
-            let ray_origin = intersection.point + normal * OFFSET_EPS;
+            let ray_origin = intersection.point + geometric_normal * OFFSET_EPS;
 
-            branched.push(super::BranchedRay {
+            branched.push(BranchedRay {
                 ray: Ray::new(ray_origin, diffuse_dir),
-                weight: surface_material.diffuse_rate,
+                kind: BranchKind::Diffuse,
+                weight: surface_material.diffuse_rate * coat_transmittance,
+                tint: Color::white(),
                 // Reflected ray continues through the incoming material
                 passing_material: incoming_material,
             });
         }
 
+        // === MICROFACET NORMAL ===
+        // Rough surfaces reflect and refract around a microfacet normal visible from the ray.
+        // Weighting by the masking of the outgoing direction keeps the estimate unbiased.
+        let (tangent, bitangent) = (intersection.tangent, intersection.bitangent);
+        let to_local = |v: Vec3| Vec3::new(v.dot(tangent), v.dot(bitangent), v.dot(normal));
+        let has_specular =
+            surface_material.specular_rate > 1e-5 || surface_material.transmission_rate > 1e-5;
+        let microfacet = (surface_material.roughness > 0.0 && has_specular)
+            .then(|| Ggx::new(surface_material.roughness));
+        let facet_normal = match microfacet {
+            Some(ggx) if to_local(-ray.direction).z > 0.0 => {
+                let wo = to_local(-ray.direction);
+                let h = ggx.sample_visible_normal(wo, rng.next_float(), rng.next_float());
+                tangent * h.x + bitangent * h.y + normal * h.z
+            }
+            _ => normal,
+        };
+        let masking =
+            |direction: Vec3| microfacet.map_or(1.0, |ggx| ggx.masking(to_local(direction)));
+
         // === SPECULAR REFLECTION (+ TOTAL INTERNAL REFLECTION) ===
         // Mirror-like reflection: angle of incidence equals angle of reflection
         let mut specular_weight = surface_material.specular_rate;
@@ -324,21 +928,33 @@ impl RayTracer {
                 surface_material.refractive_index / self.vacuum_material.refractive_index
             };
 
-            // Refraction formula using vector form:
-            let cos_i = -ray.direction.dot(normal);
-            let sin_t_sq = ratio * ratio * (1.0 - cos_i * cos_i);
+            // Refraction formula using vector form.
+            // If bending around the shading normal would not cross the geometric surface,
+            // fall back to the geometric normal so the ray cannot leak back outside.
+            let refracted =
+                Self::refract(ray.direction, facet_normal, ratio).and_then(|refracted| {
+                    if refracted.dot(geometric_normal) < 0.0 {
+                        Some(refracted)
+                    } else {
+                        Self::refract(ray.direction, geometric_normal, ratio)
+                    }
+                });
+
+            // Split the transmitted portion by the Fresnel reflectance for this angle
+            let transmission_rate = match refracted {
+                Some(_) if surface_material.fresnel != Fresnel::None => {
+                    let cos_i = -ray.direction.dot(facet_normal);
+                    let reflectance = surface_material.fresnel.reflectance(cos_i, ratio);
+                    specular_weight += surface_material.transmission_rate * reflectance;
+                    surface_material.transmission_rate * (1.0 - reflectance)
+                }
+                _ => surface_material.transmission_rate,
+            };
 
             // Check for total internal reflection
-            if sin_t_sq > 1.0 {
-                // Total internal reflection: add transmission_rate to specular reflection weight
-                // This avoids creating duplicate rays
-                specular_weight += surface_material.transmission_rate;
-            } else {
-                // Refracted ray direction
-                let cos_t = (1.0 - sin_t_sq).sqrt();
-                let refracted = ratio * ray.direction + normal * (ratio * cos_i - cos_t);
+            if let Some(refracted) = refracted {
                 // For transmission, offset in the direction of the refracted ray (inward)
-                let ray_origin = intersection.point - normal * OFFSET_EPS;
+                let ray_origin = intersection.point - geometric_normal * OFFSET_EPS;
 
                 // After refraction, determine which material the ray passes through
                 // If entering: ray passes through the surface material (inside)
@@ -349,34 +965,134 @@ impl RayTracer {
                     self.vacuum_material // Exiting to vacuum/air
                 };
 
-                branched.push(super::BranchedRay {
+                branched.push(BranchedRay {
                     ray: Ray::new(ray_origin, refracted),
-                    weight: surface_material.transmission_rate,
+                    kind: BranchKind::Transmission,
+                    weight: transmission_rate * masking(refracted) * coat_transmittance,
+                    tint: Color::white(),
                     passing_material: next_material,
                 });
+            } else {
+                // Total internal reflection: add transmission_rate to specular reflection weight
+                // This avoids creating duplicate rays
+                specular_weight += surface_material.transmission_rate;
             }
         }
 
         // Add specular reflection (or total internal reflection) if weight > 0
         if specular_weight > 1e-5 {
-            let reflected = ray.direction - normal * 2.0 * ray.direction.dot(normal);
-            let ray_origin = intersection.point + normal * OFFSET_EPS;
+            let ray_origin = intersection.point + geometric_normal * OFFSET_EPS;
+            let reflected = ray.direction.reflect(facet_normal);
+            // Only the specular_rate part is tinted; Fresnel and total internal reflection are white
+            let tint = (surface_material.specular_tint * surface_material.specular_rate
+                + Color::white() * (specular_weight - surface_material.specular_rate))
+                * (1.0 / specular_weight);
+            let specular_weight = specular_weight * coat_transmittance;
 
-            branched.push(super::BranchedRay {
-                ray: Ray::new(ray_origin, reflected),
-                weight: specular_weight,
-                // Reflected ray continues through the incoming material
-                passing_material: incoming_material,
-            });
+            if microfacet.is_some() {
+                // Rough reflection: facets may send the ray into the surface, which is lost
+                if reflected.dot(geometric_normal) > 0.0 && to_local(reflected).z > 0.0 {
+                    branched.push(BranchedRay {
+                        ray: Ray::new(ray_origin, reflected),
+                        kind: BranchKind::Glossy,
+                        weight: specular_weight * masking(reflected),
+                        tint,
+                        passing_material: incoming_material,
+                    });
+                }
+            } else {
+                // Reflect around the shading normal unless that would point into the surface
+                let reflected = if reflected.dot(geometric_normal) <= 0.0 {
+                    ray.direction.reflect(geometric_normal)
+                } else {
+                    reflected
+                };
+
+                branched.push(BranchedRay {
+                    ray: Ray::new(ray_origin, reflected),
+                    kind: BranchKind::Specular,
+                    weight: specular_weight,
+                    tint,
+                    // Reflected ray continues through the incoming material
+                    passing_material: incoming_material,
+                });
+            }
+        }
+
+        // === CLEAR COAT ===
+        // Reflection off the lacquer layer, around its own (possibly rough) microfacet normal
+        if is_entering && surface_material.coat > 1e-5 {
+            let coat_ggx = (surface_material.coat_roughness > 0.0)
+                .then(|| Ggx::new(surface_material.coat_roughness));
+            let wo = to_local(-ray.direction);
+            let coat_normal = match coat_ggx {
+                Some(ggx) if wo.z > 0.0 => {
+                    let h = ggx.sample_visible_normal(wo, rng.next_float(), rng.next_float());
+                    tangent * h.x + bitangent * h.y + normal * h.z
+                }
+                _ => normal,
+            };
+            let reflectance =
+                self.coat_reflectance(surface_material, -ray.direction.dot(coat_normal));
+            let ray_origin = intersection.point + geometric_normal * OFFSET_EPS;
+            let reflected = ray.direction.reflect(coat_normal);
+
+            match coat_ggx {
+                Some(ggx) => {
+                    if reflected.dot(geometric_normal) > 0.0 && to_local(reflected).z > 0.0 {
+                        branched.push(BranchedRay {
+                            ray: Ray::new(ray_origin, reflected),
+                            kind: BranchKind::Glossy,
+                            weight: reflectance * ggx.masking(to_local(reflected)),
+                            tint: Color::white(),
+                            passing_material: incoming_material,
+                        });
+                    }
+                }
+                None => {
+                    let reflected = if reflected.dot(geometric_normal) <= 0.0 {
+                        ray.direction.reflect(geometric_normal)
+                    } else {
+                        reflected
+                    };
+                    branched.push(BranchedRay {
+                        ray: Ray::new(ray_origin, reflected),
+                        kind: BranchKind::Specular,
+                        weight: reflectance,
+                        tint: Color::white(),
+                        passing_material: incoming_material,
+                    });
+                }
+            }
         }
 
+        // Texture filtering measures the footprint over the whole path
+        let travelled = ray.travelled + intersection.t;
+        for branch in &mut branched {
+            branch.ray.travelled = travelled;
+        }
         branched
     }
+
+    /// Refract a direction through a surface using Snell's law in vector form.
+    /// `normal` must face the incident side and `ratio` is n_incident / n_transmitted.
+    /// Returns None on total internal reflection.
+    fn refract(direction: Vec3, normal: Vec3, ratio: Float) -> Option<Vec3> {
+        let cos_i = -direction.dot(normal);
+        let sin_t_sq = ratio * ratio * (1.0 - cos_i * cos_i);
+        if sin_t_sq > 1.0 {
+            return None;
+        }
+        let cos_t = (1.0 - sin_t_sq).sqrt();
+        Some(ratio * direction + normal * (ratio * cos_i - cos_t))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::raytracer::aabb::Aabb;
+    use crate::raytracer::light::{DirectionalLight, Light, PointLight, QuadLight, SpotLight};
     use crate::raytracer::material::Material;
 
     // Mock Surface implementation for testing
@@ -393,6 +1109,10 @@ mod tests {
         fn material(&self) -> Material {
             self.material
         }
+
+        fn bounds(&self) -> Aabb {
+            Aabb::empty()
+        }
     }
 
     #[test]
@@ -437,4 +1157,879 @@ mod tests {
         // Should return background color and not panic
         assert_eq!(color, Color::black());
     }
+
+    #[test]
+    fn test_branch_rays_stay_on_geometric_side() {
+        let vacuum = Material::new(Color::black(), 0.0, 0.0, 1.0, 1.0, Color::black());
+        let tracer = RayTracer::new(Color::black(), 8, 1e-3, vacuum);
+
+        // Grazing ray on a flat surface whose shading normal is strongly tilted
+        let geometric = Vec3::new(0.0, 0.0, 1.0);
+        let shading = Vec3::new(0.9, 0.0, 0.3).normalize();
+        let material = Material::new(Color::white(), 0.0, 0.5, 0.5, 1.5, Color::black());
+        let intersection =
+            Intersection::new(1.0, Vec3::zero(), geometric, material).with_shading_normal(shading);
+        let ray = Ray::new(Vec3::new(-1.0, 0.0, 0.1), Vec3::new(1.0, 0.0, -0.1));
+
+        let branched = tracer.branch_rays(&ray, &intersection, vacuum, &mut Rng::new(0));
+        assert_eq!(branched.len(), 2);
+        for b in &branched {
+            let side = b.ray.direction.dot(geometric);
+            let origin_side = b.ray.origin.dot(geometric);
+            // Reflected rays leave above the surface, transmitted rays below
+            assert!(side * origin_side > 0.0);
+        }
+
+        // Branches remember the path length so far for texture filtering
+        let ray = ray.with_travelled(2.0);
+        for b in tracer.branch_rays(&ray, &intersection, vacuum, &mut Rng::new(0)) {
+            assert_eq!(b.ray.travelled, 3.0);
+        }
+    }
+
+    #[test]
+    fn test_render_independent_of_thread_count() {
+        use crate::raytracer::sphere::Sphere;
+
+        let camera = Camera::new(
+            Vec3::new(0.0, -5.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
+            60.0,
+            37,
+            23,
+            2,
+        );
+        let surfaces = [
+            Sphere::new(Vec3::new(0.0, 0.0, 0.0), 1.0, Material::glass(0.9)),
+            Sphere::new(Vec3::new(1.5, 1.0, 0.0), 0.7, Material::diffuse_surface()),
+        ];
+        let lights = [Light::new(Vec3::new(2.0, -3.0, 4.0), 0.5, Color::white())];
+        let vacuum = Material::new(Color::black(), 0.0, 0.0, 1.0, 1.0, Color::black());
+
+        let mut tracer = RayTracer::new(Color::new(0.1, 0.2, 0.3), 8, 1e-3, vacuum);
+        tracer.threads = 1;
+        tracer.tile_size = 64;
+        let reference = tracer.render(&camera, &surfaces, &lights);
+
+        tracer.threads = 4;
+        tracer.tile_size = 5;
+        let parallel = tracer.render(&camera, &surfaces, &lights);
+
+        assert_eq!(parallel.width, 37);
+        assert_eq!(parallel.height, 23);
+        for y in 0..23 {
+            for x in 0..37 {
+                assert_eq!(reference.get_pixel(x, y), parallel.get_pixel(x, y));
+            }
+        }
+    }
+
+    #[test]
+    fn test_path_tracing_diffuse_under_uniform_sky() {
+        use crate::raytracer::mesh::Triangle;
+
+        // A large diffuse floor lit only by a uniform white background:
+        // every cosine-sampled bounce escapes, so each path returns exactly albedo * sky
+        let floor = Triangle::new(
+            Vec3::new(-100.0, -100.0, 0.0),
+            Vec3::new(100.0, -100.0, 0.0),
+            Vec3::new(0.0, 100.0, 0.0),
+            Material::matte(Color::new(0.5, 0.25, 0.75), 1.0),
+        );
+        let vacuum = Material::new(Color::black(), 0.0, 0.0, 1.0, 1.0, Color::black());
+        let mut tracer = RayTracer::new(Color::white(), 8, 1e-3, vacuum);
+        tracer.integrator = Integrator::PathTracing;
+
+        let ray = Ray::new(Vec3::new(0.0, 0.0, 1.0), Vec3::new(0.1, 0.2, -1.0));
+        let lights: Vec<Light> = vec![];
+        for seed in 0..16 {
+            let mut rng = Rng::new(seed);
+            let color = tracer.trace_sample(&ray, &[floor], &lights, &mut rng);
+            assert!((color.r - 0.5).abs() < 1e-5);
+            assert!((color.g - 0.25).abs() < 1e-5);
+            assert!((color.b - 0.75).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_path_tracing_deterministic() {
+        use crate::raytracer::sphere::Sphere;
+
+        let camera = Camera::new(
+            Vec3::new(0.0, -5.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
+            60.0,
+            19,
+            11,
+            1,
+        );
+        let surfaces = [
+            Sphere::new(Vec3::new(0.0, 0.0, 0.0), 1.0, Material::glass(0.9)),
+            Sphere::new(
+                Vec3::new(0.0, 0.0, -101.0),
+                100.0,
+                Material::diffuse_surface(),
+            ),
+        ];
+        let lights = [Light::new(Vec3::new(2.0, -3.0, 4.0), 0.5, Color::white())];
+        let vacuum = Material::new(Color::black(), 0.0, 0.0, 1.0, 1.0, Color::black());
+
+        let mut tracer = RayTracer::new(Color::new(0.1, 0.2, 0.3), 8, 1e-3, vacuum);
+        tracer.integrator = Integrator::PathTracing;
+        tracer.samples_per_pixel = 4;
+        tracer.seed = 9;
+        tracer.threads = 1;
+        let reference = tracer.render(&camera, &surfaces, &lights);
+
+        tracer.threads = 3;
+        tracer.tile_size = 4;
+        let parallel = tracer.render(&camera, &surfaces, &lights);
+
+        tracer.seed = 10;
+        let reseeded = tracer.render(&camera, &surfaces, &lights);
+
+        let mut differs = false;
+        for y in 0..11 {
+            for x in 0..19 {
+                assert_eq!(reference.get_pixel(x, y), parallel.get_pixel(x, y));
+                differs |= reference.get_pixel(x, y) != reseeded.get_pixel(x, y);
+            }
+        }
+        assert!(differs);
+    }
+
+    #[test]
+    fn test_direct_light_inverse_square() {
+        let vacuum = Material::new(Color::black(), 0.0, 0.0, 1.0, 1.0, Color::black());
+        let mut tracer = RayTracer::new(Color::black(), 8, 1e-3, vacuum);
+        tracer.light_samples = 64;
+
+        let material = Material::matte(Color::white(), 1.0);
+        let up = Vec3::new(0.0, 0.0, 1.0);
+        let view = Ray::new(up, -up);
+        let surfaces: Vec<MockSurface> = vec![];
+        let mut rng = Rng::new(3);
+
+        // Irradiance from a sphere light straight above is π * L * (r / d)^2
+        let expected = |d: Float| 0.25 / (d * d);
+        for d in [2.0, 4.0, 8.0] {
+            let light = Light::new(Vec3::new(0.0, 0.0, d), 0.5, Color::white());
+            let intersection = Intersection::new(1.0, Vec3::zero(), up, material);
+            let color = tracer.compute_direct_light(
+                &view,
+                &intersection,
+                &light,
+                &surfaces,
+                std::slice::from_ref(&light),
+                &mut rng,
+            );
+            assert!((color.r - expected(d)).abs() / expected(d) < 0.02);
+        }
+    }
+
+    #[test]
+    fn test_direct_diffuse_matches_legacy_output() {
+        let vacuum = Material::new(Color::black(), 0.0, 0.0, 1.0, 1.0, Color::black());
+        let tracer = RayTracer::new(Color::black(), 8, 1e-3, vacuum);
+        let up = Vec3::new(0.0, 0.0, 1.0);
+        let view = Ray::new(up, -up);
+        let surfaces: Vec<MockSurface> = vec![];
+        let light = PointLight::new(Vec3::new(0.0, 0.0, 2.0), Color::new(10.0, 10.0, 10.0));
+        let albedo = Color::new(0.8, 0.4, 0.2);
+        let shade = |material: Material| {
+            let intersection = Intersection::new(1.0, Vec3::zero(), up, material);
+            tracer.compute_direct_light(
+                &view,
+                &intersection,
+                &light,
+                &surfaces,
+                std::slice::from_ref(&light),
+                &mut Rng::new(0),
+            )
+        };
+
+        // Fully diffuse materials keep the legacy albedo / π * irradiance (2.5 here)
+        let legacy = albedo * (2.5 / PI);
+        let matte = shade(Material::matte(albedo, 1.0));
+        assert!((matte.r - legacy.r).abs() < 1e-5);
+        assert!((matte.g - legacy.g).abs() < 1e-5);
+        assert!((matte.b - legacy.b).abs() < 1e-5);
+
+        // Partly diffuse materials are scaled by their diffuse rate
+        let floor = shade(Material::new(albedo, 0.4, 0.6, 0.0, 1.0, Color::black()));
+        assert!((floor.r - 0.4 * legacy.r).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_soft_shadow_penumbra() {
+        use crate::raytracer::sphere::Sphere;
+
+        let vacuum = Material::new(Color::black(), 0.0, 0.0, 1.0, 1.0, Color::black());
+        let mut tracer = RayTracer::new(Color::black(), 8, 1e-3, vacuum);
+        tracer.light_samples = 256;
+
+        let light = Light::new(Vec3::new(0.0, 0.0, 10.0), 2.0, Color::white());
+        let material = Material::matte(Color::white(), 1.0);
+        let up = Vec3::new(0.0, 0.0, 1.0);
+        let view = Ray::new(up, -up);
+        let mut rng = Rng::new(5);
+
+        // Occluder halfway up, covering only part of the light as seen from the origin
+        let occluder = [Sphere::new(Vec3::new(0.8, 0.0, 5.0), 0.8, material)];
+        let lit = Intersection::new(1.0, Vec3::zero(), up, material);
+        let unoccluded = tracer.compute_direct_light(
+            &view,
+            &lit,
+            &light,
+            &[] as &[Sphere],
+            std::slice::from_ref(&light),
+            &mut rng,
+        );
+        let penumbra = tracer.compute_direct_light(
+            &view,
+            &lit,
+            &light,
+            &occluder,
+            std::slice::from_ref(&light),
+            &mut rng,
+        );
+
+        assert!(penumbra.r > 0.1 * unoccluded.r);
+        assert!(penumbra.r < 0.9 * unoccluded.r);
+    }
+
+    #[test]
+    fn test_mesh_light_occludes_itself() {
+        use crate::raytracer::light::MeshLight;
+        use crate::raytracer::mesh::TriangleMesh;
+
+        let vacuum = Material::new(Color::black(), 0.0, 0.0, 1.0, 1.0, Color::black());
+        let mut tracer = RayTracer::new(Color::black(), 8, 1e-3, vacuum);
+        tracer.light_samples = 4096;
+
+        // Downward-facing emissive squares centered above the origin
+        let square = |z: Float, half: Float, first: u32| {
+            let positions = vec![
+                Vec3::new(-half, -half, z),
+                Vec3::new(-half, half, z),
+                Vec3::new(half, -half, z),
+                Vec3::new(half, half, z),
+            ];
+            let faces = vec![
+                [first, first + 1, first + 2],
+                [first + 3, first + 2, first + 1],
+            ];
+            (positions, faces)
+        };
+        let glow = Material::emissive(Color::white());
+        let (lower_positions, lower_faces) = square(2.0, 1.0, 0);
+        let (upper_positions, upper_faces) = square(3.0, 0.5, 4);
+        let (lower, _) = MeshLight::split(TriangleMesh::new(
+            lower_positions.clone(),
+            lower_faces.clone(),
+            glow,
+        ));
+        // The upper square is hidden from the origin behind the lower one
+        let (stacked, _) = MeshLight::split(TriangleMesh::new(
+            [lower_positions, upper_positions].concat(),
+            [lower_faces, upper_faces].concat(),
+            glow,
+        ));
+
+        let up = Vec3::new(0.0, 0.0, 1.0);
+        let view = Ray::new(up, -up);
+        let lit = Intersection::new(1.0, Vec3::zero(), up, Material::matte(Color::white(), 1.0));
+        let surfaces: Vec<MockSurface> = vec![];
+        let mut rng = Rng::new(9);
+        let expected = tracer.compute_direct_light(
+            &view,
+            &lit,
+            &lower,
+            &surfaces,
+            std::slice::from_ref(&lower),
+            &mut rng,
+        );
+        let occluded = tracer.compute_direct_light(
+            &view,
+            &lit,
+            &stacked,
+            &surfaces,
+            std::slice::from_ref(&stacked),
+            &mut rng,
+        );
+        assert!((occluded.r - expected.r).abs() / expected.r < 0.03);
+    }
+
+    #[test]
+    fn test_mixed_light_types() {
+        use crate::raytracer::sphere::Sphere;
+
+        let vacuum = Material::new(Color::black(), 0.0, 0.0, 1.0, 1.0, Color::black());
+        let tracer = RayTracer::new(Color::black(), 8, 1e-3, vacuum);
+        let material = Material::matte(Color::white(), 1.0);
+        let up = Vec3::new(0.0, 0.0, 1.0);
+        let view = Ray::new(up, -up);
+        let lit = Intersection::new(1.0, Vec3::zero(), up, material);
+        let mut rng = Rng::new(1);
+
+        let point = PointLight::new(Vec3::new(0.0, 0.0, 2.0), Color::white());
+        let spot = SpotLight::new(
+            Vec3::new(0.0, 0.0, 2.0),
+            Vec3::new(0.0, 0.0, -1.0),
+            Color::white(),
+            0.2,
+            0.3,
+        );
+        let sun = DirectionalLight::new(Vec3::new(0.0, 0.0, -1.0), Color::white());
+        let lights: Vec<&dyn LightSource> = vec![&point, &spot, &sun];
+
+        // Point and spot: (1 / π) * I / d²; sun: (1 / π) * E
+        let expected = (0.25 + 0.25 + 1.0) / PI;
+        let color =
+            tracer.compute_direct_lighting(&view, &lit, &[] as &[Sphere], &lights, &mut rng);
+        assert!((color.r - expected).abs() < 1e-5);
+
+        // An occluder above blocks all three
+        let occluder = [Sphere::new(Vec3::new(0.0, 0.0, 1.0), 0.5, material)];
+        let shadowed = tracer.compute_direct_lighting(&view, &lit, &occluder, &lights, &mut rng);
+        assert_eq!(shadowed, Color::black());
+
+        // Outside the spot cone only the point light and the sun remain
+        let aside = Intersection::new(1.0, Vec3::new(3.0, 0.0, 0.0), up, material);
+        let color =
+            tracer.compute_direct_lighting(&view, &aside, &[] as &[Sphere], &lights, &mut rng);
+        let point_only = 2.0 / (13.0 as Float).powf(1.5) / PI;
+        assert!((color.r - (point_only + 1.0 / PI)).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_sun_disk_visible_to_escaping_rays() {
+        let vacuum = Material::new(Color::black(), 0.0, 0.0, 1.0, 1.0, Color::black());
+        let tracer = RayTracer::new(Color::black(), 8, 1e-3, vacuum);
+        let sun = DirectionalLight::new(Vec3::new(0.0, 0.0, -1.0), Color::white())
+            .with_angular_diameter(0.01);
+        let surfaces: Vec<MockSurface> = vec![];
+
+        let towards_sun = Ray::new(Vec3::zero(), Vec3::new(0.0, 0.0, 1.0));
+        let away = Ray::new(Vec3::zero(), Vec3::new(1.0, 0.0, 0.0));
+        assert!(tracer.trace_ray(&towards_sun, &surfaces, &[sun]).r > 1.0);
+        assert_eq!(tracer.trace_ray(&away, &surfaces, &[sun]), Color::black());
+    }
+
+    #[test]
+    fn test_quad_light_seen_by_camera_rays() {
+        use crate::raytracer::sphere::Sphere;
+
+        let vacuum = Material::new(Color::black(), 0.0, 0.0, 1.0, 1.0, Color::black());
+        let tracer = RayTracer::new(Color::black(), 8, 1e-3, vacuum);
+        let panel = QuadLight::new(
+            Vec3::new(-1.0, -1.0, 3.0),
+            Vec3::new(0.0, 2.0, 0.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            Color::new(4.0, 4.0, 4.0),
+        );
+        let ray = Ray::new(Vec3::zero(), Vec3::new(0.0, 0.0, 1.0));
+
+        let surfaces: Vec<Sphere> = vec![];
+        assert_eq!(tracer.trace_ray(&ray, &surfaces, &[panel]), panel.emission);
+
+        // A surface in front of the panel hides it
+        let material = Material::matte(Color::white(), 1.0);
+        let blocker = [Sphere::new(Vec3::new(0.0, 0.0, 1.5), 0.5, material)];
+        assert_ne!(tracer.trace_ray(&ray, &blocker, &[panel]), panel.emission);
+    }
+
+    #[test]
+    fn test_emissive_surface_glows() {
+        use crate::raytracer::sphere::Sphere;
+
+        let vacuum = Material::new(Color::black(), 0.0, 0.0, 1.0, 1.0, Color::black());
+        let mut tracer = RayTracer::new(Color::black(), 8, 1e-3, vacuum);
+        let emission = Color::new(3.0, 2.0, 1.0);
+        let neon = [Sphere::new(
+            Vec3::new(0.0, 0.0, 5.0),
+            1.0,
+            Material::emissive(emission),
+        )];
+        let ray = Ray::new(Vec3::zero(), Vec3::new(0.0, 0.0, 1.0));
+        let lights: Vec<Light> = vec![];
+
+        assert_eq!(tracer.trace_ray(&ray, &neon, &lights), emission);
+        tracer.integrator = Integrator::PathTracing;
+        assert_eq!(tracer.trace_ray(&ray, &neon, &lights), emission);
+    }
+
+    #[test]
+    fn test_path_tracing_under_environment_light() {
+        use crate::raytracer::environment::EnvironmentLight;
+        use crate::raytracer::mesh::Triangle;
+
+        // Same floor as the uniform sky test, but lit by a uniform environment light:
+        // its contribution now comes from shadow rays instead of escaping bounces
+        let floor = Triangle::new(
+            Vec3::new(-100.0, -100.0, 0.0),
+            Vec3::new(100.0, -100.0, 0.0),
+            Vec3::new(0.0, 100.0, 0.0),
+            Material::matte(Color::new(0.5, 0.25, 0.75), 1.0),
+        );
+        let vacuum = Material::new(Color::black(), 0.0, 0.0, 1.0, 1.0, Color::black());
+        let mut tracer = RayTracer::new(Color::black(), 8, 1e-3, vacuum);
+        tracer.integrator = Integrator::PathTracing;
+        tracer.light_samples = 4;
+
+        let environment = EnvironmentLight::new(8, 4, vec![Color::white(); 32]);
+        let lights: [&dyn LightSource; 1] = [&environment];
+        let sky_ray = Ray::new(Vec3::new(0.0, 0.0, 1.0), Vec3::new(0.0, 0.0, 1.0));
+        let mut rng = Rng::new(0);
+        let sky = tracer.trace_sample(&sky_ray, &[floor], &lights, &mut rng);
+        assert_eq!(sky, Color::white());
+
+        let ray = Ray::new(Vec3::new(0.0, 0.0, 1.0), Vec3::new(0.1, 0.2, -1.0));
+        let n = 2000;
+        let mut total = Color::black();
+        for seed in 0..n {
+            let mut rng = Rng::new(seed);
+            total = total + tracer.trace_sample(&ray, &[floor], &lights, &mut rng);
+        }
+        let mean = total * (1.0 / n as Float);
+        assert!((mean.r - 0.5).abs() < 0.02);
+        assert!((mean.b - 0.75).abs() < 0.03);
+    }
+
+    #[test]
+    fn test_sky_is_miss_color() {
+        use crate::raytracer::sky::{SkyLight, sun_direction};
+
+        let vacuum = Material::new(Color::black(), 0.0, 0.0, 1.0, 1.0, Color::black());
+        let tracer = RayTracer::new(Color::black(), 8, 1e-3, vacuum);
+        let sky = SkyLight::new(sun_direction(0.8, 1.0), 3.0, Color::new(0.2, 0.2, 0.2));
+        let sun = sky.sun();
+        let lights: [&dyn LightSource; 2] = [&sky, &sun];
+        let surfaces: Vec<MockSurface> = vec![];
+
+        let up = Ray::new(Vec3::zero(), Vec3::new(0.0, 0.0, 1.0));
+        assert_eq!(
+            tracer.trace_ray(&up, &surfaces, &lights),
+            sky.escaped(up.direction)
+        );
+
+        // Looking straight at the sun adds its disk on top of the sky
+        let at_sun = Ray::new(Vec3::zero(), sky.sun_direction());
+        let color = tracer.trace_ray(&at_sun, &surfaces, &lights);
+        assert!(color.g > 1000.0 * sky.escaped(at_sun.direction).g);
+    }
+
+    #[test]
+    fn test_fresnel_dielectric_branch_weights() {
+        let vacuum = Material::new(Color::black(), 0.0, 0.0, 1.0, 1.0, Color::black());
+        let tracer = RayTracer::new(Color::black(), 8, 1e-3, vacuum);
+        let glass = Material::dielectric(1.5);
+        let normal = Vec3::new(0.0, 0.0, 1.0);
+        let intersection = Intersection::new(1.0, Vec3::zero(), normal, glass);
+
+        let weight_of = |branched: &[BranchedRay], kind: BranchKind| -> Float {
+            branched
+                .iter()
+                .filter(|b| b.kind == kind)
+                .map(|b| b.weight)
+                .sum()
+        };
+
+        // Head-on: mostly transmitted, about 4% reflected
+        let head_on = Ray::new(Vec3::new(0.0, 0.0, 1.0), Vec3::new(0.0, 0.0, -1.0));
+        let branched = tracer.branch_rays(&head_on, &intersection, vacuum, &mut Rng::new(0));
+        assert!((weight_of(&branched, BranchKind::Specular) - 0.04).abs() < 1e-3);
+        assert!((weight_of(&branched, BranchKind::Transmission) - 0.96).abs() < 1e-3);
+
+        // Grazing: mostly reflected
+        let grazing = Ray::new(
+            Vec3::new(-1.0, 0.0, 0.05),
+            Vec3::new(1.0, 0.0, -0.05).normalize(),
+        );
+        let branched = tracer.branch_rays(&grazing, &intersection, vacuum, &mut Rng::new(0));
+        assert!(weight_of(&branched, BranchKind::Specular) > 0.5);
+        let total = weight_of(&branched, BranchKind::Specular)
+            + weight_of(&branched, BranchKind::Transmission);
+        assert!((total - 1.0).abs() < 1e-5);
+
+        // From inside beyond the critical angle: total internal reflection
+        let inside = Ray::new(
+            Vec3::new(-1.0, 0.0, -1.0),
+            Vec3::new(1.0, 0.0, 1.0).normalize(),
+        );
+        let branched = tracer.branch_rays(&inside, &intersection, glass, &mut Rng::new(0));
+        assert_eq!(branched.len(), 1);
+        assert_eq!(branched[0].kind, BranchKind::Specular);
+        assert!((branched[0].weight - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_rough_reflection_branches() {
+        let vacuum = Material::new(Color::black(), 0.0, 0.0, 1.0, 1.0, Color::black());
+        let tracer = RayTracer::new(Color::black(), 8, 1e-3, vacuum);
+        let brushed = Material::mirror(Color::white(), 1.0).with_roughness(0.4);
+        let normal = Vec3::new(0.0, 0.0, 1.0);
+        let mut intersection = Intersection::new(1.0, Vec3::zero(), normal, brushed);
+        intersection.compute_tangent_frame();
+        let ray = Ray::new(
+            Vec3::new(-1.0, 0.0, 1.0),
+            Vec3::new(1.0, 0.0, -1.0).normalize(),
+        );
+        let mirror_direction = ray.direction.reflect(normal);
+
+        let mut rng = Rng::new(6);
+        let mut spread = 0.0;
+        for _ in 0..200 {
+            for branch in tracer.branch_rays(&ray, &intersection, vacuum, &mut rng) {
+                assert_eq!(branch.kind, BranchKind::Glossy);
+                assert!(branch.ray.direction.dot(normal) > 0.0);
+                assert!(branch.weight > 0.0 && branch.weight <= 1.0);
+                spread += 1.0 - branch.ray.direction.dot(mirror_direction);
+            }
+        }
+        // Directions scatter around, but stay close to, the mirror direction
+        assert!(spread > 0.0);
+        assert!(spread / 200.0 < 0.5);
+    }
+
+    #[test]
+    fn test_glossy_direct_lighting_highlight() {
+        let vacuum = Material::new(Color::black(), 0.0, 0.0, 1.0, 1.0, Color::black());
+        let mut tracer = RayTracer::new(Color::black(), 8, 1e-3, vacuum);
+        tracer.light_samples = 16;
+        let satin =
+            Material::new(Color::black(), 0.0, 1.0, 0.0, 1.0, Color::black()).with_roughness(0.3);
+        let up = Vec3::new(0.0, 0.0, 1.0);
+        let mut intersection = Intersection::new(1.0, Vec3::zero(), up, satin);
+        intersection.compute_tangent_frame();
+        let view = Ray::new(
+            Vec3::new(-1.0, 0.0, 1.0),
+            Vec3::new(1.0, 0.0, -1.0).normalize(),
+        );
+        let surfaces: Vec<MockSurface> = vec![];
+        let mut rng = Rng::new(8);
+
+        let in_mirror = PointLight::new(Vec3::new(3.0, 0.0, 3.0), Color::white());
+        let off_mirror = PointLight::new(Vec3::new(-3.0, 0.0, 3.0), Color::white());
+        let highlight = tracer.compute_direct_light(
+            &view,
+            &intersection,
+            &in_mirror,
+            &surfaces,
+            std::slice::from_ref(&in_mirror),
+            &mut rng,
+        );
+        let dim = tracer.compute_direct_light(
+            &view,
+            &intersection,
+            &off_mirror,
+            &surfaces,
+            std::slice::from_ref(&off_mirror),
+            &mut rng,
+        );
+        assert!(highlight.r > 10.0 * dim.r);
+
+        // A perfectly smooth mirror only reflects lights through branching
+        let smooth = Intersection::new(1.0, Vec3::zero(), up, satin.with_roughness(0.0));
+        let none = tracer.compute_direct_light(
+            &view,
+            &smooth,
+            &in_mirror,
+            &surfaces,
+            std::slice::from_ref(&in_mirror),
+            &mut rng,
+        );
+        assert_eq!(none, Color::black());
+    }
+
+    #[test]
+    fn test_branching_rough_glossy_matches_path_tracing() {
+        let vacuum = Material::new(Color::black(), 0.0, 0.0, 1.0, 1.0, Color::black());
+        let mut tracer = RayTracer::new(Color::black(), 8, 1e-3, vacuum);
+        tracer.light_samples = 4;
+        let satin =
+            Material::new(Color::black(), 0.0, 1.0, 0.0, 1.0, Color::black()).with_roughness(0.3);
+        let floor = [
+            Triangle::new(
+                Vec3::new(-10.0, -10.0, 0.0),
+                Vec3::new(10.0, -10.0, 0.0),
+                Vec3::new(10.0, 10.0, 0.0),
+                satin,
+            ),
+            Triangle::new(
+                Vec3::new(-10.0, -10.0, 0.0),
+                Vec3::new(10.0, 10.0, 0.0),
+                Vec3::new(-10.0, 10.0, 0.0),
+                satin,
+            ),
+        ];
+        let panel = [QuadLight::new(
+            Vec3::new(1.0, -1.0, 2.0),
+            Vec3::new(0.0, 2.0, 0.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            Color::new(4.0, 4.0, 4.0),
+        )];
+        // Looking at the light's mirror image in the rough floor
+        let ray = Ray::new(
+            Vec3::new(-2.0, 0.0, 2.0),
+            Vec3::new(1.0, 0.0, -1.0).normalize(),
+        );
+
+        let mean = |integrator: Integrator| {
+            let tracer = RayTracer {
+                integrator,
+                ..tracer
+            };
+            let n = 4000;
+            let mut total = Color::black();
+            for sample in 0..n {
+                let mut rng = Rng::for_sample(1, 0, sample);
+                total = total + tracer.trace_sample(&ray, &floor, &panel, &mut rng);
+            }
+            total.r / n as Float
+        };
+
+        let branching = mean(Integrator::Branching);
+        let path_tracing = mean(Integrator::PathTracing);
+        assert!(path_tracing > 0.1);
+        assert!((branching - path_tracing).abs() / path_tracing < 0.05);
+    }
+
+    #[test]
+    fn test_path_tracing_rough_metal_furnace() {
+        use crate::raytracer::environment::EnvironmentLight;
+        use crate::raytracer::mesh::Triangle;
+
+        // A rough mirror under a uniform white environment reflects at most all of it;
+        // counting lights both through direct lighting and glossy bounces would exceed 1
+        let floor = Triangle::new(
+            Vec3::new(-100.0, -100.0, 0.0),
+            Vec3::new(100.0, -100.0, 0.0),
+            Vec3::new(0.0, 100.0, 0.0),
+            Material::mirror(Color::black(), 1.0).with_roughness(0.5),
+        );
+        let vacuum = Material::new(Color::black(), 0.0, 0.0, 1.0, 1.0, Color::black());
+        let mut tracer = RayTracer::new(Color::black(), 8, 1e-3, vacuum);
+        tracer.integrator = Integrator::PathTracing;
+        tracer.light_samples = 4;
+
+        let environment = EnvironmentLight::new(8, 4, vec![Color::white(); 32]);
+        let lights: [&dyn LightSource; 1] = [&environment];
+        let ray = Ray::new(
+            Vec3::new(0.0, 0.0, 1.0),
+            Vec3::new(0.1, 0.2, -1.0).normalize(),
+        );
+
+        let n = 4000;
+        let mut total = 0.0;
+        for seed in 0..n {
+            let mut rng = Rng::new(seed);
+            total += tracer.trace_sample(&ray, &[floor], &lights, &mut rng).g;
+        }
+        let mean = total / n as Float;
+        assert!(mean > 0.85 && mean < 1.02);
+    }
+
+    #[test]
+    fn test_clear_coat_branches() {
+        let vacuum = Material::new(Color::black(), 0.0, 0.0, 1.0, 1.0, Color::black());
+        let tracer = RayTracer::new(Color::black(), 8, 1e-3, vacuum);
+        let lacquered = Material::matte(Color::new(0.8, 0.1, 0.1), 1.0).with_coat(1.0, 0.0);
+        let normal = Vec3::new(0.0, 0.0, 1.0);
+        let intersection = Intersection::new(1.0, Vec3::zero(), normal, lacquered);
+
+        let weight_of = |branched: &[BranchedRay], kind: BranchKind| -> Float {
+            branched
+                .iter()
+                .filter(|b| b.kind == kind)
+                .map(|b| b.weight)
+                .sum()
+        };
+
+        // Head-on the coat reflects about 4% and the base receives the rest
+        let head_on = Ray::new(Vec3::new(0.0, 0.0, 1.0), Vec3::new(0.0, 0.0, -1.0));
+        let branched = tracer.branch_rays(&head_on, &intersection, vacuum, &mut Rng::new(0));
+        assert!((weight_of(&branched, BranchKind::Specular) - 0.04).abs() < 1e-3);
+        assert!((weight_of(&branched, BranchKind::Diffuse) - 0.96).abs() < 1e-3);
+
+        // At grazing angles the coat dominates
+        let grazing = Ray::new(
+            Vec3::new(-1.0, 0.0, 0.05),
+            Vec3::new(1.0, 0.0, -0.05).normalize(),
+        );
+        let branched = tracer.branch_rays(&grazing, &intersection, vacuum, &mut Rng::new(0));
+        assert!(weight_of(&branched, BranchKind::Specular) > 0.5);
+        let total =
+            weight_of(&branched, BranchKind::Specular) + weight_of(&branched, BranchKind::Diffuse);
+        assert!((total - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_principled_metal_reflects_base_color() {
+        use crate::raytracer::material::PrincipledMaterial;
+        use crate::raytracer::mesh::Triangle;
+
+        // A smooth gold floor under a white sky reflects exactly its base color
+        let gold = Color::new(1.0, 0.8, 0.3);
+        let floor = Triangle::new(
+            Vec3::new(-100.0, -100.0, 0.0),
+            Vec3::new(100.0, -100.0, 0.0),
+            Vec3::new(0.0, 100.0, 0.0),
+            Material::from(PrincipledMaterial::metal(gold, 0.0)),
+        );
+        let vacuum = Material::new(Color::black(), 0.0, 0.0, 1.0, 1.0, Color::black());
+        let mut tracer = RayTracer::new(Color::white(), 8, 1e-3, vacuum);
+        let ray = Ray::new(
+            Vec3::new(0.0, 0.0, 1.0),
+            Vec3::new(0.1, 0.2, -1.0).normalize(),
+        );
+        let lights: Vec<Light> = vec![];
+
+        let color = tracer.trace_ray(&ray, &[floor], &lights);
+        assert!((color.g - 0.8).abs() < 1e-5);
+        assert!((color.b - 0.3).abs() < 1e-5);
+
+        tracer.integrator = Integrator::PathTracing;
+        let color = tracer.trace_ray(&ray, &[floor], &lights);
+        assert!((color.g - 0.8).abs() < 1e-5);
+        assert!((color.b - 0.3).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_sheen_brightens_grazing_views() {
+        let vacuum = Material::new(Color::black(), 0.0, 0.0, 1.0, 1.0, Color::black());
+        let tracer = RayTracer::new(Color::black(), 8, 1e-3, vacuum);
+        let cloth = Material::matte(Color::new(0.2, 0.2, 0.2), 1.0);
+        let velvet = cloth.with_sheen(Color::white());
+        let up = Vec3::new(0.0, 0.0, 1.0);
+        let light = PointLight::new(Vec3::new(-3.0, 0.0, 0.5), Color::white());
+        let surfaces: Vec<MockSurface> = vec![];
+        let mut rng = Rng::new(0);
+        let mut direct = |view: &Ray, material: Material| {
+            let intersection = Intersection::new(1.0, Vec3::zero(), up, material);
+            tracer.compute_direct_light(
+                view,
+                &intersection,
+                &light,
+                &surfaces,
+                std::slice::from_ref(&light),
+                &mut rng,
+            )
+        };
+
+        // Viewed from opposite the grazing light, sheen adds a strong rim
+        let opposite = Ray::new(
+            Vec3::new(3.0, 0.0, 0.5),
+            Vec3::new(-3.0, 0.0, -0.5).normalize(),
+        );
+        assert!(direct(&opposite, velvet).r > 2.0 * direct(&opposite, cloth).r);
+
+        // Viewed from the light's side, sheen has almost no effect
+        let along = Ray::new(
+            Vec3::new(-3.0, 0.0, 0.5),
+            Vec3::new(3.0, 0.0, -0.5).normalize(),
+        );
+        assert!(direct(&along, velvet).r < 1.01 * direct(&along, cloth).r);
+    }
+
+    #[test]
+    fn test_white_furnace() {
+        use crate::raytracer::material::PrincipledMaterial;
+
+        let vacuum = Material::new(Color::black(), 0.0, 0.0, 1.0, 1.0, Color::black());
+        let mut tracer = RayTracer::new(Color::black(), 8, 1e-3, vacuum);
+        tracer.light_samples = 4;
+
+        for integrator in [Integrator::Branching, Integrator::PathTracing] {
+            let tracer = RayTracer {
+                integrator,
+                ..tracer
+            };
+
+            // Energy-conserving materials return at most what they receive
+            let white_matte = tracer.furnace_test(Material::matte(Color::white(), 1.0), 1024);
+            assert!((white_matte.albedo - 1.0).abs() < 0.05);
+            assert!(!white_matte.gains_energy());
+            let grey = tracer.furnace_test(Material::matte(Color::white(), 0.5), 1024);
+            assert!((grey.albedo - 0.5).abs() < 0.03);
+            let glass = tracer.furnace_test(Material::dielectric(1.5), 256);
+            assert!(!glass.gains_energy());
+            let rough_mirror = Material::perfect_mirror().with_roughness(0.5);
+            assert!(!tracer.furnace_test(rough_mirror, 1024).gains_energy());
+            let lacquer = Material::from(
+                PrincipledMaterial::new(Color::new(0.9, 0.9, 0.9)).with_clearcoat(1.0, 0.2),
+            );
+            assert!(!tracer.furnace_test(lacquer, 1024).gains_energy());
+
+            // Rates summing above 1 are flagged
+            let mut bright = Material::matte(Color::white(), 1.0);
+            bright.specular_rate = 0.5;
+            let flagged = tracer.white_furnace(
+                [
+                    ("matte", Material::diffuse_surface()),
+                    ("bright", bright),
+                    ("mirror", Material::perfect_mirror()),
+                ],
+                1024,
+            );
+            assert_eq!(flagged.len(), 1);
+            assert_eq!(flagged[0].0, "bright");
+            assert!((flagged[0].1.albedo - 1.5).abs() < 0.1);
+
+            // Sheen on top of a white diffuse base adds the most light at grazing angles
+            let sheen = Material::matte(Color::white(), 1.0).with_sheen(Color::new(5.0, 5.0, 5.0));
+            let result = tracer.furnace_test(sheen, 1024);
+            assert!(result.gains_energy());
+            assert!(result.cos_theta <= 0.2);
+        }
+    }
+
+    #[test]
+    fn test_normal_map_shades_with_tilted_normal() {
+        use crate::raytracer::mesh::Triangle;
+        use crate::raytracer::texture::Textured;
+        use std::sync::Arc;
+
+        let vacuum = Material::new(Color::black(), 0.0, 0.0, 1.0, 1.0, Color::black());
+        let tracer = RayTracer::new(Color::black(), 8, 1e-3, vacuum);
+        let floor = Triangle::new(
+            Vec3::new(-1.0, -1.0, 0.0),
+            Vec3::new(1.0, -1.0, 0.0),
+            Vec3::new(-1.0, 1.0, 0.0),
+            Material::matte(Color::white(), 1.0),
+        );
+        let tilted = Textured::new(floor).with_normal_map(Arc::new(Color::new(1.0, 0.5, 1.0)));
+        let up = Vec3::new(0.0, 0.0, 1.0);
+        let view = Ray::new(Vec3::new(-0.5, -0.5, 1.0), -up);
+        let light = PointLight::new(Vec3::new(-0.5, -0.5, 1.0), Color::white());
+        let surfaces: Vec<MockSurface> = vec![];
+        let mut rng = Rng::new(0);
+
+        let flat_hit = floor.intersect(&view).unwrap();
+        let tilted_hit = tilted.intersect(&view).unwrap();
+        let flat = tracer.compute_direct_light(
+            &view,
+            &flat_hit,
+            &light,
+            &surfaces,
+            std::slice::from_ref(&light),
+            &mut rng,
+        );
+        let bumpy = tracer.compute_direct_light(
+            &view,
+            &tilted_hit,
+            &light,
+            &surfaces,
+            std::slice::from_ref(&light),
+            &mut rng,
+        );
+        assert!((bumpy.r - flat.r * (0.5 as Float).sqrt()).abs() < 1e-4);
+
+        // Secondary rays still leave from above the geometric surface
+        for branch in tracer.branch_rays(&view, &tilted_hit, vacuum, &mut rng) {
+            assert!(branch.ray.origin.z > 0.0);
+        }
+    }
 }
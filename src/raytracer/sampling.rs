@@ -0,0 +1,158 @@
+//! Random number generation and sampling helpers for Monte Carlo rendering.
+
+use super::vector::{Float, Vec3};
+use std::f32::consts::PI;
+
+/// Small, fast pseudo-random number generator (PCG32).
+///
+/// Every pixel sample gets its own generator derived from the render seed,
+/// so results are reproducible and independent of how work is split across threads.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    /// Internal 64-bit state
+    state: u64,
+    /// Stream selector (must be odd)
+    increment: u64,
+}
+
+impl Rng {
+    /// Create a new generator from a seed.
+    pub fn new(seed: u64) -> Self {
+        Self::with_stream(seed, 0)
+    }
+
+    /// Create a new generator from a seed and a stream index.
+    /// Different streams produce independent sequences for the same seed.
+    pub fn with_stream(seed: u64, stream: u64) -> Self {
+        let mut rng = Self {
+            state: 0,
+            increment: (stream << 1) | 1,
+        };
+        rng.next_u32();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.next_u32();
+        rng
+    }
+
+    /// Create the generator for one sample of one pixel.
+    pub fn for_sample(seed: u64, pixel: u64, sample: u64) -> Self {
+        Self::with_stream(mix(seed ^ mix(sample)), pixel)
+    }
+
+    /// Next uniformly distributed 32-bit integer.
+    pub fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.state = old
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(self.increment);
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        let rot = (old >> 59) as u32;
+        xorshifted.rotate_right(rot)
+    }
+
+    /// Next uniformly distributed float in [0, 1).
+    pub fn next_float(&mut self) -> Float {
+        (self.next_u32() >> 8) as Float * (1.0 / (1u32 << 24) as Float)
+    }
+}
+
+/// SplitMix64 finalizer, used to decorrelate seeds.
+fn mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E3779B97F4A7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+/// Build two unit vectors that form an orthonormal basis together with `normal`.
+/// `normal` must be normalized.
+pub fn orthonormal_basis(normal: Vec3) -> (Vec3, Vec3) {
+    // Duff et al. 2017, "Building an Orthonormal Basis, Revisited"
+    let sign = Float::copysign(1.0, normal.z);
+    let a = -1.0 / (sign + normal.z);
+    let b = normal.x * normal.y * a;
+    let tangent = Vec3::new(
+        1.0 + sign * normal.x * normal.x * a,
+        sign * b,
+        -sign * normal.x,
+    );
+    let bitangent = Vec3::new(b, sign + normal.y * normal.y * a, -normal.y);
+    (tangent, bitangent)
+}
+
+/// Sample a direction in the hemisphere around `normal` with a cosine-weighted distribution.
+/// The probability density is cos(θ) / π.
+///
+/// # Arguments
+/// * `normal` - Hemisphere axis (normalized)
+/// * `u1`, `u2` - Uniform random numbers in [0, 1)
+pub fn cosine_sample_hemisphere(normal: Vec3, u1: Float, u2: Float) -> Vec3 {
+    let r = u1.sqrt();
+    let phi = 2.0 * PI * u2;
+    let (tangent, bitangent) = orthonormal_basis(normal);
+    let z = (1.0 - u1).max(0.0).sqrt();
+    (tangent * (r * phi.cos()) + bitangent * (r * phi.sin()) + normal * z).normalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rng_deterministic() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        let mut c = Rng::new(43);
+
+        let seq_a: Vec<u32> = (0..8).map(|_| a.next_u32()).collect();
+        let seq_b: Vec<u32> = (0..8).map(|_| b.next_u32()).collect();
+        let seq_c: Vec<u32> = (0..8).map(|_| c.next_u32()).collect();
+
+        assert_eq!(seq_a, seq_b);
+        assert_ne!(seq_a, seq_c);
+    }
+
+    #[test]
+    fn test_rng_float_range_and_mean() {
+        let mut rng = Rng::for_sample(7, 123, 4);
+        let n = 10000;
+        let mut sum = 0.0;
+        for _ in 0..n {
+            let x = rng.next_float();
+            assert!((0.0..1.0).contains(&x));
+            sum += x;
+        }
+        assert!((sum / n as Float - 0.5).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_orthonormal_basis() {
+        for normal in [
+            Vec3::new(0.0, 0.0, 1.0),
+            Vec3::new(0.0, 0.0, -1.0),
+            Vec3::new(1.0, 2.0, 3.0).normalize(),
+        ] {
+            let (t, b) = orthonormal_basis(normal);
+            assert!((t.length() - 1.0).abs() < 1e-5);
+            assert!((b.length() - 1.0).abs() < 1e-5);
+            assert!(t.dot(normal).abs() < 1e-5);
+            assert!(b.dot(normal).abs() < 1e-5);
+            assert!(t.dot(b).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_cosine_sample_hemisphere() {
+        let normal = Vec3::new(0.0, 1.0, 0.0);
+        let mut rng = Rng::new(1);
+        let n = 10000;
+        let mut mean_cos = 0.0;
+        for _ in 0..n {
+            let dir = cosine_sample_hemisphere(normal, rng.next_float(), rng.next_float());
+            assert!(dir.dot(normal) >= 0.0);
+            mean_cos += dir.dot(normal);
+        }
+        // E[cos θ] under a cosine-weighted distribution is 2/3
+        assert!((mean_cos / n as Float - 2.0 / 3.0).abs() < 0.02);
+    }
+}
@@ -0,0 +1,227 @@
+//! Scene container owning geometry, lights, cameras and render settings.
+
+use super::Surface;
+use super::bvh::Bvh;
+use super::camera::Camera;
+use super::image::Image;
+use super::light::LightSource;
+use super::material::Material;
+use super::raytracer::RayTracer;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Everything needed to render a scene.
+///
+/// Surfaces and lights are shared trait objects, so one mesh can be referenced
+/// by several surfaces and the scene can be edited between frames without
+/// copying geometry. Call `build` to get a `BuiltScene` that can be rendered.
+#[derive(Clone)]
+pub struct Scene {
+    /// Renderer settings
+    pub raytracer: RayTracer,
+    /// Cameras the scene can be viewed through (the first one is the default)
+    pub cameras: Vec<Camera>,
+    /// Geometry of the scene
+    pub surfaces: Vec<Arc<dyn Surface>>,
+    /// Light sources, excluding the environment
+    pub lights: Vec<Arc<dyn LightSource>>,
+    /// Light arriving from infinitely far away (environment map or sky), if any
+    pub environment: Option<Arc<dyn LightSource>>,
+    /// Materials by name
+    pub materials: HashMap<String, Material>,
+}
+
+impl Scene {
+    /// Create an empty scene rendered with the given settings.
+    pub fn new(raytracer: RayTracer) -> Self {
+        Self {
+            raytracer,
+            cameras: Vec::new(),
+            surfaces: Vec::new(),
+            lights: Vec::new(),
+            environment: None,
+            materials: HashMap::new(),
+        }
+    }
+
+    /// Add a camera to the scene.
+    pub fn add_camera(&mut self, camera: Camera) {
+        self.cameras.push(camera);
+    }
+
+    /// Add a surface to the scene.
+    pub fn add_surface(&mut self, surface: impl Surface + 'static) {
+        self.surfaces.push(Arc::new(surface));
+    }
+
+    /// Add a light source to the scene.
+    pub fn add_light(&mut self, light: impl LightSource + 'static) {
+        self.lights.push(Arc::new(light));
+    }
+
+    /// Set the light arriving from infinitely far away, replacing the previous one.
+    pub fn set_environment(&mut self, environment: impl LightSource + 'static) {
+        self.environment = Some(Arc::new(environment));
+    }
+
+    /// Register a named material. Returns the material previously using this name, if any.
+    pub fn add_material(&mut self, name: &str, material: Material) -> Option<Material> {
+        self.materials.insert(name.to_string(), material)
+    }
+
+    /// Prepare the scene for rendering.
+    ///
+    /// Builds the bounding volume hierarchy over all surfaces and collects the lights
+    /// with the environment into the list used for direct lighting. Lights prepare their
+    /// own sampling tables (environment importance maps, mesh area tables) when they are
+    /// created, so building only shares them.
+    pub fn build(&self) -> BuiltScene {
+        let mut lights = self.lights.clone();
+        lights.extend(self.environment.clone());
+
+        BuiltScene {
+            raytracer: self.raytracer,
+            cameras: self.cameras.clone(),
+            bvh: Bvh::new(self.surfaces.clone()),
+            lights,
+        }
+    }
+}
+
+/// A scene prepared for rendering by `Scene::build`.
+///
+/// Immutable and `Send + Sync`, so render threads share it without copying.
+pub struct BuiltScene {
+    /// Renderer settings
+    pub raytracer: RayTracer,
+    /// Cameras the scene can be viewed through
+    pub cameras: Vec<Camera>,
+    /// Hierarchy over all surfaces
+    bvh: Bvh<Arc<dyn Surface>>,
+    /// Light sources followed by the environment
+    lights: Vec<Arc<dyn LightSource>>,
+}
+
+impl BuiltScene {
+    /// Render the image seen by one of the scene's cameras.
+    ///
+    /// # Panics
+    /// Panics if there is no camera at `camera_index`.
+    pub fn render(&self, camera_index: usize) -> Image {
+        self.render_with(&self.cameras[camera_index])
+    }
+
+    /// Render the scene through a camera that is not part of it.
+    pub fn render_with(&self, camera: &Camera) -> Image {
+        self.raytracer
+            .render(camera, std::slice::from_ref(&self.bvh), &self.lights)
+    }
+
+    /// Number of surfaces in the scene.
+    pub fn surface_count(&self) -> usize {
+        self.bvh.len()
+    }
+
+    /// Light sources used for direct lighting, including the environment.
+    pub fn lights(&self) -> &[Arc<dyn LightSource>] {
+        &self.lights
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raytracer::light::{Light, PointLight};
+    use crate::raytracer::material::Color;
+    use crate::raytracer::sky::SkyLight;
+    use crate::raytracer::sphere::Sphere;
+    use crate::raytracer::vector::Vec3;
+
+    fn test_scene() -> Scene {
+        let vacuum = Material::new(Color::black(), 0.0, 0.0, 1.0, 1.0, Color::black());
+        let mut scene = Scene::new(RayTracer::new(Color::black(), 4, 1e-3, vacuum));
+        scene.add_camera(Camera::new(
+            Vec3::new(0.0, -5.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
+            40.0,
+            16,
+            16,
+            1,
+        ));
+        scene.add_material("red", Material::matte(Color::new(1.0, 0.0, 0.0), 1.0));
+        scene.add_surface(Sphere::new(
+            Vec3::new(0.0, 0.0, 0.0),
+            1.0,
+            scene.materials["red"],
+        ));
+        scene.add_light(PointLight::new(
+            Vec3::new(0.0, -5.0, 5.0),
+            Color::new(50.0, 50.0, 50.0),
+        ));
+        scene
+    }
+
+    #[test]
+    fn test_built_scene_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Scene>();
+        assert_send_sync::<BuiltScene>();
+    }
+
+    #[test]
+    fn test_build_collects_lights_and_environment() {
+        let mut scene = test_scene();
+        scene.set_environment(SkyLight::new(
+            Vec3::new(0.0, 0.0, 1.0),
+            3.0,
+            Color::new(0.3, 0.3, 0.3),
+        ));
+        let built = scene.build();
+
+        assert_eq!(built.surface_count(), 1);
+        assert_eq!(built.lights().len(), 2);
+    }
+
+    #[test]
+    fn test_render_matches_loose_lists() {
+        let scene = test_scene();
+        let image = scene.build().render(0);
+
+        let surfaces = [Sphere::new(
+            Vec3::new(0.0, 0.0, 0.0),
+            1.0,
+            scene.materials["red"],
+        )];
+        let lights = [PointLight::new(
+            Vec3::new(0.0, -5.0, 5.0),
+            Color::new(50.0, 50.0, 50.0),
+        )];
+        let expected = scene
+            .raytracer
+            .render(&scene.cameras[0], &surfaces, &lights);
+
+        for y in 0..image.height {
+            for x in 0..image.width {
+                assert_eq!(image.get_pixel(x, y), expected.get_pixel(x, y));
+            }
+        }
+        assert!(image.get_pixel(8, 8).unwrap().r > 0.0);
+    }
+
+    #[test]
+    fn test_scene_edits_do_not_affect_built_scene() {
+        let mut scene = test_scene();
+        let built = scene.build();
+        scene.add_light(Light::new(
+            Vec3::new(0.0, 0.0, 5.0),
+            0.5,
+            Color::new(10.0, 10.0, 10.0),
+        ));
+        scene.surfaces.clear();
+
+        assert_eq!(built.surface_count(), 1);
+        assert_eq!(built.lights().len(), 1);
+        assert_eq!(scene.build().surface_count(), 0);
+    }
+}
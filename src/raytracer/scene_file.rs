@@ -0,0 +1,1737 @@
+//! Text scene description format and its loader.
+//!
+//! A scene file is a list of blocks. A block starts with a keyword at the
+//! beginning of a line, followed by indented `field value...` lines. Anything
+//! after `#` is a comment. Colors are three numbers between 0 and 1 (or one
+//! number for a grey), positions and directions are three numbers in meters
+//! with Z pointing up, and angles are in degrees.
+//!
+//! ```text
+//! # A glass ball on a floor
+//! settings
+//!     background 0.1 0.1 0.1
+//!     max_depth 16
+//!
+//! camera
+//!     position 0 -3 3
+//!     look_at 0 0 0.5
+//!     fov 60
+//!     resolution 1920 1080
+//!
+//! material glass
+//!     albedo 1 1 1
+//!     transmission 0.9
+//!     specular 0.1
+//!     ior 1.5
+//!     fresnel exact
+//!
+//! sphere
+//!     center 0 0 0.5
+//!     radius 0.5
+//!     material glass
+//!
+//! light sphere
+//!     center 0 0 10
+//!     radius 2
+//!     power 4000
+//! ```
+//!
+//! Blocks and their fields (fields in brackets are optional, with their default):
+//!
+//! * `settings` - `[background 0 0 0]`, `[max_depth 8]`, `[min_weight 0.001]`,
+//!   `[integrator branching|path]`, `[samples_per_pixel 1]`, `[light_samples 1]`,
+//!   `[seed 0]`, `[threads 0]` (0 = all cores), `[tile_size 32]`
+//! * `camera` - `position`, `direction` or `look_at`, `[up 0 0 1]`, `[fov 60]`,
+//!   `[resolution 640 480]`, `[subdivisions 1]`. A scene may have several cameras.
+//! * `material <name>` - `[albedo 1 1 1]`, `[specular 0]`, `[transmission 0]`,
+//!   `[diffuse]` (defaults to the rate left by specular and transmission),
+//!   `[ior 1.5]`, `[absorption 0 0 0]`, `[emission 0 0 0]`,
+//!   `[fresnel none|schlick|exact]`, `[roughness 0]`, `[specular_tint 1 1 1]`,
+//!   `[coat 0]`, `[coat_roughness 0]`, `[sheen 0 0 0]`
+//!   Textures named by `[albedo_map]`, `[diffuse_map]`, `[specular_map]`,
+//!   `[transmission_map]`, `[roughness_map]`, `[normal_map]` and `[bump_map]`
+//!   (with `[bump_scale 0.01]`, in meters) are applied to every object using the material.
+//! * `principled <name>` - `[base_color 0.8 0.8 0.8]`, `[metallic 0]`, `[roughness 0.5]`,
+//!   `[specular 0.5]`, `[transmission 0]`, `[clearcoat 0]`, `[clearcoat_roughness 0.03]`,
+//!   `[sheen 0]`, `[sheen_tint 0.5]`, `[ior 1.5]`, `[emission 0 0 0]`
+//! * `texture <name>` - `type image|checker|gradient|noise` and the fields of that type:
+//!   * `image` - `path` relative to the scene file, `[data no]` (yes for linear values such
+//!     as normal or roughness maps), `[wrap repeat|clamp|mirror]`,
+//!     `[filter trilinear|bilinear|nearest]`
+//!   * `checker` - `[even 1 1 1]`, `[odd 0 0 0]`, `[scale 1]` (squares per unit of UV)
+//!   * `gradient` - `[start 0 0 0]`, `[end 1 1 1]`, `[axis u|v]`
+//!   * `noise` - `[low 0 0 0]`, `[high 1 1 1]`, `[scale 1]` (features per meter),
+//!     `[octaves 1]`, `[seed 0]`
+//! * `sphere` - `center`, `radius`, `[material]`. A sphere with an emissive material becomes a
+//!   sphere light.
+//! * `triangle` - `vertices` (9 numbers), `[normals]` (9 numbers), `[uvs]` (6 numbers), `[material]`
+//! * `mesh` - `path` to an OBJ file relative to the scene file, `[material]` replacing the MTL materials.
+//!   Faces with an emissive material become a mesh light, emitting from both sides with
+//!   `[two_sided no]` set to yes. The mesh is placed by `[scale 1 1 1]`, then
+//!   `[rotate 0 0 1 0]` (axis, then angle), then `[translate 0 0 0]`.
+//! * `light point` - `position`, `intensity`, `power` (watts) or `lumens`, `[color 1 1 1]`
+//! * `light sphere` - `center`, `radius`, `emission`, `power` or `lumens`, `[color 1 1 1]`
+//! * `light spot` - `position`, `direction`, `inner_angle`, `outer_angle`,
+//!   `intensity`, `power` or `lumens`, `[color 1 1 1]`, `[falloff 1]`
+//! * `light directional` - `direction` the light travels in, `irradiance`, `[angular_diameter 0]`
+//! * `light quad` - `corner`, `edge_u`, `edge_v`, `emission`, `[two_sided no]`
+//! * `light disk` - `center`, `normal`, `radius`, `emission`, `[two_sided no]`
+//! * `environment` - `path` to an HDR/EXR image, `[scale 1]`, `[rotation 0]`
+//! * `sky` - `sun_elevation`, `[sun_azimuth 0]`, `[turbidity 3]`,
+//!   `[ground_albedo 0.3 0.3 0.3]`, `[sun yes]` (adds the matching sun light)
+//!
+//! A scene has at most one `environment` or `sky` block. Materials and textures may be
+//! used before the block that defines them. Objects without a
+//! material use `Material::diffuse_surface()`. Image textures pick their mip levels
+//! for the first camera.
+
+use super::Surface;
+use super::camera::Camera;
+use super::environment::EnvironmentLight;
+use super::light::{
+    DirectionalLight, DiskLight, LUMENS_PER_WATT, Light, LightSource, MeshLight, PointLight,
+    QuadLight, SpotLight,
+};
+use super::material::{Color, Fresnel, Material, MaterialError, PrincipledMaterial};
+use super::mesh::{Triangle, TriangleMesh};
+use super::obj::{ObjError, load_obj};
+use super::raytracer::{Integrator, RayTracer};
+use super::scene::Scene;
+use super::sky::{SkyLight, sun_direction};
+use super::sphere::Sphere;
+use super::texture::{
+    Checker, Filter, Gradient, GradientAxis, ImageTexture, Noise, Texture, Textured, WrapMode,
+};
+use super::transform::{Transform, Transformable};
+use super::vector::{Float, Vec3};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Keywords that start a block.
+const BLOCKS: [&str; 11] = [
+    "settings",
+    "camera",
+    "material",
+    "principled",
+    "texture",
+    "sphere",
+    "triangle",
+    "mesh",
+    "light",
+    "environment",
+    "sky",
+];
+
+/// Error produced while loading a scene file.
+#[derive(Debug)]
+pub enum SceneError {
+    /// The scene file could not be read
+    Io {
+        /// Path of the file that failed to load
+        path: PathBuf,
+        /// Underlying I/O error
+        error: std::io::Error,
+    },
+    /// A line or block of the scene file is invalid
+    Parse {
+        /// Name of the scene file
+        file: String,
+        /// Line number (1-based, 0 for problems with the file as a whole)
+        line: usize,
+        /// Description of the problem
+        message: String,
+    },
+    /// A mesh referenced by the scene failed to load
+    Mesh(ObjError),
+    /// An environment or texture image referenced by the scene failed to load
+    Image {
+        /// Path of the image
+        path: PathBuf,
+        /// Underlying decoding error
+        error: ::image::ImageError,
+    },
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneError::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+            SceneError::Parse {
+                file,
+                line: 0,
+                message,
+            } => write!(f, "{}: {}", file, message),
+            SceneError::Parse {
+                file,
+                line,
+                message,
+            } => write!(f, "{}:{}: {}", file, line, message),
+            SceneError::Mesh(error) => error.fmt(f),
+            SceneError::Image { path, error } => write!(f, "{}: {}", path.display(), error),
+        }
+    }
+}
+
+impl std::error::Error for SceneError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SceneError::Io { error, .. } => Some(error),
+            SceneError::Parse { .. } => None,
+            SceneError::Mesh(error) => Some(error),
+            SceneError::Image { error, .. } => Some(error),
+        }
+    }
+}
+
+impl From<ObjError> for SceneError {
+    fn from(error: ObjError) -> Self {
+        SceneError::Mesh(error)
+    }
+}
+
+/// Load a scene file. Meshes and images are resolved relative to its directory.
+pub fn load_scene(path: impl AsRef<Path>) -> Result<Scene, SceneError> {
+    let path = path.as_ref();
+    let source = std::fs::read_to_string(path).map_err(|error| SceneError::Io {
+        path: path.to_path_buf(),
+        error,
+    })?;
+    let directory = path.parent().unwrap_or(Path::new(""));
+    parse_scene(&source, &path.display().to_string(), directory)
+}
+
+/// Parse scene source text.
+///
+/// # Arguments
+/// * `source` - Contents of the scene file
+/// * `file` - File name used in error messages
+/// * `directory` - Directory that mesh and image paths are relative to
+pub fn parse_scene(source: &str, file: &str, directory: &Path) -> Result<Scene, SceneError> {
+    let blocks = split_blocks(source, file)?;
+
+    let mut scene = Scene::new(default_raytracer());
+
+    // Textures filter for the footprint of the default camera's pixels
+    let pixel_spread = match blocks.iter().find(|b| b.keyword == "camera") {
+        Some(block) => block.camera()?.pixel_spread(),
+        None => 0.0,
+    };
+
+    // Textures and materials first, so objects can refer to them before their definition
+    let mut textures = HashMap::new();
+    for block in blocks.iter().filter(|b| b.keyword == "texture") {
+        let name = block.name()?;
+        let texture = block.texture(directory)?;
+        block.finish()?;
+        if textures.insert(name, texture).is_some() {
+            return Err(block.error(block.line, format!("texture '{}' is defined twice", name)));
+        }
+    }
+
+    let mut material_maps = HashMap::new();
+    for block in blocks
+        .iter()
+        .filter(|b| matches!(b.keyword, "material" | "principled"))
+    {
+        let name = block.name()?;
+        let material = if block.keyword == "material" {
+            if let Some(maps) = block.material_maps(&textures, pixel_spread)? {
+                material_maps.insert(name, maps);
+            }
+            block.material()?
+        } else {
+            block.principled()?
+        };
+        block.finish()?;
+        if scene.add_material(name, material).is_some() {
+            return Err(block.error(block.line, format!("material '{}' is defined twice", name)));
+        }
+    }
+
+    let mut has_settings = false;
+    for block in &blocks {
+        match block.keyword {
+            "material" | "principled" | "texture" => continue,
+            "settings" => {
+                block.no_arguments()?;
+                if std::mem::replace(&mut has_settings, true) {
+                    return Err(
+                        block.error(block.line, "only one settings block is allowed".into())
+                    );
+                }
+                scene.raytracer = block.settings()?;
+            }
+            "camera" => {
+                block.no_arguments()?;
+                scene.add_camera(block.camera()?);
+            }
+            "sphere" => {
+                block.no_arguments()?;
+                let sphere = block.sphere(&scene.materials)?;
+                // Like emissive mesh faces, a glowing sphere is sampled as a light
+                if sphere.material.emission.luminance() > 0.0 {
+                    scene.add_light(Light::from_sphere(&sphere));
+                } else {
+                    add_surface(&mut scene, sphere, block.maps_ref(&material_maps)?);
+                }
+            }
+            "triangle" => {
+                block.no_arguments()?;
+                let triangle = block.triangle(&scene.materials)?;
+                add_surface(&mut scene, triangle, block.maps_ref(&material_maps)?);
+            }
+            "mesh" => {
+                block.no_arguments()?;
+                // Emissive faces belong to a light, the rest is drawn as a surface
+                let two_sided = block.flag("two_sided")?.unwrap_or(false);
+                for mesh in block.meshes(&scene.materials, directory)? {
+                    let (light, surface) = MeshLight::split(mesh);
+                    if light.mesh().face_count() > 0 {
+                        scene.add_light(light.with_two_sided(two_sided));
+                    }
+                    if let Some(surface) = surface {
+                        add_surface(&mut scene, surface, block.maps_ref(&material_maps)?);
+                    }
+                }
+            }
+            "light" => scene.lights.push(block.light()?),
+            "environment" | "sky" => {
+                block.no_arguments()?;
+                if scene.environment.is_some() {
+                    return Err(block.error(
+                        block.line,
+                        "only one environment or sky block is allowed".into(),
+                    ));
+                }
+                if block.keyword == "environment" {
+                    scene.set_environment(block.environment(directory)?);
+                } else {
+                    let (sky, sun) = block.sky()?;
+                    scene.set_environment(sky);
+                    if let Some(sun) = sun {
+                        scene.add_light(sun);
+                    }
+                }
+            }
+            _ => unreachable!("split_blocks only accepts known keywords"),
+        }
+        block.finish()?;
+    }
+
+    if scene.cameras.is_empty() {
+        return Err(SceneError::Parse {
+            file: file.to_string(),
+            line: 0,
+            message: "the scene has no camera block".into(),
+        });
+    }
+    Ok(scene)
+}
+
+/// Add a surface, wrapped in the textures of its material if it has any.
+fn add_surface<S: Surface + 'static>(scene: &mut Scene, surface: S, maps: Option<&MaterialMaps>) {
+    match maps {
+        Some(maps) => scene.add_surface(maps.wrap(surface)),
+        None => scene.add_surface(surface),
+    }
+}
+
+/// Renderer used when the scene has no `settings` block.
+fn default_raytracer() -> RayTracer {
+    let vacuum = Material::new(Color::black(), 0.0, 0.0, 1.0, 1.0, Color::black());
+    RayTracer::new(Color::black(), 8, 1e-3, vacuum)
+}
+
+/// Split the source into blocks of fields.
+fn split_blocks<'a>(source: &'a str, file: &'a str) -> Result<Vec<Block<'a>>, SceneError> {
+    let mut blocks: Vec<Block> = Vec::new();
+
+    for (line_idx, raw_line) in source.lines().enumerate() {
+        let line_number = line_idx + 1;
+        let error = |message: String| SceneError::Parse {
+            file: file.to_string(),
+            line: line_number,
+            message,
+        };
+
+        let line = raw_line.split('#').next().unwrap_or("");
+        let mut tokens = line.split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            continue;
+        };
+        let args: Vec<&str> = tokens.collect();
+
+        if !line.starts_with([' ', '\t']) {
+            if !BLOCKS.contains(&keyword) {
+                return Err(error(format!(
+                    "unknown block '{}' (expected one of: {})",
+                    keyword,
+                    BLOCKS.join(", ")
+                )));
+            }
+            blocks.push(Block {
+                file,
+                keyword,
+                args,
+                line: line_number,
+                fields: Vec::new(),
+            });
+            continue;
+        }
+
+        let Some(block) = blocks.last_mut() else {
+            return Err(error(format!(
+                "field '{}' is outside of a block (only block keywords start at the beginning of a line)",
+                keyword
+            )));
+        };
+        if let Some(previous) = block.fields.iter().find(|f| f.key == keyword) {
+            return Err(error(format!(
+                "'{}' is already set on line {}",
+                keyword, previous.line
+            )));
+        }
+        block.fields.push(Field {
+            key: keyword,
+            values: args,
+            line: line_number,
+            used: Cell::new(false),
+        });
+    }
+
+    Ok(blocks)
+}
+
+/// One `field value...` line of a block.
+struct Field<'a> {
+    key: &'a str,
+    values: Vec<&'a str>,
+    line: usize,
+    /// Set when the block reader consumes the field, to report unknown fields
+    used: Cell<bool>,
+}
+
+/// A block keyword with its arguments and fields.
+struct Block<'a> {
+    file: &'a str,
+    keyword: &'a str,
+    args: Vec<&'a str>,
+    line: usize,
+    fields: Vec<Field<'a>>,
+}
+
+/// Textures a `material` block applies to the objects using it.
+#[derive(Default)]
+struct MaterialMaps {
+    albedo: Option<Arc<dyn Texture>>,
+    diffuse_rate: Option<Arc<dyn Texture>>,
+    specular_rate: Option<Arc<dyn Texture>>,
+    transmission_rate: Option<Arc<dyn Texture>>,
+    roughness: Option<Arc<dyn Texture>>,
+    normal_map: Option<Arc<dyn Texture>>,
+    bump_map: Option<Arc<dyn Texture>>,
+    bump_scale: Float,
+    pixel_spread: Float,
+}
+
+impl MaterialMaps {
+    /// Wrap a surface in the textures.
+    fn wrap<S: Surface>(&self, surface: S) -> Textured<S> {
+        Textured {
+            surface,
+            albedo: self.albedo.clone(),
+            diffuse_rate: self.diffuse_rate.clone(),
+            specular_rate: self.specular_rate.clone(),
+            transmission_rate: self.transmission_rate.clone(),
+            roughness: self.roughness.clone(),
+            normal_map: self.normal_map.clone(),
+            bump_map: self.bump_map.clone(),
+            bump_scale: self.bump_scale,
+            pixel_spread: self.pixel_spread,
+        }
+    }
+}
+
+/// Emitted light given either directly or as a total power.
+enum Strength {
+    /// Radiance, intensity or irradiance, depending on the light
+    Direct(Color),
+    /// Total power in watts with the hue of the color (converted from lumens if given so)
+    Power(Color, Float),
+}
+
+impl<'a> Block<'a> {
+    fn error(&self, line: usize, message: String) -> SceneError {
+        SceneError::Parse {
+            file: self.file.to_string(),
+            line,
+            message,
+        }
+    }
+
+    /// Block keyword with its arguments, as written in the file.
+    fn title(&self) -> String {
+        std::iter::once(self.keyword)
+            .chain(self.args.iter().copied())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn no_arguments(&self) -> Result<(), SceneError> {
+        if self.args.is_empty() {
+            Ok(())
+        } else {
+            Err(self.error(
+                self.line,
+                format!(
+                    "'{}' takes no arguments, found '{}'",
+                    self.keyword,
+                    self.args.join(" ")
+                ),
+            ))
+        }
+    }
+
+    /// The single name argument of a material or texture block.
+    fn name(&self) -> Result<&'a str, SceneError> {
+        let kind = if self.keyword == "texture" {
+            "texture"
+        } else {
+            "material"
+        };
+        match self.args[..] {
+            [name] => Ok(name),
+            [] => Err(self.error(self.line, format!("'{}' needs a name", self.keyword))),
+            _ => Err(self.error(
+                self.line,
+                format!(
+                    "{} names cannot contain spaces: '{}'",
+                    kind,
+                    self.args.join(" ")
+                ),
+            )),
+        }
+    }
+
+    /// Report the first field that no reader asked for.
+    fn finish(&self) -> Result<(), SceneError> {
+        match self.fields.iter().find(|f| !f.used.get()) {
+            Some(field) => Err(self.error(
+                field.line,
+                format!("unknown field '{}' in {}", field.key, self.title()),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn field(&self, key: &str) -> Option<&Field<'a>> {
+        let field = self.fields.iter().find(|f| f.key == key)?;
+        field.used.set(true);
+        Some(field)
+    }
+
+    fn require<T>(&self, key: &str, value: Option<T>) -> Result<T, SceneError> {
+        value.ok_or_else(|| self.error(self.line, format!("{} is missing '{}'", self.title(), key)))
+    }
+
+    fn numbers<const N: usize>(&self, key: &str) -> Result<Option<[Float; N]>, SceneError> {
+        let Some(field) = self.field(key) else {
+            return Ok(None);
+        };
+        if field.values.len() != N {
+            let expected = if N == 1 {
+                "a number".into()
+            } else {
+                format!("{} numbers", N)
+            };
+            return Err(self.error(
+                field.line,
+                format!(
+                    "'{}' expects {}, found {}",
+                    key,
+                    expected,
+                    field.values.len()
+                ),
+            ));
+        }
+        let mut numbers = [0.0; N];
+        for (number, token) in numbers.iter_mut().zip(&field.values) {
+            *number = parse_number(token).map_err(|message| self.error(field.line, message))?;
+        }
+        Ok(Some(numbers))
+    }
+
+    fn float(&self, key: &str) -> Result<Option<Float>, SceneError> {
+        Ok(self.numbers::<1>(key)?.map(|[x]| x))
+    }
+
+    /// A number that must be greater than zero, such as a radius.
+    fn positive(&self, key: &str) -> Result<Option<Float>, SceneError> {
+        match self.float(key)? {
+            Some(value) if value <= 0.0 => Err(self.error(
+                self.field(key).map_or(self.line, |f| f.line),
+                format!("'{}' must be positive, found {}", key, value),
+            )),
+            value => Ok(value),
+        }
+    }
+
+    fn vec3(&self, key: &str) -> Result<Option<Vec3>, SceneError> {
+        Ok(self.numbers::<3>(key)?.map(|[x, y, z]| Vec3::new(x, y, z)))
+    }
+
+    /// A color written as three channels or a single grey value.
+    fn color(&self, key: &str) -> Result<Option<Color>, SceneError> {
+        let Some(field) = self.field(key) else {
+            return Ok(None);
+        };
+        let parse =
+            |token: &&str| parse_number(token).map_err(|message| self.error(field.line, message));
+        match field.values[..] {
+            [grey] => {
+                let grey = parse(&grey)?;
+                Ok(Some(Color::new(grey, grey, grey)))
+            }
+            [r, g, b] => Ok(Some(Color::new(parse(&r)?, parse(&g)?, parse(&b)?))),
+            _ => Err(self.error(
+                field.line,
+                format!(
+                    "'{}' expects a color (3 numbers, or 1 for a grey), found {} values",
+                    key,
+                    field.values.len()
+                ),
+            )),
+        }
+    }
+
+    fn integer<T: FromStr>(&self, key: &str) -> Result<Option<T>, SceneError> {
+        match self.word(key)? {
+            Some(token) => token.parse().map(Some).map_err(|_| {
+                self.error(
+                    self.field(key).map_or(self.line, |f| f.line),
+                    format!("'{}' expects a whole number, found '{}'", key, token),
+                )
+            }),
+            None => Ok(None),
+        }
+    }
+
+    /// A single word value.
+    fn word(&self, key: &str) -> Result<Option<&'a str>, SceneError> {
+        let Some(field) = self.field(key) else {
+            return Ok(None);
+        };
+        match field.values[..] {
+            [word] => Ok(Some(word)),
+            _ => Err(self.error(
+                field.line,
+                format!("'{}' expects one value, found {}", key, field.values.len()),
+            )),
+        }
+    }
+
+    /// A value running to the end of the line, such as a path with spaces.
+    fn text(&self, key: &str) -> Result<Option<String>, SceneError> {
+        let Some(field) = self.field(key) else {
+            return Ok(None);
+        };
+        if field.values.is_empty() {
+            return Err(self.error(field.line, format!("'{}' needs a value", key)));
+        }
+        Ok(Some(field.values.join(" ")))
+    }
+
+    /// One of `choices`, returned by index.
+    fn choice(&self, key: &str, choices: &[&str]) -> Result<Option<usize>, SceneError> {
+        match self.word(key)? {
+            Some(word) => choices
+                .iter()
+                .position(|&c| c == word)
+                .map(Some)
+                .ok_or_else(|| {
+                    self.error(
+                        self.field(key).map_or(self.line, |f| f.line),
+                        format!(
+                            "'{}' must be one of {}, found '{}'",
+                            key,
+                            choices.join(", "),
+                            word
+                        ),
+                    )
+                }),
+            None => Ok(None),
+        }
+    }
+
+    fn flag(&self, key: &str) -> Result<Option<bool>, SceneError> {
+        Ok(self.choice(key, &["no", "yes"])?.map(|index| index == 1))
+    }
+
+    /// Look up the named material of an object.
+    fn material_ref(
+        &self,
+        materials: &HashMap<String, Material>,
+    ) -> Result<Option<Material>, SceneError> {
+        match self.word("material")? {
+            Some(name) => materials.get(name).copied().map(Some).ok_or_else(|| {
+                self.error(
+                    self.field("material").map_or(self.line, |f| f.line),
+                    format!("unknown material '{}'", name),
+                )
+            }),
+            None => Ok(None),
+        }
+    }
+
+    /// Textures of the object's material, if it has any.
+    fn maps_ref<'m>(
+        &self,
+        maps: &'m HashMap<&str, MaterialMaps>,
+    ) -> Result<Option<&'m MaterialMaps>, SceneError> {
+        Ok(self.word("material")?.and_then(|name| maps.get(name)))
+    }
+
+    /// Light output given by `direct` or by `power` (or `lumens`) and `color`.
+    fn strength(&self, direct: &str) -> Result<Strength, SceneError> {
+        let color = self.color("color")?;
+        let power = match (self.float("power")?, self.float("lumens")?) {
+            (Some(_), Some(_)) => {
+                return Err(self.error(
+                    self.field("lumens").map_or(self.line, |f| f.line),
+                    "'power' and 'lumens' cannot both be set".into(),
+                ));
+            }
+            (Some(watts), None) => Some(("power", watts)),
+            (None, Some(lumens)) => Some(("lumens", lumens / LUMENS_PER_WATT)),
+            (None, None) => None,
+        };
+        match (self.color(direct)?, power) {
+            (Some(value), None) => {
+                if color.is_some() {
+                    return Err(self.error(
+                        self.field("color").map_or(self.line, |f| f.line),
+                        format!(
+                            "'color' only applies to 'power' and 'lumens', put the color in '{}'",
+                            direct
+                        ),
+                    ));
+                }
+                Ok(Strength::Direct(value))
+            }
+            (None, Some((_, watts))) => Ok(Strength::Power(color.unwrap_or(Color::white()), watts)),
+            (Some(_), Some((key, _))) => Err(self.error(
+                self.field(key).map_or(self.line, |f| f.line),
+                format!("'{}' and '{}' cannot both be set", direct, key),
+            )),
+            (None, None) => Err(self.error(
+                self.line,
+                format!("{} needs '{}', 'power' or 'lumens'", self.title(), direct),
+            )),
+        }
+    }
+
+    fn settings(&self) -> Result<RayTracer, SceneError> {
+        let mut raytracer = default_raytracer();
+        if let Some(background) = self.color("background")? {
+            raytracer.background_color = background;
+        }
+        if let Some(max_depth) = self.integer("max_depth")? {
+            raytracer.max_depth = max_depth;
+        }
+        if let Some(min_weight) = self.float("min_weight")? {
+            raytracer.min_weight = min_weight;
+        }
+        if let Some(integrator) = self.choice("integrator", &["branching", "path"])? {
+            raytracer.integrator = [Integrator::Branching, Integrator::PathTracing][integrator];
+        }
+        if let Some(samples) = self.integer("samples_per_pixel")? {
+            raytracer.samples_per_pixel = samples;
+        }
+        if let Some(samples) = self.integer("light_samples")? {
+            raytracer.light_samples = samples;
+        }
+        if let Some(seed) = self.integer("seed")? {
+            raytracer.seed = seed;
+        }
+        if let Some(threads) = self.integer("threads")? {
+            raytracer.threads = threads;
+        }
+        if let Some(tile_size) = self.integer("tile_size")? {
+            raytracer.tile_size = tile_size;
+        }
+        Ok(raytracer)
+    }
+
+    fn camera(&self) -> Result<Camera, SceneError> {
+        let position = self.require("position", self.vec3("position")?)?;
+        let direction = match (self.vec3("direction")?, self.vec3("look_at")?) {
+            (Some(direction), None) => direction,
+            (None, Some(target)) => target - position,
+            (Some(_), Some(_)) => {
+                return Err(self.error(
+                    self.field("look_at").map_or(self.line, |f| f.line),
+                    "'direction' and 'look_at' cannot both be set".into(),
+                ));
+            }
+            (None, None) => {
+                return Err(self.error(self.line, "camera needs 'direction' or 'look_at'".into()));
+            }
+        };
+        let [width, height] = match self.field("resolution") {
+            Some(field) => {
+                let parse = |token: &str| {
+                    token.parse::<u32>().ok().filter(|&n| n > 0).ok_or_else(|| {
+                        self.error(
+                            field.line,
+                            format!(
+                                "'resolution' expects two positive whole numbers, found '{}'",
+                                token
+                            ),
+                        )
+                    })
+                };
+                match field.values[..] {
+                    [width, height] => [parse(width)?, parse(height)?],
+                    _ => {
+                        return Err(self.error(
+                            field.line,
+                            format!(
+                                "'resolution' expects width and height, found {} values",
+                                field.values.len()
+                            ),
+                        ));
+                    }
+                }
+            }
+            None => [640, 480],
+        };
+        let subdivisions = match self.integer("subdivisions")? {
+            Some(0) => {
+                return Err(self.error(
+                    self.field("subdivisions").map_or(self.line, |f| f.line),
+                    "'subdivisions' expects a positive whole number, found '0'".into(),
+                ));
+            }
+            subdivisions => subdivisions.unwrap_or(1),
+        };
+
+        Ok(Camera::new(
+            position,
+            direction,
+            self.vec3("up")?.unwrap_or(Vec3::new(0.0, 0.0, 1.0)),
+            self.float("fov")?.unwrap_or(60.0),
+            width,
+            height,
+            subdivisions,
+        ))
+    }
+
+    fn material(&self) -> Result<Material, SceneError> {
+        let specular = self.float("specular")?.unwrap_or(0.0);
+        let transmission = self.float("transmission")?.unwrap_or(0.0);
+        // Like MTL materials, the energy left by the other lobes goes to diffuse
+        let diffuse = self
+            .float("diffuse")?
+            .unwrap_or((1.0 - specular - transmission).max(0.0));
+        let fresnel = self.choice("fresnel", &["none", "schlick", "exact"])?;
+
+        let material = Material {
+            albedo: self.color("albedo")?.unwrap_or(Color::white()),
+            diffuse_rate: diffuse,
+            specular_rate: specular,
+            transmission_rate: transmission,
+            refractive_index: self.float("ior")?.unwrap_or(1.5),
+            absorption: self.color("absorption")?.unwrap_or(Color::black()),
+            emission: self.color("emission")?.unwrap_or(Color::black()),
+            fresnel: fresnel.map_or(Fresnel::None, |i| {
+                [Fresnel::None, Fresnel::Schlick, Fresnel::Exact][i]
+            }),
+            roughness: self.float("roughness")?.unwrap_or(0.0),
+            specular_tint: self.color("specular_tint")?.unwrap_or(Color::white()),
+            coat: self.float("coat")?.unwrap_or(0.0),
+            coat_roughness: self.float("coat_roughness")?.unwrap_or(0.0),
+            sheen: self.color("sheen")?.unwrap_or(Color::black()),
+        };
+        material.validate().map_err(|error| {
+            // Point at the field the problem comes from, or the last one involved
+            let keys: &[&str] = match &error {
+                MaterialError::RateOutOfRange { field, .. } => match *field {
+                    "diffuse_rate" => &["diffuse"],
+                    "specular_rate" => &["specular"],
+                    "transmission_rate" => &["transmission"],
+                    _ => &["coat"],
+                },
+                MaterialError::EnergyGain { .. } => &["diffuse", "specular", "transmission"],
+                MaterialError::ColorOutOfRange { field, .. } => match *field {
+                    "albedo" => &["albedo"],
+                    "albedo + sheen" => &["albedo", "sheen"],
+                    _ => &["specular_tint"],
+                },
+            };
+            let line = self
+                .fields
+                .iter()
+                .filter(|f| keys.contains(&f.key))
+                .map(|f| f.line)
+                .max()
+                .unwrap_or(self.line);
+            self.error(line, format!("{}: {}", self.title(), error))
+        })?;
+        Ok(material)
+    }
+
+    /// Textures named by the `*_map` fields of a material block, or None if there are none.
+    fn material_maps(
+        &self,
+        textures: &HashMap<&str, Arc<dyn Texture>>,
+        pixel_spread: Float,
+    ) -> Result<Option<MaterialMaps>, SceneError> {
+        let map = |key: &str| match self.word(key)? {
+            Some(name) => textures.get(name).cloned().map(Some).ok_or_else(|| {
+                self.error(
+                    self.field(key).map_or(self.line, |f| f.line),
+                    format!("unknown texture '{}'", name),
+                )
+            }),
+            None => Ok(None),
+        };
+        let maps = MaterialMaps {
+            albedo: map("albedo_map")?,
+            diffuse_rate: map("diffuse_map")?,
+            specular_rate: map("specular_map")?,
+            transmission_rate: map("transmission_map")?,
+            roughness: map("roughness_map")?,
+            normal_map: map("normal_map")?,
+            bump_map: map("bump_map")?,
+            bump_scale: self.float("bump_scale")?.unwrap_or(0.01),
+            pixel_spread,
+        };
+        let textured = [
+            &maps.albedo,
+            &maps.diffuse_rate,
+            &maps.specular_rate,
+            &maps.transmission_rate,
+            &maps.roughness,
+            &maps.normal_map,
+            &maps.bump_map,
+        ]
+        .iter()
+        .any(|texture| texture.is_some());
+        Ok(textured.then_some(maps))
+    }
+
+    fn texture(&self, directory: &Path) -> Result<Arc<dyn Texture>, SceneError> {
+        let kinds = ["image", "checker", "gradient", "noise"];
+        let kind = self.require("type", self.choice("type", &kinds)?)?;
+        let texture: Arc<dyn Texture> = match kinds[kind] {
+            "image" => {
+                let path = directory.join(self.require("path", self.text("path")?)?);
+                let image = if self.flag("data")?.unwrap_or(false) {
+                    ImageTexture::load_linear(&path)
+                } else {
+                    ImageTexture::load(&path)
+                }
+                .map_err(|error| SceneError::Image { path, error })?;
+                let wrap = self.choice("wrap", &["repeat", "clamp", "mirror"])?;
+                let filter = self.choice("filter", &["trilinear", "bilinear", "nearest"])?;
+                Arc::new(
+                    image
+                        .with_wrap(wrap.map_or(WrapMode::Repeat, |i| {
+                            [WrapMode::Repeat, WrapMode::Clamp, WrapMode::Mirror][i]
+                        }))
+                        .with_filter(filter.map_or(Filter::Trilinear, |i| {
+                            [Filter::Trilinear, Filter::Bilinear, Filter::Nearest][i]
+                        })),
+                )
+            }
+            "checker" => Arc::new(Checker::new(
+                self.color("even")?.unwrap_or(Color::white()),
+                self.color("odd")?.unwrap_or(Color::black()),
+                self.float("scale")?.unwrap_or(1.0),
+            )),
+            "gradient" => {
+                let axis = self.choice("axis", &["u", "v"])?;
+                Arc::new(Gradient::new(
+                    self.color("start")?.unwrap_or(Color::black()),
+                    self.color("end")?.unwrap_or(Color::white()),
+                    axis.map_or(GradientAxis::U, |i| [GradientAxis::U, GradientAxis::V][i]),
+                ))
+            }
+            _ => Arc::new(
+                Noise::with_seed(
+                    self.color("low")?.unwrap_or(Color::black()),
+                    self.color("high")?.unwrap_or(Color::white()),
+                    self.float("scale")?.unwrap_or(1.0),
+                    self.integer("seed")?.unwrap_or(0),
+                )
+                .with_octaves(self.integer("octaves")?.unwrap_or(1)),
+            ),
+        };
+        Ok(texture)
+    }
+
+    fn principled(&self) -> Result<Material, SceneError> {
+        let defaults = PrincipledMaterial::default();
+        let principled = PrincipledMaterial {
+            base_color: self.color("base_color")?.unwrap_or(defaults.base_color),
+            metallic: self.float("metallic")?.unwrap_or(defaults.metallic),
+            roughness: self.float("roughness")?.unwrap_or(defaults.roughness),
+            specular: self.float("specular")?.unwrap_or(defaults.specular),
+            transmission: self.float("transmission")?.unwrap_or(defaults.transmission),
+            clearcoat: self.float("clearcoat")?.unwrap_or(defaults.clearcoat),
+            clearcoat_roughness: self
+                .float("clearcoat_roughness")?
+                .unwrap_or(defaults.clearcoat_roughness),
+            sheen: self.float("sheen")?.unwrap_or(defaults.sheen),
+            sheen_tint: self.float("sheen_tint")?.unwrap_or(defaults.sheen_tint),
+            ior: self.float("ior")?.unwrap_or(defaults.ior),
+            emission: self.color("emission")?.unwrap_or(defaults.emission),
+        };
+        Ok(principled.into())
+    }
+
+    fn sphere(&self, materials: &HashMap<String, Material>) -> Result<Sphere, SceneError> {
+        let center = self.require("center", self.vec3("center")?)?;
+        let radius = self.require("radius", self.positive("radius")?)?;
+        let material = self.material_ref(materials)?;
+        Ok(Sphere::new(
+            center,
+            radius,
+            material.unwrap_or(Material::diffuse_surface()),
+        ))
+    }
+
+    fn triangle(&self, materials: &HashMap<String, Material>) -> Result<Triangle, SceneError> {
+        let v = self.require("vertices", self.numbers::<9>("vertices")?)?;
+        let vertices = [
+            Vec3::new(v[0], v[1], v[2]),
+            Vec3::new(v[3], v[4], v[5]),
+            Vec3::new(v[6], v[7], v[8]),
+        ];
+        let material = self
+            .material_ref(materials)?
+            .unwrap_or(Material::diffuse_surface());
+        let mut triangle = match self.numbers::<9>("normals")? {
+            Some(n) => Triangle::with_normals(
+                vertices[0],
+                vertices[1],
+                vertices[2],
+                [
+                    Vec3::new(n[0], n[1], n[2]),
+                    Vec3::new(n[3], n[4], n[5]),
+                    Vec3::new(n[6], n[7], n[8]),
+                ],
+                material,
+            ),
+            None => Triangle::new(vertices[0], vertices[1], vertices[2], material),
+        };
+        if let Some(uv) = self.numbers::<6>("uvs")? {
+            triangle = triangle.with_uvs([[uv[0], uv[1]], [uv[2], uv[3]], [uv[4], uv[5]]]);
+        }
+        Ok(triangle)
+    }
+
+    fn meshes(
+        &self,
+        materials: &HashMap<String, Material>,
+        directory: &Path,
+    ) -> Result<Vec<TriangleMesh>, SceneError> {
+        let path = self.require("path", self.text("path")?)?;
+        let material = self.material_ref(materials)?;
+        let transform = self.placement()?;
+        let groups = load_obj(directory.join(path))?;
+
+        Ok(groups
+            .into_iter()
+            .map(|group| {
+                if material.is_none() && transform == Transform::identity() {
+                    return group.mesh;
+                }
+                let mut data = group.mesh.data().transformed(&transform);
+                if let Some(material) = material {
+                    data.materials = vec![material];
+                    for face in &mut data.faces {
+                        face.material = 0;
+                    }
+                }
+                TriangleMesh::from_data(data)
+            })
+            .collect())
+    }
+
+    /// Transform placing a mesh: `scale`, then `rotate` (axis and angle), then `translate`.
+    fn placement(&self) -> Result<Transform, SceneError> {
+        let line = |key: &str| self.field(key).map_or(self.line, |f| f.line);
+        let scale = self.vec3("scale")?.unwrap_or(Vec3::new(1.0, 1.0, 1.0));
+        if scale.x == 0.0 || scale.y == 0.0 || scale.z == 0.0 {
+            return Err(self.error(line("scale"), "'scale' factors must be non-zero".into()));
+        }
+        let [x, y, z, angle] = self.numbers::<4>("rotate")?.unwrap_or([0.0, 0.0, 1.0, 0.0]);
+        let axis = Vec3::new(x, y, z);
+        if axis.length_squared() == 0.0 {
+            return Err(self.error(
+                line("rotate"),
+                "'rotate' needs a non-zero axis before the angle".into(),
+            ));
+        }
+        let offset = self.vec3("translate")?.unwrap_or(Vec3::zero());
+
+        Ok(Transform::scaling(scale)
+            .then(Transform::rotation(axis, angle.to_radians()))
+            .then(Transform::translation(offset)))
+    }
+
+    fn light(&self) -> Result<Arc<dyn LightSource>, SceneError> {
+        let kinds = ["point", "sphere", "spot", "directional", "quad", "disk"];
+        let kind = match self.args[..] {
+            [kind] if kinds.contains(&kind) => kind,
+            [] => {
+                return Err(self.error(
+                    self.line,
+                    format!("'light' needs a type: {}", kinds.join(", ")),
+                ));
+            }
+            _ => {
+                return Err(self.error(
+                    self.line,
+                    format!(
+                        "unknown light type '{}' (expected one of: {})",
+                        self.args.join(" "),
+                        kinds.join(", ")
+                    ),
+                ));
+            }
+        };
+
+        let light: Arc<dyn LightSource> = match kind {
+            "point" => {
+                let position = self.require("position", self.vec3("position")?)?;
+                Arc::new(match self.strength("intensity")? {
+                    Strength::Direct(intensity) => PointLight::new(position, intensity),
+                    Strength::Power(color, watts) => PointLight::with_power(position, color, watts),
+                })
+            }
+            "sphere" => {
+                let center = self.require("center", self.vec3("center")?)?;
+                let radius = self.require("radius", self.positive("radius")?)?;
+                Arc::new(match self.strength("emission")? {
+                    Strength::Direct(emission) => Light::new(center, radius, emission),
+                    Strength::Power(color, watts) => {
+                        Light::with_power(center, radius, color, watts)
+                    }
+                })
+            }
+            "spot" => {
+                let position = self.require("position", self.vec3("position")?)?;
+                let direction = self.require("direction", self.vec3("direction")?)?;
+                let inner = self.require("inner_angle", self.float("inner_angle")?)?;
+                let outer = self.require("outer_angle", self.float("outer_angle")?)?;
+                let (inner, outer) = (inner.to_radians(), outer.to_radians());
+                let light = match self.strength("intensity")? {
+                    Strength::Direct(intensity) => {
+                        SpotLight::new(position, direction, intensity, inner, outer)
+                    }
+                    Strength::Power(color, watts) => {
+                        SpotLight::with_power(position, direction, color, watts, inner, outer)
+                    }
+                };
+                Arc::new(light.with_falloff(self.float("falloff")?.unwrap_or(1.0)))
+            }
+            "directional" => {
+                let direction = self.require("direction", self.vec3("direction")?)?;
+                let irradiance = self.require("irradiance", self.color("irradiance")?)?;
+                let diameter = self.float("angular_diameter")?.unwrap_or(0.0);
+                Arc::new(
+                    DirectionalLight::new(direction, irradiance)
+                        .with_angular_diameter(diameter.to_radians()),
+                )
+            }
+            "quad" => {
+                let corner = self.require("corner", self.vec3("corner")?)?;
+                let edge_u = self.require("edge_u", self.vec3("edge_u")?)?;
+                let edge_v = self.require("edge_v", self.vec3("edge_v")?)?;
+                let emission = self.require("emission", self.color("emission")?)?;
+                Arc::new(
+                    QuadLight::new(corner, edge_u, edge_v, emission)
+                        .with_two_sided(self.flag("two_sided")?.unwrap_or(false)),
+                )
+            }
+            _ => {
+                let center = self.require("center", self.vec3("center")?)?;
+                let normal = self.require("normal", self.vec3("normal")?)?;
+                let radius = self.require("radius", self.positive("radius")?)?;
+                let emission = self.require("emission", self.color("emission")?)?;
+                Arc::new(
+                    DiskLight::new(center, normal, radius, emission)
+                        .with_two_sided(self.flag("two_sided")?.unwrap_or(false)),
+                )
+            }
+        };
+        Ok(light)
+    }
+
+    fn environment(&self, directory: &Path) -> Result<EnvironmentLight, SceneError> {
+        let path = directory.join(self.require("path", self.text("path")?)?);
+        let environment =
+            EnvironmentLight::load(&path).map_err(|error| SceneError::Image { path, error })?;
+        Ok(environment
+            .with_scale(self.float("scale")?.unwrap_or(1.0))
+            .with_rotation(self.float("rotation")?.unwrap_or(0.0).to_radians()))
+    }
+
+    /// The sky, and its sun unless disabled.
+    fn sky(&self) -> Result<(SkyLight, Option<DirectionalLight>), SceneError> {
+        let elevation = self.require("sun_elevation", self.float("sun_elevation")?)?;
+        let azimuth = self.float("sun_azimuth")?.unwrap_or(0.0);
+        let sky = SkyLight::new(
+            sun_direction(elevation.to_radians(), azimuth.to_radians()),
+            self.float("turbidity")?.unwrap_or(3.0),
+            self.color("ground_albedo")?
+                .unwrap_or(Color::new(0.3, 0.3, 0.3)),
+        );
+        let sun = self.flag("sun")?.unwrap_or(true).then(|| sky.sun());
+        Ok((sky, sun))
+    }
+}
+
+fn parse_number(token: &str) -> Result<Float, String> {
+    token
+        .parse::<Float>()
+        .ok()
+        .filter(|x| x.is_finite())
+        .ok_or_else(|| format!("invalid number '{}'", token))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> Result<Scene, SceneError> {
+        parse_scene(source, "test.scene", Path::new(""))
+    }
+
+    /// Line and message of a parse error.
+    fn parse_error(source: &str) -> (usize, String) {
+        match parse(source) {
+            Err(SceneError::Parse { line, message, .. }) => (line, message),
+            Err(other) => panic!("expected a parse error, got {}", other),
+            Ok(_) => panic!("expected a parse error"),
+        }
+    }
+
+    const CAMERA: &str = "camera\n    position 0 -3 3\n    look_at 0 0 0\n";
+
+    #[test]
+    fn test_parse_scene() {
+        let source = "
+# Materials can be used before they are defined
+sphere
+    center 0 0 0.5
+    radius 0.5
+    material glass
+
+triangle
+    vertices 3 3 0  -3 -1 0  3 -1 0
+    uvs 1 1  0 0  1 0
+    material floor
+
+settings
+    background 0.1        # grey
+    max_depth 4
+    integrator path
+    samples_per_pixel 8
+
+camera
+    position 0 -3 3
+    direction 0 3 -2
+    fov 45
+    resolution 320 200
+    subdivisions 2
+
+material glass
+    transmission 0.9
+    specular 0.1
+    fresnel exact
+
+principled floor
+    base_color 0.9 0.75 0.2
+    roughness 0.8
+
+light sphere
+    center 0 0 10
+    radius 2
+    power 4000
+
+light spot
+    position 0 0 5
+    direction 0 0 -1
+    inner_angle 20
+    outer_angle 30
+    intensity 10 10 10
+
+sky
+    sun_elevation 30
+";
+        let scene = parse(source).unwrap();
+
+        assert_eq!(scene.cameras[0].width, 320);
+        assert_eq!(scene.cameras[0].height, 200);
+        assert_eq!(scene.cameras[0].subdivisions, 2);
+        assert_eq!(scene.raytracer.max_depth, 4);
+        assert_eq!(scene.raytracer.integrator, Integrator::PathTracing);
+        assert_eq!(scene.raytracer.samples_per_pixel, 8);
+        assert!((scene.raytracer.background_color.g - 0.1).abs() < 1e-6);
+
+        assert_eq!(scene.materials.len(), 2);
+        let glass = scene.materials["glass"];
+        assert_eq!(glass.diffuse_rate, 0.0);
+        assert!(matches!(glass.fresnel, Fresnel::Exact));
+
+        assert_eq!(scene.surfaces.len(), 2);
+        assert!((scene.surfaces[0].material().transmission_rate - 0.9).abs() < 1e-6);
+        // Sphere, spot, then the sun of the sky
+        assert!(scene.environment.is_some());
+        assert_eq!(scene.lights.len(), 3);
+        assert!(!scene.lights[0].is_delta());
+        assert!(scene.lights[1].is_delta());
+    }
+
+    #[test]
+    fn test_material_diffuse_defaults_to_remaining_energy() {
+        let scene = parse(&format!("{}material paint\n    specular 0.3\n", CAMERA)).unwrap();
+        assert!((scene.materials["paint"].diffuse_rate - 0.7).abs() < 1e-6);
+
+        let (line, message) = parse_error(&format!(
+            "{}material hot\n    diffuse 0.8\n    specular 0.5\n",
+            CAMERA
+        ));
+        assert_eq!(line, 6);
+        assert!(message.contains("material hot"), "{}", message);
+
+        // Range errors point at their own field
+        let (line, message) = parse_error(&format!(
+            "{}material hot\n    coat 2\n    albedo 0.5\n",
+            CAMERA
+        ));
+        assert_eq!(line, 5);
+        assert!(
+            message.contains("coat must be between 0 and 1"),
+            "{}",
+            message
+        );
+        let (line, message) = parse_error(&format!(
+            "{}material hot\n    specular_tint 1 2 1\n    albedo 0.5\n",
+            CAMERA
+        ));
+        assert_eq!(line, 5);
+        assert!(message.contains("specular_tint channels"), "{}", message);
+    }
+
+    #[test]
+    fn test_errors_report_line_and_field() {
+        let cases = [
+            ("spher\n", 1, "unknown block 'spher'"),
+            (
+                "sphere\n    center 0 0\n    radius 1\n",
+                2,
+                "'center' expects 3 numbers, found 2",
+            ),
+            ("sphere\n    center 0 0 x\n", 2, "invalid number 'x'"),
+            (
+                "sphere\n    center 0 0 0\n    radius 1\n    colour 1 0 0\n",
+                4,
+                "unknown field 'colour' in sphere",
+            ),
+            (
+                "sphere\n    center 0 0 0\n",
+                1,
+                "sphere is missing 'radius'",
+            ),
+            (
+                "sphere\n    radius 1\n    radius 2\n",
+                3,
+                "'radius' is already set on line 5",
+            ),
+            (
+                "sphere\n    center 0 0 0\n    radius 1\n    material gold\n",
+                4,
+                "unknown material 'gold'",
+            ),
+            (
+                "sphere\n    center 0 0 0\n    radius 0\n",
+                3,
+                "'radius' must be positive, found 0",
+            ),
+            (
+                "light sphere\n    center 0 0 0\n    radius -1\n    emission 1\n",
+                3,
+                "'radius' must be positive, found -1",
+            ),
+            (
+                "light disk\n    center 0 0 0\n    normal 0 0 1\n    radius 0\n    emission 1\n",
+                4,
+                "'radius' must be positive, found 0",
+            ),
+            (
+                "mesh\n    path quad.obj\n    scale 1 0 1\n",
+                3,
+                "'scale' factors must be non-zero",
+            ),
+            (
+                "mesh\n    path quad.obj\n    rotate 0 0 0 90\n",
+                3,
+                "'rotate' needs a non-zero axis",
+            ),
+            ("light\n", 1, "'light' needs a type"),
+            ("light laser\n", 1, "unknown light type 'laser'"),
+            (
+                "light point\n    position 0 0 1\n",
+                1,
+                "light point needs 'intensity', 'power' or 'lumens'",
+            ),
+            (
+                "light point\n    position 0 0 1\n    power 1\n    lumens 683\n",
+                4,
+                "'power' and 'lumens' cannot both be set",
+            ),
+            (
+                "settings\n    integrator photon\n",
+                2,
+                "'integrator' must be one of branching, path",
+            ),
+            (
+                "settings\n    max_depth 2.5\n",
+                2,
+                "'max_depth' expects a whole number",
+            ),
+        ];
+        for (body, line, expected) in cases {
+            let (error_line, message) = parse_error(&format!("{}{}", CAMERA, body));
+            assert_eq!(error_line, line + 3, "{}", message);
+            assert!(
+                message.contains(expected),
+                "'{}' does not contain '{}'",
+                message,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_light_power_in_lumens() {
+        let scene = parse(&format!(
+            "{}light point\n    position 0 0 1\n    power 2\n\
+             light point\n    position 0 0 1\n    lumens {}\n",
+            CAMERA,
+            2.0 * LUMENS_PER_WATT
+        ))
+        .unwrap();
+        let [watts, lumens] = [0, 1].map(|i| scene.lights[i].sample(Vec3::zero(), 0.5, 0.5));
+        assert!((watts.unwrap().radiance.g - lumens.unwrap().radiance.g).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_camera_rejects_zero_subdivisions() {
+        let (line, message) = parse_error(&format!("{}    subdivisions 0\n", CAMERA));
+        assert_eq!(line, 4);
+        assert!(
+            message.contains("'subdivisions' expects a positive whole number"),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn test_field_outside_block() {
+        let (line, message) = parse_error("# comment\n    radius 1\n");
+        assert_eq!(line, 2);
+        assert!(
+            message.contains("'radius' is outside of a block"),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn test_example_scene_loads() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("scenes/example.txt");
+        let scene = load_scene(path).unwrap();
+        assert_eq!(scene.surfaces.len(), 6);
+        assert_eq!(scene.lights.len(), 3);
+        assert_eq!(scene.materials.len(), 5);
+
+        // The floor matches the demo scene in main.rs
+        let floor = &scene.materials["floor"];
+        assert_eq!(floor.albedo, Color::white());
+        assert!((floor.diffuse_rate - 0.2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_missing_camera() {
+        let error = parse("sphere\n    center 0 0 0\n    radius 1\n")
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "test.scene: the scene has no camera block"
+        );
+    }
+
+    #[test]
+    fn test_mesh_path_relative_to_scene() {
+        let directory = std::env::temp_dir().join("raytracer_scene_test");
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(
+            directory.join("quad.obj"),
+            "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3 4\n",
+        )
+        .unwrap();
+        let scene_path = directory.join("test.scene");
+        std::fs::write(
+            &scene_path,
+            format!(
+                "{}material red\n    albedo 1 0 0\nmesh\n    path quad.obj\n    material red\n",
+                CAMERA
+            ),
+        )
+        .unwrap();
+
+        let scene = load_scene(&scene_path).unwrap();
+        assert_eq!(scene.surfaces.len(), 1);
+        let bounds = scene.surfaces[0].bounds();
+        assert!((bounds.max.x - 1.0).abs() < 1e-5);
+        let hit = scene.surfaces[0]
+            .intersect(&super::super::Ray::new(
+                Vec3::new(0.5, 0.5, 1.0),
+                Vec3::new(0.0, 0.0, -1.0),
+            ))
+            .unwrap();
+        assert_eq!(hit.material.albedo.g, 0.0);
+
+        std::fs::write(
+            &scene_path,
+            format!("{}mesh\n    path missing.obj\n", CAMERA),
+        )
+        .unwrap();
+        assert!(matches!(load_scene(&scene_path), Err(SceneError::Mesh(_))));
+    }
+
+    #[test]
+    fn test_mesh_placement() {
+        let directory = std::env::temp_dir().join("raytracer_scene_placement_test");
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(
+            directory.join("quad.obj"),
+            "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3 4\n",
+        )
+        .unwrap();
+        let scene_path = directory.join("test.scene");
+        std::fs::write(
+            &scene_path,
+            format!(
+                "{}mesh\n    path quad.obj\n    translate 0 0 1\n    rotate 0 0 1 90\n    scale 2 2 2\n",
+                CAMERA
+            ),
+        )
+        .unwrap();
+
+        // Scaled to 2 x 2, turned a quarter turn about Z, then lifted by 1
+        let scene = load_scene(&scene_path).unwrap();
+        let down_at = |x: Float, y: Float| {
+            super::super::Ray::new(Vec3::new(x, y, 5.0), Vec3::new(0.0, 0.0, -1.0))
+        };
+        let hit = scene.surfaces[0].intersect(&down_at(-1.5, 1.5)).unwrap();
+        assert!((hit.point.z - 1.0).abs() < 1e-5);
+        assert!((hit.normal.z.abs() - 1.0).abs() < 1e-5);
+        assert!(scene.surfaces[0].intersect(&down_at(0.5, 0.5)).is_none());
+    }
+
+    #[test]
+    fn test_emissive_mesh_becomes_light() {
+        let directory = std::env::temp_dir().join("raytracer_scene_light_test");
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(
+            directory.join("panel.obj"),
+            "v 0 0 2\nv 0 1 2\nv 1 1 2\nv 1 0 2\nf 1 2 3 4\n",
+        )
+        .unwrap();
+        let scene_path = directory.join("test.scene");
+        std::fs::write(
+            &scene_path,
+            format!(
+                "{}material lamp\n    emission 5 5 5\nmesh\n    path panel.obj\n    material lamp\n",
+                CAMERA
+            ),
+        )
+        .unwrap();
+
+        let scene = load_scene(&scene_path).unwrap();
+        assert!(scene.surfaces.is_empty());
+        assert_eq!(scene.lights.len(), 1);
+        let below = Vec3::new(0.5, 0.5, 0.0);
+        let sample = scene.lights[0].sample(below, 0.5, 0.5).unwrap();
+        assert_eq!(sample.radiance, Color::new(5.0, 5.0, 5.0));
+        let above = Vec3::new(0.5, 0.5, 4.0);
+        assert!(scene.lights[0].sample(above, 0.5, 0.5).is_none());
+
+        std::fs::write(
+            &scene_path,
+            format!(
+                "{}material lamp\n    emission 5 5 5\nmesh\n    path panel.obj\n    material lamp\n    two_sided yes\n",
+                CAMERA
+            ),
+        )
+        .unwrap();
+        let scene = load_scene(&scene_path).unwrap();
+        let sample = scene.lights[0].sample(above, 0.5, 0.5).unwrap();
+        assert_eq!(sample.radiance, Color::new(5.0, 5.0, 5.0));
+    }
+
+    #[test]
+    fn test_emissive_sphere_becomes_light() {
+        let scene = parse(&format!(
+            "{}material lamp\n    emission 5 5 5\nsphere\n    center 0 0 2\n    radius 0.5\n    material lamp\n",
+            CAMERA
+        ))
+        .unwrap();
+        assert!(scene.surfaces.is_empty());
+        assert_eq!(scene.lights.len(), 1);
+        let sample = scene.lights[0].sample(Vec3::zero(), 0.5, 0.5).unwrap();
+        assert_eq!(sample.radiance, Color::new(5.0, 5.0, 5.0));
+    }
+
+    #[test]
+    fn test_textured_materials() {
+        let source = format!(
+            "{}
+material floor
+    albedo_map tiles
+    roughness_map grain
+
+texture tiles
+    type checker
+    even 1 0 0
+    odd 0 0 1
+    scale 2
+
+texture grain
+    type gradient
+    axis v
+
+triangle
+    vertices 0 0 0   1 0 0   0 1 0
+    material floor
+
+sphere
+    center 5 0 0
+    radius 1
+",
+            CAMERA
+        );
+        let scene = parse(&source).unwrap();
+        let down = Vec3::new(0.0, 0.0, -1.0);
+        let hit_at = |x: Float, y: Float| {
+            scene.surfaces[0]
+                .intersect(&super::super::Ray::new(Vec3::new(x, y, 1.0), down))
+                .unwrap()
+        };
+        let hit = hit_at(0.2, 0.3);
+        assert_eq!(hit.material.albedo, Color::red());
+        assert!((hit.material.roughness - 0.3).abs() < 1e-5);
+        assert_eq!(hit_at(0.7, 0.1).material.albedo, Color::blue());
+        // Objects without a textured material are not wrapped
+        let hit = scene.surfaces[1]
+            .intersect(&super::super::Ray::new(Vec3::new(5.0, 0.0, 5.0), down))
+            .unwrap();
+        assert_eq!(hit.material.albedo, Material::diffuse_surface().albedo);
+
+        let (line, message) = parse_error(&format!(
+            "{}material floor\n    albedo_map marble\n",
+            CAMERA
+        ));
+        assert_eq!(line, 5);
+        assert!(message.contains("unknown texture 'marble'"), "{}", message);
+        let (line, message) = parse_error(&format!("{}texture marble\n    scale 2\n", CAMERA));
+        assert_eq!(line, 4);
+        assert!(
+            message.contains("texture marble is missing 'type'"),
+            "{}",
+            message
+        );
+        let (line, message) = parse_error(&format!(
+            "{}texture marble\n    type noise\n    even 1\n",
+            CAMERA
+        ));
+        assert_eq!(line, 6);
+        assert!(message.contains("unknown field 'even'"), "{}", message);
+    }
+
+    #[test]
+    fn test_image_and_noise_textures() {
+        let directory = std::env::temp_dir().join("raytracer_scene_texture_test");
+        std::fs::create_dir_all(&directory).unwrap();
+        // Left half black, right half white
+        let mut image = ::image::RgbImage::new(2, 1);
+        image.put_pixel(1, 0, ::image::Rgb([255, 255, 255]));
+        image.save(directory.join("halves.png")).unwrap();
+        let scene_path = directory.join("test.scene");
+        std::fs::write(
+            &scene_path,
+            format!(
+                "{}
+texture halves
+    type image
+    path halves.png
+    wrap clamp
+    filter nearest
+
+texture marble
+    type noise
+    low 0.2
+    high 0.8
+    scale 3
+    octaves 4
+
+material painted
+    albedo_map halves
+    specular_map marble
+
+triangle
+    vertices 0 0 0   1 0 0   0 1 0
+    material painted
+",
+                CAMERA
+            ),
+        )
+        .unwrap();
+
+        let scene = load_scene(&scene_path).unwrap();
+        let down = Vec3::new(0.0, 0.0, -1.0);
+        let hit_at = |x: Float| {
+            scene.surfaces[0]
+                .intersect(&super::super::Ray::new(Vec3::new(x, 0.1, 1.0), down))
+                .unwrap()
+        };
+        assert_eq!(hit_at(0.2).material.albedo, Color::black());
+        let hit = hit_at(0.7);
+        assert_eq!(hit.material.albedo, Color::white());
+        assert!((0.2..=0.8).contains(&hit.material.specular_rate));
+
+        std::fs::write(
+            &scene_path,
+            format!(
+                "{}texture gone\n    type image\n    path missing.png\n",
+                CAMERA
+            ),
+        )
+        .unwrap();
+        assert!(matches!(
+            load_scene(&scene_path),
+            Err(SceneError::Image { .. })
+        ));
+    }
+}
@@ -0,0 +1,331 @@
+//! Hierarchical scene graph of named nodes with local transforms.
+
+use super::Surface;
+use super::instance::Instance;
+use super::light::LightSource;
+use super::material::Material;
+use super::scene::Scene;
+use super::transform::{Transform, Transformable};
+use std::sync::Arc;
+
+/// Handle to a node of a `SceneGraph`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NodeId(usize);
+
+/// A light that can be attached to a node and placed by its world transform.
+pub trait NodeLight: Send + Sync {
+    /// Create the light moved into world space.
+    fn place(&self, transform: &Transform) -> Arc<dyn LightSource>;
+}
+
+impl<L: LightSource + Transformable + 'static> NodeLight for L {
+    fn place(&self, transform: &Transform) -> Arc<dyn LightSource> {
+        Arc::new(self.transformed(transform))
+    }
+}
+
+/// A named node holding geometry and lights in its own local space.
+pub struct SceneNode {
+    /// Name used for lookups
+    pub name: String,
+    /// Transform from this node's space to its parent's space
+    pub transform: Transform,
+    /// Material replacing the materials of this node and its descendants,
+    /// unless a descendant sets its own
+    pub material: Option<Material>,
+    /// Whether the node and its descendants are rendered
+    pub visible: bool,
+    /// Geometry attached to the node
+    pub surfaces: Vec<Arc<dyn Surface>>,
+    /// Lights attached to the node
+    pub lights: Vec<Arc<dyn NodeLight>>,
+    /// Child nodes in insertion order
+    children: Vec<NodeId>,
+}
+
+impl SceneNode {
+    /// Attach a surface, given in the node's local space.
+    pub fn add_surface(&mut self, surface: impl Surface + 'static) {
+        self.surfaces.push(Arc::new(surface));
+    }
+
+    /// Attach a light, given in the node's local space.
+    pub fn add_light(&mut self, light: impl NodeLight + 'static) {
+        self.lights.push(Arc::new(light));
+    }
+}
+
+/// World-space surfaces and lights produced by `SceneGraph::flatten`.
+pub type Flattened = (Vec<Arc<dyn Surface>>, Vec<Arc<dyn LightSource>>);
+
+/// A tree of named nodes whose transforms and material overrides are inherited
+/// by their children, such as wheels attached to a car or moons orbiting planets.
+///
+/// Animation code finds nodes by name and changes their transforms; `flatten`
+/// then produces the world-space surfaces and lights to render.
+pub struct SceneGraph {
+    /// All nodes, indexed by `NodeId` (the root is node 0)
+    nodes: Vec<SceneNode>,
+}
+
+impl Default for SceneGraph {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SceneGraph {
+    /// Create a graph with a single root node named "root".
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![SceneNode {
+                name: "root".to_string(),
+                transform: Transform::identity(),
+                material: None,
+                visible: true,
+                surfaces: Vec::new(),
+                lights: Vec::new(),
+                children: Vec::new(),
+            }],
+        }
+    }
+
+    /// The root node.
+    pub fn root(&self) -> NodeId {
+        NodeId(0)
+    }
+
+    /// Add an empty node under `parent`.
+    ///
+    /// # Arguments
+    /// * `parent` - Node the new node is attached to
+    /// * `name` - Name used by `find` (names do not have to be unique)
+    /// * `transform` - Transform from the new node's space to the parent's space
+    pub fn add_node(&mut self, parent: NodeId, name: &str, transform: Transform) -> NodeId {
+        let id = NodeId(self.nodes.len());
+        self.nodes.push(SceneNode {
+            name: name.to_string(),
+            transform,
+            material: None,
+            visible: true,
+            surfaces: Vec::new(),
+            lights: Vec::new(),
+            children: Vec::new(),
+        });
+        self.nodes[parent.0].children.push(id);
+        id
+    }
+
+    /// Get a node for editing.
+    pub fn node_mut(&mut self, id: NodeId) -> &mut SceneNode {
+        &mut self.nodes[id.0]
+    }
+
+    /// Find the first node with the given name, searching depth-first from the root.
+    pub fn find(&self, name: &str) -> Option<NodeId> {
+        self.descendants(self.root())
+            .find(|&id| self.nodes[id.0].name == name)
+    }
+
+    /// Get a node by name for editing (see `find`).
+    pub fn find_mut(&mut self, name: &str) -> Option<&mut SceneNode> {
+        let id = self.find(name)?;
+        Some(self.node_mut(id))
+    }
+
+    /// Ids of `id` and all nodes below it, depth-first in insertion order.
+    fn descendants(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        let mut stack = vec![id];
+        std::iter::from_fn(move || {
+            let id = stack.pop()?;
+            stack.extend(self.nodes[id.0].children.iter().rev());
+            Some(id)
+        })
+    }
+
+    /// Collect the world-space surfaces and lights of all visible nodes.
+    ///
+    /// Geometry is shared, not copied: surfaces of transformed nodes or nodes with
+    /// a material override are wrapped in an `Instance`.
+    pub fn flatten(&self) -> Flattened {
+        let mut surfaces: Vec<Arc<dyn Surface>> = Vec::new();
+        let mut lights = Vec::new();
+        // (node, parent-to-world transform, inherited material)
+        let mut stack = vec![(self.root(), Transform::identity(), None)];
+
+        while let Some((id, parent_transform, inherited)) = stack.pop() {
+            let node = &self.nodes[id.0];
+            if !node.visible {
+                continue;
+            }
+            let transform = parent_transform * node.transform;
+            let material = node.material.or(inherited);
+
+            for surface in &node.surfaces {
+                if transform == Transform::identity() && material.is_none() {
+                    surfaces.push(Arc::clone(surface));
+                } else {
+                    let mut instance = Instance::new(Arc::clone(surface), transform);
+                    instance.material = material;
+                    surfaces.push(Arc::new(instance));
+                }
+            }
+            lights.extend(node.lights.iter().map(|light| light.place(&transform)));
+
+            for &child in node.children.iter().rev() {
+                stack.push((child, transform, material));
+            }
+        }
+
+        (surfaces, lights)
+    }
+
+    /// Add the flattened surfaces and lights to a scene.
+    pub fn add_to(&self, scene: &mut Scene) {
+        let (surfaces, lights) = self.flatten();
+        scene.surfaces.extend(surfaces);
+        scene.lights.extend(lights);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raytracer::light::PointLight;
+    use crate::raytracer::material::Color;
+    use crate::raytracer::raytracer::RayTracer;
+    use crate::raytracer::sphere::Sphere;
+    use crate::raytracer::vector::{Float, Vec3};
+    use crate::raytracer::{Intersection, Ray};
+    use std::f32::consts::FRAC_PI_2;
+
+    /// A car with two wheels, each a small sphere, and a headlight.
+    fn car_graph() -> SceneGraph {
+        let mut graph = SceneGraph::new();
+        let root = graph.root();
+        let car = graph.add_node(
+            root,
+            "car",
+            Transform::translation(Vec3::new(10.0, 0.0, 0.0)),
+        );
+        graph.node_mut(car).add_surface(Sphere::new(
+            Vec3::new(0.0, 0.0, 1.0),
+            0.5,
+            Material::diffuse_surface(),
+        ));
+        graph
+            .node_mut(car)
+            .add_light(PointLight::new(Vec3::new(2.0, 0.0, 1.0), Color::white()));
+        for (name, x) in [("front_wheel", 1.0), ("rear_wheel", -1.0)] {
+            let wheel = graph.add_node(car, name, Transform::translation(Vec3::new(x, 0.0, 0.0)));
+            graph.node_mut(wheel).add_surface(Sphere::new(
+                Vec3::zero(),
+                0.3,
+                Material::diffuse_surface(),
+            ));
+        }
+        graph
+    }
+
+    /// Closest hit among flattened surfaces.
+    fn trace(surfaces: &[Arc<dyn Surface>], ray: &Ray) -> Option<Intersection> {
+        surfaces
+            .iter()
+            .filter_map(|s| s.intersect(ray))
+            .min_by(|a, b| a.t.total_cmp(&b.t))
+    }
+
+    fn down_at(x: Float, y: Float) -> Ray {
+        Ray::new(Vec3::new(x, y, 10.0), Vec3::new(0.0, 0.0, -1.0))
+    }
+
+    #[test]
+    fn test_lookup_by_name() {
+        let mut graph = car_graph();
+        let wheel = graph.find("front_wheel").unwrap();
+        assert_ne!(graph.find("rear_wheel"), Some(wheel));
+        assert!(graph.find("trailer").is_none());
+        assert_eq!(graph.find_mut("front_wheel").unwrap().name, "front_wheel");
+    }
+
+    #[test]
+    fn test_children_inherit_transforms() {
+        let graph = car_graph();
+        let (surfaces, lights) = graph.flatten();
+        assert_eq!(surfaces.len(), 3);
+        assert_eq!(lights.len(), 1);
+        let sample = lights[0]
+            .sample(Vec3::new(12.0, 0.0, 0.0), 0.5, 0.5)
+            .unwrap();
+        assert!((sample.distance - 1.0).abs() < 1e-5);
+
+        // The rear wheel lies outside the body, so a ray from above hits its top
+        let hit = trace(&surfaces, &down_at(9.0, 0.0)).unwrap();
+        assert!((hit.point.z - 0.3).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_moving_a_parent_moves_its_children() {
+        let mut graph = car_graph();
+        // Drive the car forward and turn it a quarter turn to the left
+        graph.find_mut("car").unwrap().transform = Transform::translation(Vec3::new(0.0, 5.0, 0.0))
+            * Transform::rotation(Vec3::new(0.0, 0.0, 1.0), FRAC_PI_2);
+
+        let (surfaces, lights) = graph.flatten();
+        // The front wheel is now at (0, 6, 0)
+        let hit = trace(&surfaces, &down_at(0.0, 6.0)).unwrap();
+        assert!((hit.point.z - 0.3).abs() < 1e-4);
+        assert!(trace(&surfaces, &down_at(11.0, 0.0)).is_none());
+
+        // The headlight turned with the car to (0, 7, 1)
+        let headlight = lights[0]
+            .sample(Vec3::new(0.0, 7.0, 0.0), 0.5, 0.5)
+            .unwrap();
+        assert!((headlight.distance - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_material_override_and_visibility() {
+        let mut graph = car_graph();
+        let red = Material::matte(Color::new(1.0, 0.0, 0.0), 1.0);
+        let blue = Material::matte(Color::new(0.0, 0.0, 1.0), 1.0);
+        graph.find_mut("car").unwrap().material = Some(red);
+        graph.find_mut("rear_wheel").unwrap().material = Some(blue);
+
+        let (surfaces, _) = graph.flatten();
+        let body = trace(&surfaces, &down_at(10.0, 0.0)).unwrap();
+        let front = trace(&surfaces, &down_at(11.0, 0.0)).unwrap();
+        let rear = trace(&surfaces, &down_at(9.0, 0.0)).unwrap();
+        assert_eq!(body.material.albedo, red.albedo);
+        assert_eq!(front.material.albedo, red.albedo);
+        assert_eq!(rear.material.albedo, blue.albedo);
+
+        graph.find_mut("front_wheel").unwrap().visible = false;
+        let (surfaces, lights) = graph.flatten();
+        assert_eq!(surfaces.len(), 2);
+        assert_eq!(lights.len(), 1);
+
+        graph.find_mut("car").unwrap().visible = false;
+        let (surfaces, lights) = graph.flatten();
+        assert!(surfaces.is_empty() && lights.is_empty());
+    }
+
+    #[test]
+    fn test_untransformed_surfaces_are_shared() {
+        let mut graph = SceneGraph::new();
+        let root = graph.root();
+        let sphere: Arc<dyn Surface> =
+            Arc::new(Sphere::new(Vec3::zero(), 1.0, Material::diffuse_surface()));
+        graph.node_mut(root).surfaces.push(Arc::clone(&sphere));
+
+        let mut scene = Scene::new(RayTracer::new(
+            Color::black(),
+            4,
+            1e-3,
+            Material::diffuse_surface(),
+        ));
+        graph.add_to(&mut scene);
+        assert_eq!(scene.surfaces.len(), 1);
+        assert!(Arc::ptr_eq(&scene.surfaces[0], &sphere));
+    }
+}
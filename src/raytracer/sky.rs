@@ -0,0 +1,318 @@
+//! Analytic daylight sky (Preetham et al. 1999, "A Practical Analytic Model for Daylight").
+
+use super::light::{DirectionalLight, LightSample, LightSource};
+use super::material::Color;
+use super::sampling::cosine_sample_hemisphere;
+use super::vector::{Float, Vec3};
+use std::f32::consts::{FRAC_PI_2, PI};
+
+/// Solar irradiance above the atmosphere (W/m²).
+const SOLAR_CONSTANT: Float = 1361.0;
+
+/// Apparent angular diameter of the sun seen from the earth (radians).
+pub const SUN_ANGULAR_DIAMETER: Float = 0.0093;
+
+/// Luminance → radiance conversion: the model works in kcd/m², the renderer in W/(sr·m²).
+const KCD_TO_RADIANCE: Float = 1000.0 / 683.0;
+
+/// Unit direction towards the sun in the Z-up scene convention.
+///
+/// # Arguments
+/// * `elevation` - Angle above the horizon, in radians
+/// * `azimuth` - Angle around the up axis measured from +X towards +Y, in radians
+pub fn sun_direction(elevation: Float, azimuth: Float) -> Vec3 {
+    Vec3::new(
+        elevation.cos() * azimuth.cos(),
+        elevation.cos() * azimuth.sin(),
+        elevation.sin(),
+    )
+}
+
+/// A procedural clear sky lit by the sun.
+///
+/// Rays escaping upwards see the Preetham sky for the given sun position and
+/// turbidity; rays escaping downwards see a diffuse ground lit by sky and sun.
+/// The sun disk itself is not part of the sky: pair it with `sun()` to get a
+/// matching directional light.
+#[derive(Copy, Clone, Debug)]
+pub struct SkyLight {
+    /// Unit direction towards the sun
+    sun_direction: Vec3,
+    /// Atmospheric turbidity (2 = very clear, 10 = hazy)
+    turbidity: Float,
+    /// Perez coefficients A..E for luminance Y and chromaticity x, y
+    perez: [[Float; 5]; 3],
+    /// Zenith values of Y, x, y divided by the Perez function at the zenith
+    zenith: [Float; 3],
+    /// Radiance of the ground seen from above
+    ground: Color,
+}
+
+impl SkyLight {
+    /// Create a new sky.
+    ///
+    /// # Arguments
+    /// * `sun_direction` - Direction towards the sun (clamped to the horizon if below it)
+    /// * `turbidity` - Atmospheric haziness, from 2 (very clear) to 10 (hazy)
+    /// * `ground_albedo` - Reflectance of the ground below the horizon
+    pub fn new(sun_direction: Vec3, turbidity: Float, ground_albedo: Color) -> Self {
+        let sun_direction = sun_direction.normalize();
+        let t = turbidity.clamp(1.7, 10.0);
+        let theta_s = sun_direction.z.clamp(-1.0, 1.0).acos().min(FRAC_PI_2);
+
+        let perez = [
+            [
+                0.1787 * t - 1.4630,
+                -0.3554 * t + 0.4275,
+                -0.0227 * t + 5.3251,
+                0.1206 * t - 2.5771,
+                -0.0670 * t + 0.3703,
+            ],
+            [
+                -0.0193 * t - 0.2592,
+                -0.0665 * t + 0.0008,
+                -0.0004 * t + 0.2125,
+                -0.0641 * t - 0.8989,
+                -0.0033 * t + 0.0452,
+            ],
+            [
+                -0.0167 * t - 0.2608,
+                -0.0950 * t + 0.0092,
+                -0.0079 * t + 0.2102,
+                -0.0441 * t - 1.6537,
+                -0.0109 * t + 0.0529,
+            ],
+        ];
+
+        let chi = (4.0 / 9.0 - t / 120.0) * (PI - 2.0 * theta_s);
+        let zenith_luminance = ((4.0453 * t - 4.9710) * chi.tan() - 0.2155 * t + 2.4192).max(0.0);
+        let t2 = t * t;
+        let s = theta_s;
+        let s2 = s * s;
+        let s3 = s2 * s;
+        let zenith_x = t2 * (0.00166 * s3 - 0.00375 * s2 + 0.00209 * s)
+            + t * (-0.02903 * s3 + 0.06377 * s2 - 0.03202 * s + 0.00394)
+            + (0.11693 * s3 - 0.21196 * s2 + 0.06052 * s + 0.25886);
+        let zenith_y = t2 * (0.00275 * s3 - 0.00610 * s2 + 0.00317 * s)
+            + t * (-0.04214 * s3 + 0.08970 * s2 - 0.04153 * s + 0.00516)
+            + (0.15346 * s3 - 0.26756 * s2 + 0.06670 * s + 0.26688);
+
+        let zenith_values = [zenith_luminance, zenith_x, zenith_y];
+        let mut zenith = [0.0; 3];
+        for i in 0..3 {
+            zenith[i] = zenith_values[i] / perez_function(&perez[i], 0.0, theta_s);
+        }
+
+        let mut sky = Self {
+            sun_direction,
+            turbidity: t,
+            perez,
+            zenith,
+            ground: Color::black(),
+        };
+
+        // Diffuse ground: albedo / π times the irradiance from sky and sun
+        let sun = sky.sun();
+        let sun_irradiance = sun.irradiance * sun_direction.z.max(0.0);
+        let irradiance = sky.sky_irradiance() + sun_irradiance;
+        sky.ground = ground_albedo * irradiance * (1.0 / PI);
+        sky
+    }
+
+    /// Get the unit direction towards the sun.
+    pub fn sun_direction(&self) -> Vec3 {
+        self.sun_direction
+    }
+
+    /// Create the sun matching this sky as a directional light.
+    ///
+    /// Sunlight is extinguished by Rayleigh and aerosol scattering along the air
+    /// mass it crosses, so it dims and reddens as the sun nears the horizon.
+    pub fn sun(&self) -> DirectionalLight {
+        let elevation = self.sun_direction.z;
+        let irradiance = if elevation <= 0.0 {
+            Color::black()
+        } else {
+            // Kasten–Young relative air mass
+            let zenith_degrees = elevation.acos().to_degrees();
+            let air_mass = 1.0 / (elevation + 0.50572 * (96.07995 - zenith_degrees).powf(-1.6364));
+
+            // Optical depths at roughly 680, 550 and 440 nm
+            let rayleigh = [0.045, 0.097, 0.235];
+            let beta = 0.04608 * self.turbidity - 0.04586;
+            let wavelengths: [Float; 3] = [0.68, 0.55, 0.44];
+            let [r, g, b] = [0, 1, 2]
+                .map(|i| (-air_mass * (rayleigh[i] + beta * wavelengths[i].powf(-1.3))).exp());
+            Color::new(r, g, b) * SOLAR_CONSTANT
+        };
+
+        DirectionalLight::new(-self.sun_direction, irradiance)
+            .with_angular_diameter(SUN_ANGULAR_DIAMETER)
+    }
+
+    /// Radiance of the sky (or ground) seen in `direction`.
+    pub fn radiance_from(&self, direction: Vec3) -> Color {
+        let direction = direction.normalize();
+        if direction.z < 0.0 {
+            return self.ground;
+        }
+
+        // Keep the Perez function finite at the horizon
+        let cos_theta = direction.z.max(0.01);
+        let theta = cos_theta.acos();
+        let gamma = direction.dot(self.sun_direction).clamp(-1.0, 1.0).acos();
+
+        let luminance = self.zenith[0] * perez_function(&self.perez[0], theta, gamma);
+        let x = self.zenith[1] * perez_function(&self.perez[1], theta, gamma);
+        let y = self.zenith[2] * perez_function(&self.perez[2], theta, gamma);
+        xyy_to_rgb(x, y, luminance) * KCD_TO_RADIANCE
+    }
+
+    /// Irradiance from the sky dome on an upward-facing surface, integrated numerically.
+    fn sky_irradiance(&self) -> Color {
+        const THETA_STEPS: usize = 16;
+        const PHI_STEPS: usize = 32;
+        let d_theta = FRAC_PI_2 / THETA_STEPS as Float;
+        let d_phi = 2.0 * PI / PHI_STEPS as Float;
+
+        let mut irradiance = Color::black();
+        for i in 0..THETA_STEPS {
+            let theta = (i as Float + 0.5) * d_theta;
+            let weight = theta.cos() * theta.sin() * d_theta * d_phi;
+            for j in 0..PHI_STEPS {
+                let phi = (j as Float + 0.5) * d_phi;
+                let direction = Vec3::new(
+                    theta.sin() * phi.cos(),
+                    theta.sin() * phi.sin(),
+                    theta.cos(),
+                );
+                irradiance = irradiance + self.radiance_from(direction) * weight;
+            }
+        }
+        irradiance
+    }
+}
+
+impl LightSource for SkyLight {
+    /// Sample the sky or the ground with equal probability, cosine-weighted around
+    /// the vertical, which follows the smooth falloff of the sky towards the horizon.
+    fn sample(&self, _point: Vec3, u1: Float, u2: Float) -> Option<LightSample> {
+        let (axis, u1) = if u1 < 0.5 {
+            (Vec3::new(0.0, 0.0, 1.0), u1 * 2.0)
+        } else {
+            (Vec3::new(0.0, 0.0, -1.0), (u1 - 0.5) * 2.0)
+        };
+        let direction = cosine_sample_hemisphere(axis, u1, u2);
+        let pdf = 0.5 * direction.z.abs() / PI;
+        if pdf <= 0.0 {
+            return None;
+        }
+
+        Some(LightSample {
+            direction,
+            distance: Float::INFINITY,
+            radiance: self.radiance_from(direction),
+            pdf,
+        })
+    }
+
+    fn escaped(&self, direction: Vec3) -> Color {
+        self.radiance_from(direction)
+    }
+}
+
+/// Perez sky distribution function F(θ, γ).
+fn perez_function(coefficients: &[Float; 5], theta: Float, gamma: Float) -> Float {
+    let [a, b, c, d, e] = *coefficients;
+    let cos_gamma = gamma.cos();
+    (1.0 + a * (b / theta.cos().max(0.01)).exp())
+        * (1.0 + c * (d * gamma).exp() + e * cos_gamma * cos_gamma)
+}
+
+/// Convert CIE xyY to linear sRGB.
+fn xyy_to_rgb(x: Float, y: Float, luminance: Float) -> Color {
+    if y <= 0.0 {
+        return Color::black();
+    }
+    let big_x = x * luminance / y;
+    let big_z = (1.0 - x - y) * luminance / y;
+    let big_y = luminance;
+    Color::new(
+        (3.2406 * big_x - 1.5372 * big_y - 0.4986 * big_z).max(0.0),
+        (-0.9689 * big_x + 1.8758 * big_y + 0.0415 * big_z).max(0.0),
+        (0.0557 * big_x - 0.2040 * big_y + 1.0570 * big_z).max(0.0),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sun_direction() {
+        let noon = sun_direction(FRAC_PI_2, 0.0);
+        assert!((noon.z - 1.0).abs() < 1e-5);
+
+        let east = sun_direction(0.0, FRAC_PI_2);
+        assert!((east.y - 1.0).abs() < 1e-5);
+        assert!(east.z.abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_daylight_sky_colors() {
+        let sky = SkyLight::new(sun_direction(1.0, 0.0), 2.5, Color::new(0.3, 0.3, 0.3));
+
+        // Clear sky is blue at the zenith and brighter towards the sun
+        let zenith = sky.radiance_from(Vec3::new(0.0, 0.0, 1.0));
+        assert!(zenith.b > zenith.r);
+        let near_sun = sky.radiance_from(sun_direction(0.9, 0.0));
+        let away = sky.radiance_from(sun_direction(0.9, PI));
+        assert!(near_sun.luminance() > away.luminance());
+
+        // The ground reflects what the sky and sun deliver
+        let ground = sky.radiance_from(Vec3::new(0.0, 0.0, -1.0));
+        assert!(ground.luminance() > 0.0);
+        let black_ground = SkyLight::new(sun_direction(1.0, 0.0), 2.5, Color::black());
+        assert_eq!(
+            black_ground.radiance_from(Vec3::new(0.0, 0.0, -1.0)),
+            Color::black()
+        );
+    }
+
+    #[test]
+    fn test_sun_reddens_near_horizon() {
+        let noon = SkyLight::new(sun_direction(1.4, 0.0), 3.0, Color::black()).sun();
+        let sunset = SkyLight::new(sun_direction(0.05, 0.0), 3.0, Color::black()).sun();
+
+        assert!(noon.irradiance.g > 500.0 && noon.irradiance.g < SOLAR_CONSTANT);
+        assert!(sunset.irradiance.g < noon.irradiance.g);
+        assert!(sunset.irradiance.r / sunset.irradiance.b > noon.irradiance.r / noon.irradiance.b);
+        assert!((sunset.direction + sun_direction(0.05, 0.0)).length() < 1e-5);
+
+        let night = SkyLight::new(sun_direction(-0.2, 0.0), 3.0, Color::black()).sun();
+        assert_eq!(night.irradiance, Color::black());
+    }
+
+    #[test]
+    fn test_sky_sampling_matches_irradiance() {
+        let sky = SkyLight::new(sun_direction(0.6, 0.3), 4.0, Color::new(0.2, 0.2, 0.2));
+        let normal = Vec3::new(0.0, 0.0, 1.0);
+
+        let n = 64;
+        let mut estimate = Color::black();
+        for i in 0..n {
+            for j in 0..n {
+                let u1 = (i as Float + 0.5) / n as Float;
+                let u2 = (j as Float + 0.5) / n as Float;
+                let sample = sky.sample(Vec3::zero(), u1, u2).unwrap();
+                assert_eq!(sample.radiance, sky.escaped(sample.direction));
+                let cos = sample.direction.dot(normal).max(0.0);
+                estimate = estimate + sample.radiance * (cos / sample.pdf);
+            }
+        }
+        estimate = estimate * (1.0 / (n * n) as Float);
+
+        let expected = sky.sky_irradiance();
+        assert!((estimate.g - expected.g).abs() / expected.g < 0.03);
+    }
+}
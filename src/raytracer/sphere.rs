@@ -1,8 +1,10 @@
 //! Sphere primitive for the raytracer.
 
+use super::aabb::Aabb;
 use super::material::Material;
 use super::vector::{Float, Vec3};
 use super::{Intersection, Ray, Surface};
+use std::f32::consts::PI;
 
 /// A sphere defined by a center position and radius.
 #[derive(Copy, Clone, Debug)]
@@ -37,12 +39,33 @@ impl Sphere {
 
     /// Get the surface area of the sphere.
     pub fn surface_area(&self) -> Float {
-        4.0 * std::f32::consts::PI * self.radius * self.radius
+        4.0 * PI * self.radius * self.radius
+    }
+
+    /// Texture coordinates of a point on the sphere and their position derivatives.
+    ///
+    /// u wraps around the up (+Z) axis starting at +X, and v runs from the
+    /// south pole (0) to the north pole (1).
+    pub fn uv_at(&self, point: Vec3) -> ([Float; 2], Vec3, Vec3) {
+        let offset = point - self.center;
+        let phi = offset.y.atan2(offset.x);
+        let theta = (offset.z / self.radius).clamp(-1.0, 1.0).acos();
+        let uv = [(phi / (2.0 * PI)).rem_euclid(1.0), 1.0 - theta / PI];
+
+        let rho = (offset.x * offset.x + offset.y * offset.y).sqrt();
+        let dpdu = Vec3::new(-offset.y, offset.x, 0.0) * (2.0 * PI);
+        let dpdv = if rho > 0.0 {
+            Vec3::new(-offset.z * offset.x / rho, -offset.z * offset.y / rho, rho) * PI
+        } else {
+            // At the poles any direction along the surface will do
+            Vec3::new(-offset.z.signum() * self.radius, 0.0, 0.0) * PI
+        };
+        (uv, dpdu, dpdv)
     }
 
     /// Get the volume of the sphere.
     pub fn volume(&self) -> Float {
-        4.0 / 3.0 * std::f32::consts::PI * self.radius * self.radius * self.radius
+        4.0 / 3.0 * PI * self.radius * self.radius * self.radius
     }
 }
 
@@ -83,14 +106,21 @@ impl Surface for Sphere {
 
         let point = ray.at(t);
         let normal = self.normal_at(point);
+        let (uv, dpdu, dpdv) = self.uv_at(point);
 
-        Some(Intersection::new(t, point, normal, self.material))
+        Some(Intersection::new(t, point, normal, self.material).with_uv(uv, dpdu, dpdv))
     }
 
     /// Get the material of this sphere.
     fn material(&self) -> Material {
         self.material
     }
+
+    /// Get the bounding box (AABB) of the sphere.
+    fn bounds(&self) -> Aabb {
+        let r = Vec3::new(self.radius, self.radius, self.radius);
+        Aabb::new(self.center - r, self.center + r)
+    }
 }
 
 #[cfg(test)]
@@ -164,4 +194,14 @@ mod tests {
         let expected = 4.0 / 3.0 * std::f32::consts::PI;
         assert!((sphere.volume() - expected).abs() < 1e-5);
     }
+
+    #[test]
+    fn test_sphere_bounds() {
+        let material = Material::matte(Color::white(), 0.8);
+        let sphere = Sphere::new(Vec3::new(1.0, 2.0, 3.0), 0.5, material);
+        let bounds = sphere.bounds();
+
+        assert_eq!(bounds.min, Vec3::new(0.5, 1.5, 2.5));
+        assert_eq!(bounds.max, Vec3::new(1.5, 2.5, 3.5));
+    }
 }
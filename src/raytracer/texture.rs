@@ -0,0 +1,950 @@
+//! Image and procedural textures driving material parameters across a surface.
+
+use super::aabb::Aabb;
+use super::material::{Color, Material};
+use super::sampling::Rng;
+use super::vector::{Float, Vec3};
+use super::{Intersection, Ray, Surface};
+use std::path::Path;
+use std::sync::Arc;
+
+/// A color that varies over a surface.
+/// Textures are shared between render threads, so they must be `Send + Sync`.
+pub trait Texture: Send + Sync {
+    /// Value of the texture at a surface point.
+    ///
+    /// # Arguments
+    /// * `uv` - Texture coordinates of the point
+    /// * `point` - World-space position of the point (used by solid textures)
+    /// * `footprint` - Approximate width of the area seen by the ray, in UV units
+    ///   (0 = sample a single point)
+    fn value(&self, uv: [Float; 2], point: Vec3, footprint: Float) -> Color;
+}
+
+/// A constant color is the simplest texture.
+impl Texture for Color {
+    fn value(&self, _uv: [Float; 2], _point: Vec3, _footprint: Float) -> Color {
+        *self
+    }
+}
+
+/// How texture coordinates outside [0, 1] are mapped onto an image.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WrapMode {
+    /// Tile the image
+    Repeat,
+    /// Extend the edge pixels
+    Clamp,
+    /// Tile the image, flipping every other copy
+    Mirror,
+}
+
+/// How an image texture is reconstructed between pixels.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Filter {
+    /// Nearest pixel
+    Nearest,
+    /// Bilinear interpolation of the four nearest pixels
+    Bilinear,
+    /// Bilinear interpolation between the two mip levels matching the ray footprint
+    Trilinear,
+}
+
+/// One level of a mip pyramid.
+struct MipLevel {
+    width: usize,
+    height: usize,
+    pixels: Vec<Color>,
+}
+
+impl MipLevel {
+    /// Pixel at integer coordinates, wrapped into the image.
+    fn texel(&self, x: i64, y: i64, wrap: WrapMode) -> Color {
+        let x = wrap_index(x, self.width, wrap);
+        let y = wrap_index(y, self.height, wrap);
+        self.pixels[y * self.width + x]
+    }
+
+    /// Half-resolution copy, each pixel averaging a 2×2 block.
+    fn downsample(&self) -> MipLevel {
+        let width = (self.width / 2).max(1);
+        let height = (self.height / 2).max(1);
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let mut sum = Color::black();
+                for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                    let sx = (2 * x + dx).min(self.width - 1);
+                    let sy = (2 * y + dy).min(self.height - 1);
+                    sum = sum + self.pixels[sy * self.width + sx];
+                }
+                pixels.push(sum * 0.25);
+            }
+        }
+        MipLevel {
+            width,
+            height,
+            pixels,
+        }
+    }
+}
+
+/// Map an integer pixel coordinate into [0, size).
+fn wrap_index(i: i64, size: usize, wrap: WrapMode) -> usize {
+    let size = size as i64;
+    let wrapped = match wrap {
+        WrapMode::Repeat => i.rem_euclid(size),
+        WrapMode::Clamp => i.clamp(0, size - 1),
+        WrapMode::Mirror => {
+            let m = i.rem_euclid(2 * size);
+            if m < size { m } else { 2 * size - 1 - m }
+        }
+    };
+    wrapped as usize
+}
+
+/// A texture defined by an image, with a mip pyramid for filtering.
+///
+/// The image covers UV coordinates [0, 1]²: u runs left to right and v bottom to top,
+/// as in Wavefront OBJ files.
+pub struct ImageTexture {
+    /// Mip levels, from full resolution down to 1×1
+    levels: Vec<MipLevel>,
+    /// Mapping of coordinates outside [0, 1]
+    pub wrap: WrapMode,
+    /// Reconstruction filter
+    pub filter: Filter,
+}
+
+impl ImageTexture {
+    /// Create a texture from linear pixel values.
+    ///
+    /// # Arguments
+    /// * `width` - Image width in pixels
+    /// * `height` - Image height in pixels
+    /// * `pixels` - Color of each pixel, row by row from the top
+    ///
+    /// # Panics
+    /// Panics if the image is empty or `pixels` does not hold `width * height` values.
+    pub fn new(width: usize, height: usize, pixels: Vec<Color>) -> Self {
+        assert!(width > 0 && height > 0, "Texture image must not be empty");
+        assert_eq!(pixels.len(), width * height, "Texture image size mismatch");
+
+        let mut levels = vec![MipLevel {
+            width,
+            height,
+            pixels,
+        }];
+        while let Some(last) = levels.last()
+            && (last.width > 1 || last.height > 1)
+        {
+            let next = last.downsample();
+            levels.push(next);
+        }
+
+        Self {
+            levels,
+            wrap: WrapMode::Repeat,
+            filter: Filter::Trilinear,
+        }
+    }
+
+    /// Load a color texture from an image file.
+    /// 8- and 16-bit images are decoded from sRGB to linear values;
+    /// floating point images (HDR, EXR) are used as is.
+    pub fn load(path: impl AsRef<Path>) -> ::image::ImageResult<Self> {
+        Self::load_image(path, true)
+    }
+
+    /// Load a data texture (roughness, rates, normal maps) whose values are stored linearly.
+    pub fn load_linear(path: impl AsRef<Path>) -> ::image::ImageResult<Self> {
+        Self::load_image(path, false)
+    }
+
+    fn load_image(path: impl AsRef<Path>, srgb: bool) -> ::image::ImageResult<Self> {
+        let image = ::image::open(path)?;
+        let decode_srgb = srgb
+            && !matches!(
+                image.color(),
+                ::image::ColorType::Rgb32F | ::image::ColorType::Rgba32F
+            );
+        let image = image.into_rgb32f();
+        let (width, height) = image.dimensions();
+        let decode = |c: Float| if decode_srgb { srgb_to_linear(c) } else { c };
+        let pixels = image
+            .pixels()
+            .map(|pixel| Color::new(decode(pixel[0]), decode(pixel[1]), decode(pixel[2])))
+            .collect();
+        Ok(Self::new(width as usize, height as usize, pixels))
+    }
+
+    /// Set how coordinates outside [0, 1] are handled.
+    pub fn with_wrap(mut self, wrap: WrapMode) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Set the reconstruction filter.
+    pub fn with_filter(mut self, filter: Filter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Image width in pixels.
+    pub fn width(&self) -> usize {
+        self.levels[0].width
+    }
+
+    /// Image height in pixels.
+    pub fn height(&self) -> usize {
+        self.levels[0].height
+    }
+
+    /// Number of mip levels, including the full-resolution image.
+    pub fn level_count(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Sample the nearest pixel of a mip level.
+    fn nearest(&self, level: usize, uv: [Float; 2]) -> Color {
+        let level = &self.levels[level];
+        let x = (uv[0] * level.width as Float).floor() as i64;
+        let y = ((1.0 - uv[1]) * level.height as Float).floor() as i64;
+        level.texel(x, y, self.wrap)
+    }
+
+    /// Bilinearly interpolate the four pixels of a mip level around `uv`.
+    fn bilinear(&self, level: usize, uv: [Float; 2]) -> Color {
+        let level = &self.levels[level];
+        // Pixel centers sit at half-integer coordinates
+        let x = uv[0] * level.width as Float - 0.5;
+        let y = (1.0 - uv[1]) * level.height as Float - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i64, y0 as i64);
+
+        let top =
+            level.texel(x0, y0, self.wrap) * (1.0 - fx) + level.texel(x0 + 1, y0, self.wrap) * fx;
+        let bottom = level.texel(x0, y0 + 1, self.wrap) * (1.0 - fx)
+            + level.texel(x0 + 1, y0 + 1, self.wrap) * fx;
+        top * (1.0 - fy) + bottom * fy
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, uv: [Float; 2], _point: Vec3, footprint: Float) -> Color {
+        match self.filter {
+            Filter::Nearest => self.nearest(0, uv),
+            Filter::Bilinear => self.bilinear(0, uv),
+            Filter::Trilinear => {
+                // Pick the levels whose pixels are about as wide as the footprint
+                let size = self.width().max(self.height()) as Float;
+                let texels = footprint * size;
+                let max_level = (self.levels.len() - 1) as Float;
+                let lod = if texels > 1.0 {
+                    texels.log2().min(max_level)
+                } else {
+                    0.0
+                };
+                let lower = lod.floor();
+                let blend = lod - lower;
+                let color = self.bilinear(lower as usize, uv);
+                if blend > 0.0 {
+                    color * (1.0 - blend) + self.bilinear(lower as usize + 1, uv) * blend
+                } else {
+                    color
+                }
+            }
+        }
+    }
+}
+
+/// Convert an sRGB-encoded channel value to linear light.
+fn srgb_to_linear(c: Float) -> Float {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// A checkerboard alternating between two colors in UV space.
+#[derive(Copy, Clone, Debug)]
+pub struct Checker {
+    /// Color of the square at the origin
+    pub even: Color,
+    /// Color of the neighboring squares
+    pub odd: Color,
+    /// Number of squares per unit of u and v
+    pub scale: Float,
+}
+
+impl Checker {
+    /// Create a checkerboard with `scale` squares per unit of UV space.
+    pub fn new(even: Color, odd: Color, scale: Float) -> Self {
+        Self { even, odd, scale }
+    }
+}
+
+impl Texture for Checker {
+    fn value(&self, uv: [Float; 2], _point: Vec3, _footprint: Float) -> Color {
+        let cell = (uv[0] * self.scale).floor() as i64 + (uv[1] * self.scale).floor() as i64;
+        if cell.rem_euclid(2) == 0 {
+            self.even
+        } else {
+            self.odd
+        }
+    }
+}
+
+/// Texture coordinate along which a `Gradient` varies.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GradientAxis {
+    /// Left to right
+    U,
+    /// Bottom to top
+    V,
+}
+
+/// A linear blend between two colors across UV space.
+#[derive(Copy, Clone, Debug)]
+pub struct Gradient {
+    /// Color at coordinate 0
+    pub start: Color,
+    /// Color at coordinate 1
+    pub end: Color,
+    /// Coordinate the blend follows
+    pub axis: GradientAxis,
+}
+
+impl Gradient {
+    /// Create a gradient from `start` at 0 to `end` at 1 along the given axis.
+    pub fn new(start: Color, end: Color, axis: GradientAxis) -> Self {
+        Self { start, end, axis }
+    }
+}
+
+impl Texture for Gradient {
+    fn value(&self, uv: [Float; 2], _point: Vec3, _footprint: Float) -> Color {
+        let t = match self.axis {
+            GradientAxis::U => uv[0],
+            GradientAxis::V => uv[1],
+        }
+        .clamp(0.0, 1.0);
+        self.start * (1.0 - t) + self.end * t
+    }
+}
+
+/// Solid Perlin noise texture blending between two colors.
+///
+/// The noise is evaluated at the world-space position, so it needs no texture
+/// coordinates and does not stretch over curved surfaces (marble, stone, wood).
+#[derive(Clone, Debug)]
+pub struct Noise {
+    /// Color where the noise is lowest
+    pub low: Color,
+    /// Color where the noise is highest
+    pub high: Color,
+    /// Noise features per world unit
+    pub scale: Float,
+    /// Number of summed octaves of increasing frequency (fractal Brownian motion)
+    pub octaves: u32,
+    /// Shuffled lattice indices, repeated twice to avoid wrapping
+    permutation: Vec<u8>,
+}
+
+impl Noise {
+    /// Create a noise texture with `scale` features per world unit.
+    pub fn new(low: Color, high: Color, scale: Float) -> Self {
+        Self::with_seed(low, high, scale, 0)
+    }
+
+    /// Create a noise texture whose pattern depends on `seed`.
+    pub fn with_seed(low: Color, high: Color, scale: Float, seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+        let mut table: Vec<u8> = (0..=255).collect();
+        for i in (1..table.len()).rev() {
+            let j = (rng.next_u32() as usize) % (i + 1);
+            table.swap(i, j);
+        }
+        let permutation = table.iter().chain(table.iter()).copied().collect();
+
+        Self {
+            low,
+            high,
+            scale,
+            octaves: 1,
+            permutation,
+        }
+    }
+
+    /// Set the number of octaves (at least 1).
+    pub fn with_octaves(mut self, octaves: u32) -> Self {
+        self.octaves = octaves.max(1);
+        self
+    }
+
+    /// Improved Perlin noise at a point, in about [-1, 1].
+    pub fn noise(&self, p: Vec3) -> Float {
+        let cell = |c: Float| (c.floor() as i64).rem_euclid(256) as usize;
+        let (xi, yi, zi) = (cell(p.x), cell(p.y), cell(p.z));
+        let (x, y, z) = (p.x - p.x.floor(), p.y - p.y.floor(), p.z - p.z.floor());
+        let fade = |t: Float| t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+        let (u, v, w) = (fade(x), fade(y), fade(z));
+
+        let perm = |i: usize| self.permutation[i] as usize;
+        let a = perm(xi) + yi;
+        let b = perm(xi + 1) + yi;
+        let (aa, ab) = (perm(a) + zi, perm(a + 1) + zi);
+        let (ba, bb) = (perm(b) + zi, perm(b + 1) + zi);
+
+        let lerp = |t: Float, a: Float, b: Float| a + t * (b - a);
+        lerp(
+            w,
+            lerp(
+                v,
+                lerp(
+                    u,
+                    gradient(perm(aa), x, y, z),
+                    gradient(perm(ba), x - 1.0, y, z),
+                ),
+                lerp(
+                    u,
+                    gradient(perm(ab), x, y - 1.0, z),
+                    gradient(perm(bb), x - 1.0, y - 1.0, z),
+                ),
+            ),
+            lerp(
+                v,
+                lerp(
+                    u,
+                    gradient(perm(aa + 1), x, y, z - 1.0),
+                    gradient(perm(ba + 1), x - 1.0, y, z - 1.0),
+                ),
+                lerp(
+                    u,
+                    gradient(perm(ab + 1), x, y - 1.0, z - 1.0),
+                    gradient(perm(bb + 1), x - 1.0, y - 1.0, z - 1.0),
+                ),
+            ),
+        )
+    }
+}
+
+/// Dot product of a pseudo-random lattice gradient with the offset (x, y, z).
+fn gradient(hash: usize, x: Float, y: Float, z: Float) -> Float {
+    let h = hash & 15;
+    let u = if h < 8 { x } else { y };
+    let v = if h < 4 {
+        y
+    } else if h == 12 || h == 14 {
+        x
+    } else {
+        z
+    };
+    (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
+
+impl Texture for Noise {
+    fn value(&self, _uv: [Float; 2], point: Vec3, _footprint: Float) -> Color {
+        let mut sum = 0.0;
+        let mut amplitude = 0.5;
+        let mut frequency = self.scale;
+        for _ in 0..self.octaves {
+            sum += amplitude * self.noise(point * frequency);
+            amplitude *= 0.5;
+            frequency *= 2.0;
+        }
+        let t = (0.5 + sum).clamp(0.0, 1.0);
+        self.low * (1.0 - t) + self.high * t
+    }
+}
+
+/// Step in UV units used to differentiate bump maps.
+const BUMP_DELTA: Float = 1e-3;
+
+/// A surface whose material parameters are driven by textures.
+///
+/// Wraps any `Surface` reporting texture coordinates (spheres, triangles, meshes) and
+/// replaces the material of each hit with the texture values at that point.
+/// Scalar parameters (rates, roughness) use the luminance of their texture.
+/// Normal and bump maps tilt the shading normal; the geometric normal is kept,
+/// so secondary rays still leave from the correct side of the surface.
+pub struct Textured<S: Surface> {
+    /// The wrapped surface
+    pub surface: S,
+    /// Texture for `Material::albedo`
+    pub albedo: Option<Arc<dyn Texture>>,
+    /// Texture for `Material::diffuse_rate`
+    pub diffuse_rate: Option<Arc<dyn Texture>>,
+    /// Texture for `Material::specular_rate`
+    pub specular_rate: Option<Arc<dyn Texture>>,
+    /// Texture for `Material::transmission_rate`
+    pub transmission_rate: Option<Arc<dyn Texture>>,
+    /// Texture for `Material::roughness`
+    pub roughness: Option<Arc<dyn Texture>>,
+    /// Tangent-space normal map: red, green and blue encode the normal along the
+    /// tangent, bitangent and surface normal, each mapped from [-1, 1] to [0, 1]
+    pub normal_map: Option<Arc<dyn Texture>>,
+    /// Bump map whose luminance is a height above the surface
+    pub bump_map: Option<Arc<dyn Texture>>,
+    /// World-space height of a bump map value of 1
+    pub bump_scale: Float,
+    /// Angle in radians covered by one pixel, used to pick mip levels
+    /// (0 = always sample the full-resolution image)
+    pub pixel_spread: Float,
+}
+
+impl<S: Surface> Textured<S> {
+    /// Wrap a surface; no parameter is textured until a `with_*` method is called.
+    pub fn new(surface: S) -> Self {
+        Self {
+            surface,
+            albedo: None,
+            diffuse_rate: None,
+            specular_rate: None,
+            transmission_rate: None,
+            roughness: None,
+            normal_map: None,
+            bump_map: None,
+            bump_scale: 0.0,
+            pixel_spread: 0.0,
+        }
+    }
+
+    /// Drive the albedo with a texture.
+    pub fn with_albedo(mut self, texture: Arc<dyn Texture>) -> Self {
+        self.albedo = Some(texture);
+        self
+    }
+
+    /// Drive the diffuse rate with a texture.
+    pub fn with_diffuse_rate(mut self, texture: Arc<dyn Texture>) -> Self {
+        self.diffuse_rate = Some(texture);
+        self
+    }
+
+    /// Drive the specular rate with a texture.
+    pub fn with_specular_rate(mut self, texture: Arc<dyn Texture>) -> Self {
+        self.specular_rate = Some(texture);
+        self
+    }
+
+    /// Drive the transmission rate with a texture.
+    pub fn with_transmission_rate(mut self, texture: Arc<dyn Texture>) -> Self {
+        self.transmission_rate = Some(texture);
+        self
+    }
+
+    /// Drive the roughness with a texture.
+    pub fn with_roughness(mut self, texture: Arc<dyn Texture>) -> Self {
+        self.roughness = Some(texture);
+        self
+    }
+
+    /// Perturb the shading normal with a tangent-space normal map
+    /// (load it with `ImageTexture::load_linear`).
+    pub fn with_normal_map(mut self, texture: Arc<dyn Texture>) -> Self {
+        self.normal_map = Some(texture);
+        self
+    }
+
+    /// Perturb the shading normal with a height map.
+    ///
+    /// # Arguments
+    /// * `texture` - Height map (luminance, 0.0-1.0)
+    /// * `scale` - World-space height of a texture value of 1
+    pub fn with_bump_map(mut self, texture: Arc<dyn Texture>, scale: Float) -> Self {
+        self.bump_map = Some(texture);
+        self.bump_scale = scale;
+        self
+    }
+
+    /// Set the angle covered by one pixel (see `Camera::pixel_spread`) for mip filtering.
+    pub fn with_pixel_spread(mut self, pixel_spread: Float) -> Self {
+        self.pixel_spread = pixel_spread;
+        self
+    }
+
+    /// Evaluate the textures at a hit and write them into its material.
+    ///
+    /// # Arguments
+    /// * `intersection` - Hit to texture
+    /// * `travelled` - Path length before the ray that produced the hit
+    fn apply(&self, intersection: &mut Intersection, travelled: Float) {
+        // Width of the ray footprint in world units, converted to UV units.
+        // The footprint keeps widening over every bounce, not just the last segment.
+        let uv_area = intersection.dpdu.cross(intersection.dpdv).length();
+        let footprint = if self.pixel_spread > 0.0 && uv_area > 0.0 {
+            (travelled + intersection.t) * self.pixel_spread / uv_area.sqrt()
+        } else {
+            0.0
+        };
+
+        let (uv, point) = (intersection.uv, intersection.point);
+        let sample = |texture: &Arc<dyn Texture>| texture.value(uv, point, footprint);
+        let scalar = |texture: &Arc<dyn Texture>| sample(texture).luminance().clamp(0.0, 1.0);
+
+        let material = &mut intersection.material;
+        if let Some(texture) = &self.albedo {
+            material.albedo = sample(texture);
+        }
+        if let Some(texture) = &self.diffuse_rate {
+            material.diffuse_rate = scalar(texture);
+        }
+        if let Some(texture) = &self.specular_rate {
+            material.specular_rate = scalar(texture);
+        }
+        if let Some(texture) = &self.transmission_rate {
+            material.transmission_rate = scalar(texture);
+        }
+        if let Some(texture) = &self.roughness {
+            material.roughness = scalar(texture);
+        }
+        // Rates textured separately can sum past 1; scale them down like `Material::normalized`
+        let total = material.total_rate();
+        if total > 1.0 {
+            material.diffuse_rate /= total;
+            material.specular_rate /= total;
+            material.transmission_rate /= total;
+        }
+
+        // === NORMAL MAPPING ===
+        if let Some(texture) = &self.normal_map {
+            intersection.compute_tangent_frame();
+            let encoded = sample(texture);
+            let normal = intersection.tangent * (2.0 * encoded.r - 1.0)
+                + intersection.bitangent * (2.0 * encoded.g - 1.0)
+                + intersection.normal * (2.0 * encoded.b - 1.0);
+            if normal.length_squared() > 0.0 {
+                intersection.perturb_normal(normal);
+            }
+        }
+
+        // === BUMP MAPPING ===
+        // Displace the surface along the normal by the height and take the normal of the
+        // displaced surface from finite differences of the height in u and v
+        if let Some(texture) = &self.bump_map {
+            let delta = footprint.max(BUMP_DELTA);
+            let height = |uv: [Float; 2]| texture.value(uv, point, footprint).luminance();
+            let base = height(uv);
+            let dhdu = (height([uv[0] + delta, uv[1]]) - base) / delta * self.bump_scale;
+            let dhdv = (height([uv[0], uv[1] + delta]) - base) / delta * self.bump_scale;
+
+            let normal = intersection.normal;
+            let dpdu = intersection.dpdu + normal * dhdu;
+            let dpdv = intersection.dpdv + normal * dhdv;
+            let bumped = dpdu.cross(dpdv);
+            if bumped.length_squared() > 0.0 {
+                intersection.perturb_normal(bumped);
+            }
+        }
+    }
+}
+
+impl<S: Surface> Surface for Textured<S> {
+    fn intersect(&self, ray: &Ray) -> Option<Intersection> {
+        let mut intersection = self.surface.intersect(ray)?;
+        self.apply(&mut intersection, ray.travelled);
+        Some(intersection)
+    }
+
+    /// Get the untextured material of the wrapped surface.
+    fn material(&self) -> Material {
+        self.surface.material()
+    }
+
+    fn bounds(&self) -> Aabb {
+        self.surface.bounds()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raytracer::mesh::Triangle;
+    use crate::raytracer::sphere::Sphere;
+
+    #[test]
+    fn test_wrap_modes() {
+        assert_eq!(wrap_index(5, 4, WrapMode::Repeat), 1);
+        assert_eq!(wrap_index(-1, 4, WrapMode::Repeat), 3);
+        assert_eq!(wrap_index(-1, 4, WrapMode::Clamp), 0);
+        assert_eq!(wrap_index(9, 4, WrapMode::Clamp), 3);
+        assert_eq!(wrap_index(4, 4, WrapMode::Mirror), 3);
+        assert_eq!(wrap_index(-1, 4, WrapMode::Mirror), 0);
+        assert_eq!(wrap_index(8, 4, WrapMode::Mirror), 0);
+    }
+
+    #[test]
+    fn test_image_texture_filtering() {
+        // 2×1 image: black on the left, white on the right
+        let texture = ImageTexture::new(2, 1, vec![Color::black(), Color::white()])
+            .with_filter(Filter::Bilinear)
+            .with_wrap(WrapMode::Clamp);
+        let at = |u: Float| texture.value([u, 0.5], Vec3::zero(), 0.0).r;
+        assert_eq!(at(0.1), 0.0);
+        assert!((at(0.5) - 0.5).abs() < 1e-5);
+        assert_eq!(at(0.9), 1.0);
+
+        let nearest = texture.with_filter(Filter::Nearest);
+        assert_eq!(nearest.value([0.49, 0.5], Vec3::zero(), 0.0).r, 0.0);
+        assert_eq!(nearest.value([0.51, 0.5], Vec3::zero(), 0.0).r, 1.0);
+    }
+
+    #[test]
+    fn test_mip_levels_average() {
+        // 4×4 checkerboard of pixels averages to grey at the coarsest level
+        let pixels = (0..16)
+            .map(|i| {
+                if (i % 4 + i / 4) % 2 == 0 {
+                    Color::white()
+                } else {
+                    Color::black()
+                }
+            })
+            .collect();
+        let texture = ImageTexture::new(4, 4, pixels);
+        assert_eq!(texture.level_count(), 3);
+
+        let sharp = texture.value([0.125, 0.875], Vec3::zero(), 0.0);
+        assert_eq!(sharp, Color::white());
+        let blurred = texture.value([0.125, 0.875], Vec3::zero(), 1.0);
+        assert!((blurred.r - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_load_srgb_texture() {
+        let path = std::env::temp_dir().join("raytracer_texture_test.png");
+        let mut image = ::image::RgbImage::new(1, 1);
+        image.put_pixel(0, 0, ::image::Rgb([255, 188, 0]));
+        image.save(&path).unwrap();
+
+        let color = ImageTexture::load(&path)
+            .unwrap()
+            .value([0.5, 0.5], Vec3::zero(), 0.0);
+        let linear = ImageTexture::load_linear(&path)
+            .unwrap()
+            .value([0.5, 0.5], Vec3::zero(), 0.0);
+        std::fs::remove_file(&path).ok();
+
+        assert!((color.r - 1.0).abs() < 1e-5);
+        // sRGB 188 is about half the linear intensity
+        assert!((color.g - 0.5).abs() < 0.01);
+        assert!((linear.g - 188.0 / 255.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_procedural_textures() {
+        let checker = Checker::new(Color::white(), Color::black(), 2.0);
+        assert_eq!(checker.value([0.1, 0.1], Vec3::zero(), 0.0), Color::white());
+        assert_eq!(checker.value([0.6, 0.1], Vec3::zero(), 0.0), Color::black());
+        assert_eq!(checker.value([0.6, 0.6], Vec3::zero(), 0.0), Color::white());
+        assert_eq!(
+            checker.value([-0.1, 0.1], Vec3::zero(), 0.0),
+            Color::black()
+        );
+
+        let gradient = Gradient::new(Color::black(), Color::white(), GradientAxis::V);
+        assert!((gradient.value([0.9, 0.25], Vec3::zero(), 0.0).g - 0.25).abs() < 1e-6);
+
+        let noise = Noise::new(Color::black(), Color::white(), 3.0).with_octaves(4);
+        let mut min: Float = 1.0;
+        let mut max: Float = 0.0;
+        for i in 0..200 {
+            let p = Vec3::new(i as Float * 0.137, i as Float * 0.071, 0.5);
+            let value = noise.value([0.0, 0.0], p, 0.0).r;
+            min = min.min(value);
+            max = max.max(value);
+        }
+        assert!(min >= 0.0 && max <= 1.0);
+        assert!(max - min > 0.3);
+        // Noise vanishes on lattice points and is continuous
+        assert_eq!(noise.noise(Vec3::new(1.0, 2.0, 3.0)), 0.0);
+        let p = Vec3::new(0.3, 0.4, 0.5);
+        let delta = noise.noise(p) - noise.noise(p + Vec3::new(1e-3, 0.0, 0.0));
+        assert!(delta.abs() < 0.01);
+    }
+
+    #[test]
+    fn test_textured_surfaces() {
+        let material = Material::matte(Color::white(), 1.0);
+        let checker: Arc<dyn Texture> = Arc::new(Checker::new(Color::red(), Color::blue(), 2.0));
+
+        // Triangle UVs default to the barycentric coordinates
+        let triangle = Triangle::new(
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            material,
+        );
+        let textured = Textured::new(triangle).with_albedo(Arc::clone(&checker));
+        let down = Vec3::new(0.0, 0.0, -1.0);
+        let hit = textured
+            .intersect(&Ray::new(Vec3::new(0.2, 0.1, 1.0), down))
+            .unwrap();
+        assert!((hit.uv[0] - 0.2).abs() < 1e-5 && (hit.uv[1] - 0.1).abs() < 1e-5);
+        assert_eq!(hit.material.albedo, Color::red());
+        let hit = textured
+            .intersect(&Ray::new(Vec3::new(0.7, 0.1, 1.0), down))
+            .unwrap();
+        assert_eq!(hit.material.albedo, Color::blue());
+
+        // Explicit UVs and their derivatives
+        let scaled = triangle.with_uvs([[0.0, 0.0], [2.0, 0.0], [0.0, 4.0]]);
+        let hit = scaled
+            .intersect(&Ray::new(Vec3::new(0.25, 0.25, 1.0), down))
+            .unwrap();
+        assert!((hit.uv[0] - 0.5).abs() < 1e-5 && (hit.uv[1] - 1.0).abs() < 1e-5);
+        assert!((hit.dpdu - Vec3::new(0.5, 0.0, 0.0)).length() < 1e-5);
+        assert!((hit.dpdv - Vec3::new(0.0, 0.25, 0.0)).length() < 1e-5);
+
+        // Sphere: v runs from the south to the north pole, u starts at +X
+        let sphere = Sphere::new(Vec3::zero(), 2.0, material);
+        let hit = sphere
+            .intersect(&Ray::new(Vec3::new(0.0, 0.0, 5.0), down))
+            .unwrap();
+        assert!((hit.uv[1] - 1.0).abs() < 1e-5);
+        let hit = sphere
+            .intersect(&Ray::new(
+                Vec3::new(0.0, 5.0, 0.0),
+                Vec3::new(0.0, -1.0, 0.0),
+            ))
+            .unwrap();
+        assert!((hit.uv[0] - 0.25).abs() < 1e-5 && (hit.uv[1] - 0.5).abs() < 1e-5);
+        // Moving along u circles the equator (length 2πr per unit of u)
+        assert!((hit.dpdu - Vec3::new(-4.0 * std::f32::consts::PI, 0.0, 0.0)).length() < 1e-4);
+        assert!(hit.dpdv.z > 0.0);
+
+        // Scalar parameters use the luminance of their texture
+        let rough = Textured::new(sphere).with_roughness(Arc::new(Color::new(0.5, 0.5, 0.5)));
+        let hit = rough
+            .intersect(&Ray::new(Vec3::new(0.0, 0.0, 5.0), down))
+            .unwrap();
+        assert!((hit.material.roughness - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_textured_rates_stay_normalized() {
+        let sphere = Sphere::new(Vec3::zero(), 1.0, Material::matte(Color::white(), 1.0));
+        let textured = Textured::new(sphere)
+            .with_diffuse_rate(Arc::new(Color::new(0.8, 0.8, 0.8)))
+            .with_specular_rate(Arc::new(Color::new(0.6, 0.6, 0.6)))
+            .with_transmission_rate(Arc::new(Color::new(0.2, 0.2, 0.2)));
+        let hit = textured
+            .intersect(&Ray::new(
+                Vec3::new(0.0, 0.0, 5.0),
+                Vec3::new(0.0, 0.0, -1.0),
+            ))
+            .unwrap();
+        assert!((hit.material.total_rate() - 1.0).abs() < 1e-5);
+        assert!((hit.material.diffuse_rate - 0.5).abs() < 1e-5);
+        assert!((hit.material.specular_rate - 0.375).abs() < 1e-5);
+        assert!((hit.material.transmission_rate - 0.125).abs() < 1e-5);
+    }
+
+    /// Reports the footprint it is sampled with as a grey level.
+    struct Footprint;
+
+    impl Texture for Footprint {
+        fn value(&self, _uv: [Float; 2], _point: Vec3, footprint: Float) -> Color {
+            Color::new(footprint, footprint, footprint)
+        }
+    }
+
+    #[test]
+    fn test_footprint_spans_whole_path() {
+        let triangle = Triangle::new(
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Material::matte(Color::white(), 1.0),
+        );
+        let textured = Textured::new(triangle)
+            .with_albedo(Arc::new(Footprint))
+            .with_pixel_spread(0.01);
+        let ray = Ray::new(Vec3::new(0.2, 0.2, 1.0), Vec3::new(0.0, 0.0, -1.0));
+
+        let camera_hit = textured.intersect(&ray).unwrap();
+        assert!((camera_hit.material.albedo.r - 0.01).abs() < 1e-6);
+        // A bounce ray that already travelled 9 units sees a ten times wider footprint
+        let bounce_hit = textured.intersect(&ray.with_travelled(9.0)).unwrap();
+        assert!((bounce_hit.material.albedo.r - 0.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_tangent_frames() {
+        let material = Material::matte(Color::white(), 1.0);
+        let down = Vec3::new(0.0, 0.0, -1.0);
+
+        // Triangle: tangent follows increasing u, bitangent increasing v
+        let triangle = Triangle::new(
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            material,
+        )
+        .with_uvs([[0.0, 0.0], [0.0, 1.0], [1.0, 0.0]]);
+        let mut hit = triangle
+            .intersect(&Ray::new(Vec3::new(0.2, 0.2, 1.0), down))
+            .unwrap();
+        // Candidate hits carry no frame until it is asked for
+        assert_eq!(hit.tangent, Vec3::zero());
+        hit.compute_tangent_frame();
+        assert!((hit.tangent - Vec3::new(0.0, 1.0, 0.0)).length() < 1e-5);
+        assert!((hit.bitangent - Vec3::new(1.0, 0.0, 0.0)).length() < 1e-5);
+
+        // Sphere: tangent runs east along the parallels, bitangent north
+        let sphere = Sphere::new(Vec3::zero(), 1.0, material);
+        let mut hit = sphere
+            .intersect(&Ray::new(
+                Vec3::new(5.0, 0.0, 0.0),
+                Vec3::new(-1.0, 0.0, 0.0),
+            ))
+            .unwrap();
+        hit.compute_tangent_frame();
+        assert!((hit.tangent - Vec3::new(0.0, 1.0, 0.0)).length() < 1e-5);
+        assert!((hit.bitangent - Vec3::new(0.0, 0.0, 1.0)).length() < 1e-5);
+        assert!(hit.tangent.dot(hit.normal).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_normal_and_bump_mapping() {
+        let material = Material::matte(Color::white(), 1.0);
+        let floor = Triangle::new(
+            Vec3::new(-1.0, -1.0, 0.0),
+            Vec3::new(1.0, -1.0, 0.0),
+            Vec3::new(-1.0, 1.0, 0.0),
+            material,
+        )
+        .with_uvs([[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]]);
+        let up = Vec3::new(0.0, 0.0, 1.0);
+        let ray = Ray::new(Vec3::new(-0.5, -0.5, 1.0), -up);
+
+        // A flat normal map leaves the normal alone
+        let flat = Textured::new(floor).with_normal_map(Arc::new(Color::new(0.5, 0.5, 1.0)));
+        let hit = flat.intersect(&ray).unwrap();
+        assert!((hit.normal - up).length() < 1e-5);
+
+        // Tilting towards the tangent (+X here), with the geometric normal untouched
+        let tilted = Textured::new(floor).with_normal_map(Arc::new(Color::new(1.0, 0.5, 1.0)));
+        let mut hit = tilted.intersect(&ray).unwrap();
+        let expected = Vec3::new(1.0, 0.0, 1.0).normalize();
+        assert!((hit.normal - expected).length() < 1e-5);
+        assert_eq!(hit.geometric_normal, up);
+        hit.compute_tangent_frame();
+        assert!(hit.tangent.dot(hit.normal).abs() < 1e-5);
+
+        // Height rising along u (+X) tilts the normal the other way
+        let ramp: Arc<dyn Texture> = Arc::new(Gradient::new(
+            Color::black(),
+            Color::white(),
+            GradientAxis::U,
+        ));
+        let bumped = Textured::new(floor).with_bump_map(ramp, 2.0);
+        let hit = bumped.intersect(&ray).unwrap();
+        // Height grows by 2 over u ∈ [0, 1], which spans 2 world units: a 45° slope
+        assert!((hit.normal - Vec3::new(-1.0, 0.0, 1.0).normalize()).length() < 1e-3);
+        assert_eq!(hit.geometric_normal, up);
+    }
+}
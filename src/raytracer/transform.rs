@@ -0,0 +1,340 @@
+//! 4×4 matrices and affine transforms for placing geometry in the scene.
+
+use super::aabb::Aabb;
+use super::vector::{Float, Vec3};
+use std::ops::Mul;
+
+/// A 4×4 matrix stored in row-major order.
+/// Points and vectors are column vectors multiplied on the right.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Matrix4 {
+    /// Matrix elements indexed as `m[row][column]`
+    pub m: [[Float; 4]; 4],
+}
+
+impl Matrix4 {
+    /// Create a matrix from its rows.
+    pub const fn new(m: [[Float; 4]; 4]) -> Self {
+        Self { m }
+    }
+
+    /// Identity matrix.
+    pub const fn identity() -> Self {
+        Self::new([
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    /// Matrix with rows and columns swapped.
+    pub fn transpose(&self) -> Self {
+        let mut result = Self::identity();
+        for (row, values) in result.m.iter_mut().enumerate() {
+            for (column, value) in values.iter_mut().enumerate() {
+                *value = self.m[column][row];
+            }
+        }
+        result
+    }
+
+    /// Transform a point (w = 1), including translation.
+    pub fn transform_point(&self, p: Vec3) -> Vec3 {
+        let m = &self.m;
+        Vec3::new(
+            m[0][0] * p.x + m[0][1] * p.y + m[0][2] * p.z + m[0][3],
+            m[1][0] * p.x + m[1][1] * p.y + m[1][2] * p.z + m[1][3],
+            m[2][0] * p.x + m[2][1] * p.y + m[2][2] * p.z + m[2][3],
+        )
+    }
+
+    /// Transform a direction (w = 0), ignoring translation.
+    pub fn transform_vector(&self, v: Vec3) -> Vec3 {
+        let m = &self.m;
+        Vec3::new(
+            m[0][0] * v.x + m[0][1] * v.y + m[0][2] * v.z,
+            m[1][0] * v.x + m[1][1] * v.y + m[1][2] * v.z,
+            m[2][0] * v.x + m[2][1] * v.y + m[2][2] * v.z,
+        )
+    }
+}
+
+impl Mul for Matrix4 {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        let mut result = [[0.0; 4]; 4];
+        for (row, values) in result.iter_mut().enumerate() {
+            for (column, value) in values.iter_mut().enumerate() {
+                *value = (0..4).map(|k| self.m[row][k] * rhs.m[k][column]).sum();
+            }
+        }
+        Self::new(result)
+    }
+}
+
+/// An invertible affine transform with its inverse kept alongside.
+///
+/// `a * b` applies `b` first, then `a`; `a.then(b)` applies `a` first.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transform {
+    /// Object-to-world matrix
+    matrix: Matrix4,
+    /// World-to-object matrix
+    inverse: Matrix4,
+    /// Inverse transpose, which transforms normals
+    normal_matrix: Matrix4,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Transform {
+    /// Transform that leaves everything in place.
+    pub const fn identity() -> Self {
+        Self {
+            matrix: Matrix4::identity(),
+            inverse: Matrix4::identity(),
+            normal_matrix: Matrix4::identity(),
+        }
+    }
+
+    /// Create a transform from a matrix and its inverse.
+    fn with_inverse(matrix: Matrix4, inverse: Matrix4) -> Self {
+        Self {
+            matrix,
+            inverse,
+            normal_matrix: inverse.transpose(),
+        }
+    }
+
+    /// Move by `offset`.
+    pub fn translation(offset: Vec3) -> Self {
+        let matrix = |d: Vec3| {
+            Matrix4::new([
+                [1.0, 0.0, 0.0, d.x],
+                [0.0, 1.0, 0.0, d.y],
+                [0.0, 0.0, 1.0, d.z],
+                [0.0, 0.0, 0.0, 1.0],
+            ])
+        };
+        Self::with_inverse(matrix(offset), matrix(-offset))
+    }
+
+    /// Scale along each axis about the origin.
+    ///
+    /// # Panics
+    /// Panics if a scale factor is zero.
+    pub fn scaling(scale: Vec3) -> Self {
+        assert!(
+            scale.x != 0.0 && scale.y != 0.0 && scale.z != 0.0,
+            "scale factors must be non-zero"
+        );
+        let matrix = |s: Vec3| {
+            Matrix4::new([
+                [s.x, 0.0, 0.0, 0.0],
+                [0.0, s.y, 0.0, 0.0],
+                [0.0, 0.0, s.z, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ])
+        };
+        Self::with_inverse(
+            matrix(scale),
+            matrix(Vec3::new(1.0 / scale.x, 1.0 / scale.y, 1.0 / scale.z)),
+        )
+    }
+
+    /// Rotate counter-clockwise about `axis` through the origin, by `angle` radians.
+    pub fn rotation(axis: Vec3, angle: Float) -> Self {
+        let a = axis.normalize();
+        let (sin, cos) = angle.sin_cos();
+        let t = 1.0 - cos;
+        // Rodrigues' rotation formula
+        let matrix = Matrix4::new([
+            [
+                t * a.x * a.x + cos,
+                t * a.x * a.y - sin * a.z,
+                t * a.x * a.z + sin * a.y,
+                0.0,
+            ],
+            [
+                t * a.x * a.y + sin * a.z,
+                t * a.y * a.y + cos,
+                t * a.y * a.z - sin * a.x,
+                0.0,
+            ],
+            [
+                t * a.x * a.z - sin * a.y,
+                t * a.y * a.z + sin * a.x,
+                t * a.z * a.z + cos,
+                0.0,
+            ],
+            [0.0, 0.0, 0.0, 1.0],
+        ]);
+        // Rotations are orthogonal: the inverse is the transpose
+        Self::with_inverse(matrix, matrix.transpose())
+    }
+
+    /// World-to-object matrix.
+    pub fn inverse_matrix(&self) -> &Matrix4 {
+        &self.inverse
+    }
+
+    /// Apply this transform, then `next`.
+    pub fn then(self, next: Self) -> Self {
+        next * self
+    }
+
+    /// Transform a point.
+    pub fn point(&self, p: Vec3) -> Vec3 {
+        self.matrix.transform_point(p)
+    }
+
+    /// Transform a direction or offset.
+    pub fn vector(&self, v: Vec3) -> Vec3 {
+        self.matrix.transform_vector(v)
+    }
+
+    /// Transform a surface normal (with the inverse transpose) and normalize it,
+    /// so it stays perpendicular to the transformed surface under non-uniform scaling.
+    pub fn normal(&self, n: Vec3) -> Vec3 {
+        self.normal_matrix.transform_vector(n).normalize()
+    }
+
+    /// Average length of the transformed unit axes: the scale applied to sizes such
+    /// as radii. Exact for uniform scaling.
+    pub fn scale_factor(&self) -> Float {
+        let axes = [
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
+        ];
+        axes.iter()
+            .map(|&axis| self.vector(axis).length())
+            .sum::<Float>()
+            / 3.0
+    }
+
+    /// Bounding box of the transformed box (enclosing its eight transformed corners).
+    pub fn bounds(&self, bounds: Aabb) -> Aabb {
+        if bounds.min.x > bounds.max.x || bounds.min.y > bounds.max.y || bounds.min.z > bounds.max.z
+        {
+            return Aabb::empty();
+        }
+        (0..8).fold(Aabb::empty(), |acc, corner| {
+            let pick = |bit: usize, axis: usize| {
+                if corner & bit == 0 {
+                    bounds.min[axis]
+                } else {
+                    bounds.max[axis]
+                }
+            };
+            acc.union_point(self.point(Vec3::new(pick(1, 0), pick(2, 1), pick(4, 2))))
+        })
+    }
+}
+
+/// Objects that can be moved by a transform, such as lights placed by a scene graph.
+pub trait Transformable {
+    /// A copy of `self` moved from its own space into the space of `transform`.
+    fn transformed(&self, transform: &Transform) -> Self;
+}
+
+impl Mul for Transform {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::with_inverse(self.matrix * rhs.matrix, rhs.inverse * self.inverse)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_PI_2;
+
+    fn assert_close(a: Vec3, b: Vec3) {
+        assert!((a - b).length() < 1e-5, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn test_inverse_round_trip() {
+        let transform = Transform::translation(Vec3::new(1.0, -2.0, 3.0))
+            * Transform::rotation(Vec3::new(1.0, 1.0, 0.0), 0.7)
+            * Transform::scaling(Vec3::new(2.0, 0.5, 3.0));
+        let p = Vec3::new(0.3, -1.2, 2.5);
+        assert_close(
+            transform
+                .inverse_matrix()
+                .transform_point(transform.point(p)),
+            p,
+        );
+        let v = Vec3::new(-0.4, 2.0, 1.1);
+        assert_close(
+            transform
+                .inverse_matrix()
+                .transform_vector(transform.vector(v)),
+            v,
+        );
+    }
+
+    #[test]
+    fn test_composition_order() {
+        let rotate = Transform::rotation(Vec3::new(0.0, 0.0, 1.0), FRAC_PI_2);
+        let translate = Transform::translation(Vec3::new(1.0, 0.0, 0.0));
+        let origin = Vec3::zero();
+
+        // Rotate first, then translate: the origin only moves by the translation
+        assert_close(
+            rotate.then(translate).point(origin),
+            Vec3::new(1.0, 0.0, 0.0),
+        );
+        // Translate first, then rotate a quarter turn about Z
+        assert_close(
+            translate.then(rotate).point(origin),
+            Vec3::new(0.0, 1.0, 0.0),
+        );
+        assert_eq!(rotate.then(translate), translate * rotate);
+    }
+
+    #[test]
+    fn test_points_vectors_and_normals() {
+        let transform = Transform::translation(Vec3::new(5.0, 0.0, 0.0))
+            * Transform::scaling(Vec3::new(2.0, 1.0, 1.0));
+        assert_close(
+            transform.point(Vec3::new(1.0, 1.0, 0.0)),
+            Vec3::new(7.0, 1.0, 0.0),
+        );
+        assert_close(
+            transform.vector(Vec3::new(1.0, 1.0, 0.0)),
+            Vec3::new(2.0, 1.0, 0.0),
+        );
+
+        // The normal of the plane x + y = 1 stays perpendicular to it after stretching x
+        let tangent = Vec3::new(1.0, -1.0, 0.0);
+        let normal = transform.normal(Vec3::new(1.0, 1.0, 0.0).normalize());
+        assert!(normal.dot(transform.vector(tangent)).abs() < 1e-5);
+        assert!((normal.length() - 1.0).abs() < 1e-5);
+
+        // The cached normal matrix follows composition
+        let undone = transform * Transform::scaling(Vec3::new(0.5, 1.0, 1.0));
+        assert_close(
+            undone.normal(Vec3::new(1.0, 1.0, 0.0)),
+            Vec3::new(1.0, 1.0, 0.0).normalize(),
+        );
+    }
+
+    #[test]
+    fn test_bounds() {
+        let unit = Aabb::new(Vec3::new(-1.0, -1.0, -1.0), Vec3::new(1.0, 1.0, 1.0));
+        let rotated = Transform::rotation(Vec3::new(0.0, 0.0, 1.0), FRAC_PI_2 / 2.0).bounds(unit);
+        let half_diagonal = 2.0_f32.sqrt();
+        assert!((rotated.max.x - half_diagonal).abs() < 1e-5);
+        assert!((rotated.max.z - 1.0).abs() < 1e-5);
+
+        let empty = Transform::translation(Vec3::new(1.0, 0.0, 0.0)).bounds(Aabb::empty());
+        assert_eq!(empty, Aabb::empty());
+    }
+}